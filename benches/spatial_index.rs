@@ -0,0 +1,136 @@
+//! Compares `SpatialIndex` queries against testing every shape. Run with
+//! `cargo bench --bench spatial_index`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use tinae::{geometry::TransformedShape, prelude::*};
+
+const ITERATIONS: u32 = 200;
+
+fn shapes(count: usize) -> Vec<(Entity, TransformedShape)> {
+    let shapes = [
+        Shape::Circle { radius: 8. },
+        Shape::Aabb {
+            size: Vec2::new(12., 4.),
+        },
+        Shape::Capsule {
+            height: 16.,
+            radius: 4.,
+        },
+    ];
+    (0..count)
+        .map(|i| {
+            let x = (i as f32 * 7919.).sin() * 2000.;
+            let y = (i as f32 * 104729.).cos() * 2000.;
+            let transform = Transform2::from_xy(x, y).with_rotation(i as f32);
+            (
+                Entity::from_raw(i as u32),
+                shapes[i % shapes.len()].transformed_by(transform),
+            )
+        })
+        .collect()
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report(name: &str, count: usize, indexed: Duration, brute_force: Duration) {
+    println!(
+        "{:<14} {:>6} shapes: index {:>10.2?}  brute force {:>10.2?}  ({:.1}x)",
+        name,
+        count,
+        indexed,
+        brute_force,
+        brute_force.as_secs_f64() / indexed.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+fn main() {
+    for count in [100, 1000, 10000] {
+        let shapes = shapes(count);
+        let mut spatial_index = SpatialIndex::new(32.);
+        for (entity, shape) in shapes.iter() {
+            spatial_index.insert(*entity, shape.clone());
+        }
+
+        let aabb = Aabb {
+            position: Vec2::new(100., -50.),
+            size: Vec2::splat(200.),
+        };
+        report(
+            "query_aabb",
+            count,
+            time(|| {
+                black_box(spatial_index.query_aabb(black_box(aabb)));
+            }),
+            time(|| {
+                black_box(
+                    shapes
+                        .iter()
+                        .filter(|(_, shape)| shape.colliding_with(&aabb))
+                        .collect::<Vec<_>>(),
+                );
+            }),
+        );
+
+        let circle = Circle {
+            position: Vec2::new(-300., 200.),
+            radius: 150.,
+        };
+        report(
+            "query_circle",
+            count,
+            time(|| {
+                black_box(spatial_index.query_circle(black_box(circle)));
+            }),
+            time(|| {
+                black_box(
+                    shapes
+                        .iter()
+                        .filter(|(_, shape)| shape.colliding_with(&circle))
+                        .collect::<Vec<_>>(),
+                );
+            }),
+        );
+
+        let point = Vec2::new(10., 10.);
+        report(
+            "query_point",
+            count,
+            time(|| {
+                black_box(spatial_index.query_point(black_box(point)));
+            }),
+            time(|| {
+                black_box(
+                    shapes
+                        .iter()
+                        .filter(|(_, shape)| shape.contains_point(point))
+                        .collect::<Vec<_>>(),
+                );
+            }),
+        );
+
+        let (entity, shape) = shapes[count / 2].clone();
+        let mut moved = shape.clone();
+        moved.transform.translation += Vec2::splat(40.);
+        let update = time(|| {
+            spatial_index.insert(entity, moved.clone());
+            spatial_index.insert(entity, shape.clone());
+        });
+        println!(
+            "{:<14} {:>6} shapes: index {:>10.2?}",
+            "move",
+            count,
+            update / 2
+        );
+    }
+}
@@ -53,6 +53,7 @@ fn setup(mut commands: Commands, mut load_events: EventWriter<AssetStructLoadEve
             },
             Transform2::new().with_scale(Vec2::new(200., 40.)),
             Depth::Exact(0.),
+            ScreenAnchor::new(ScreenAnchorPoint::Bottom).with_offset(Vec2::new(0., 60.)),
             LoadingBarBackground,
         ))
         .with_children(|parent| {
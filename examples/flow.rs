@@ -17,12 +17,10 @@ fn main() {
         .add_fixed_event::<PlayerSpawnEvent>()
         .add_fixed_event::<EnemySpawnEvent>()
         .add_startup_system(setup.in_set(EventSet::<PlayerSpawnEvent>::Sender))
-        .add_system(
+        .add_event_reader::<PlayerSpawnEvent, _>(
             player_spawn
-                .in_schedule(CoreSchedule::FixedUpdate)
                 .in_set(ExampleSystem::PlayerSpawn)
-                .in_base_set(FlowSet::EntitySpawn)
-                .before(EventSet::<PlayerSpawnEvent>::Sender),
+                .in_base_set(FlowSet::EntitySpawn),
         )
         .add_system(
             player_update
@@ -30,12 +28,10 @@ fn main() {
                 .in_set(ExampleSystem::PlayerUpdate)
                 .in_base_set(FlowSet::EntityUpdate),
         )
-        .add_system(
+        .add_event_reader::<EnemySpawnEvent, _>(
             enemy_spawn
-                .in_schedule(CoreSchedule::FixedUpdate)
                 .in_set(ExampleSystem::EnemySpawn)
-                .in_base_set(FlowSet::EntitySpawn)
-                .before(EventSet::<PlayerSpawnEvent>::Sender),
+                .in_base_set(FlowSet::EntitySpawn),
         )
         .add_system(
             enemy_update
@@ -43,12 +39,10 @@ fn main() {
                 .in_set(ExampleSystem::EnemyUpdate)
                 .in_base_set(FlowSet::EntityUpdate),
         )
-        .add_system(
+        .add_event_sender::<EnemySpawnEvent, _>(
             enemy_spawns
-                .in_schedule(CoreSchedule::FixedUpdate)
                 .in_set(ExampleSystem::EnemySpawns)
-                .in_base_set(FlowSet::MechanicUpdate)
-                .in_set(EventSet::<EnemySpawnEvent>::Sender),
+                .in_base_set(FlowSet::MechanicUpdate),
         )
         .run();
 }
@@ -1,28 +1,29 @@
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use bevy::prelude::*;
 use tinae::prelude::*;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(TinaePlugins)
+        .insert_resource(GeometryDebug {
+            toggle_key: Some(KeyCode::F1),
+            ..Default::default()
+        })
         .add_startup_system(setup)
-        .add_system(movement.in_schedule(CoreSchedule::FixedUpdate))
+        .add_system(
+            movement
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_base_set(FlowSet::EntityMovement)
+                .before(GeometrySystem::KinematicBody),
+        )
+        .add_system(log_collisions)
         .run();
 }
 
 #[derive(Component)]
 pub struct Movement;
 
-#[derive(Component)]
-pub struct Collidable {
-    shape: Shape,
-}
-
-fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
     commands.spawn(Camera2dBundle::default());
     commands.spawn((
         SpriteBundle {
@@ -35,11 +36,10 @@ fn setup(
         },
         Transform2::new(),
         Movement,
-        Collidable {
-            shape: Shape::Aabb {
-                size: Vec2::splat(50.),
-            },
-        },
+        KinematicBody::new(),
+        Collider::new(Shape::Aabb {
+            size: Vec2::splat(50.),
+        }),
     ));
     commands.spawn((
         SpriteBundle {
@@ -51,30 +51,24 @@ fn setup(
             ..Default::default()
         },
         Transform2::from_xy(100., -75.),
-        Collidable {
-            shape: Shape::Aabb {
-                size: Vec2::splat(50.),
-            },
-        },
+        Collider::new(Shape::Aabb {
+            size: Vec2::splat(50.),
+        }),
     ));
+    let circle = Shape::Circle { radius: 150. };
     commands.spawn((
-        MaterialMesh2dBundle {
-            mesh: meshes.add(Mesh::from(shape::Circle::default())).into(),
-            material: materials.add(ColorMaterial::from(Color::PURPLE)),
-            ..default()
-        },
-        Transform2::from_xy(-150., 125.).with_scale(Vec2::splat(150.)),
-        Collidable {
-            shape: Shape::Circle { radius: 150. },
-        },
+        ShapeMeshBundle::new(
+            circle.clone(),
+            materials.add(ColorMaterial::from(Color::PURPLE)),
+        ),
+        Transform2::from_xy(-150., 125.),
+        Collider::new(circle),
     ));
 }
 
 fn movement(
-    mut collidable_query: Query<(Entity, &mut Transform2, &Collidable)>,
-    movement_query: Query<Entity, With<Movement>>,
+    mut movement_query: Query<&mut KinematicBody, With<Movement>>,
     keys: Res<Input<KeyCode>>,
-    time: Res<FixedTime>,
 ) {
     let mut movement = Vec2::ZERO;
 
@@ -91,43 +85,19 @@ fn movement(
         movement.x += 1.;
     }
 
-    for movement_entity in movement_query.iter() {
-        let new_translation =
-            if let Some((collidable_entity, collidable_transform, collidable_collidable)) =
-                collidable_query.get(movement_entity).ok()
-            {
-                let new_translation = collidable_transform.translation
-                    + movement.normalize_or_zero() * time.period.as_secs_f32() * 300.;
-                let new_transformed_shape = collidable_collidable.shape.at(new_translation);
-                let mut can_move = true;
-                for (other_collidable_entity, other_collidable_transform, other_collidable) in
-                    collidable_query.iter()
-                {
-                    if other_collidable_entity != collidable_entity {
-                        if new_transformed_shape.colliding_with(
-                            &other_collidable
-                                .shape
-                                .at(other_collidable_transform.translation),
-                        ) {
-                            can_move = false;
-                            break;
-                        }
-                    }
-                }
-                if can_move {
-                    Some(new_translation)
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-        if let Some(new_translation) = new_translation {
-            if let Some((_, mut movement_transform, _)) =
-                collidable_query.get_mut(movement_entity).ok()
-            {
-                movement_transform.translation = new_translation;
-            }
-        }
+    for mut kinematic_body in movement_query.iter_mut() {
+        kinematic_body.velocity = movement.normalize_or_zero() * 300.;
+    }
+}
+
+fn log_collisions(
+    mut collision_started_events: EventReader<CollisionStarted>,
+    mut collision_ended_events: EventReader<CollisionEnded>,
+) {
+    for collision_started_event in collision_started_events.iter() {
+        info!("Collision started: {:?}", collision_started_event);
+    }
+    for collision_ended_event in collision_ended_events.iter() {
+        info!("Collision ended: {:?}", collision_ended_event);
     }
 }
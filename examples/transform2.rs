@@ -7,7 +7,11 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(TinaePlugins)
         .add_startup_system(setup)
-        .add_system(movement.in_schedule(CoreSchedule::FixedUpdate))
+        .add_system(
+            movement
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .before(MotionSystem::Integrate),
+        )
         .add_system(y_order)
         .run();
 }
@@ -39,19 +43,42 @@ pub struct YOrder;
 
 fn setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
+    let player = commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(50.)),
+                    color: Color::RED,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Transform2::new(),
+            Depth::from(DepthLayer::YOrder(0.)),
+            YOrder,
+            Movement,
+            Velocity2::default(),
+            Acceleration2::default(),
+            LinearDamping(10.),
+            MaxSpeed(300.),
+        ))
+        .id();
     commands.spawn((
         SpriteBundle {
             sprite: Sprite {
-                custom_size: Some(Vec2::splat(50.)),
-                color: Color::RED,
+                custom_size: Some(Vec2::splat(20.)),
+                color: Color::YELLOW,
                 ..Default::default()
             },
             ..Default::default()
         },
-        Transform2::new(),
-        Depth::from(DepthLayer::YOrder(0.)),
-        YOrder,
-        Movement,
+        Transform2::from_xy(0., 50.),
+        Depth::from(DepthLayer::Above),
+        Follow::new(player)
+            .with_offset(Transform2::from_xy(0., 50.))
+            .with_smoothing(0.1)
+            .with_max_distance(40.)
+            .with_mode(FollowMode::Position),
     ));
     commands.spawn((
         SpriteBundle {
@@ -93,9 +120,8 @@ fn setup(mut commands: Commands) {
 }
 
 fn movement(
-    mut movement_query: Query<&mut Transform2, With<Movement>>,
+    mut movement_query: Query<&mut Acceleration2, With<Movement>>,
     keys: Res<Input<KeyCode>>,
-    time: Res<FixedTime>,
 ) {
     let mut movement = Vec2::ZERO;
 
@@ -112,9 +138,8 @@ fn movement(
         movement.x += 1.;
     }
 
-    for mut movement_transform in movement_query.iter_mut() {
-        movement_transform.translation +=
-            movement.normalize_or_zero() * time.period.as_secs_f32() * 300.;
+    for mut acceleration in movement_query.iter_mut() {
+        acceleration.0 = movement.normalize_or_zero() * 5000.;
     }
 }
 
@@ -3,7 +3,9 @@ use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::parse::{Parse, ParseStream};
 use syn::token::Comma;
-use syn::{parenthesized, parse_macro_input, token, Data, DeriveInput, LitStr, Result};
+use syn::{
+    parenthesized, parse_macro_input, token, Data, DeriveInput, Error, Expr, LitStr, Result, Token,
+};
 
 use crate::world_dependencies::WorldDependencies;
 
@@ -41,20 +43,55 @@ impl Parse for SpineAssetAttribute {
     }
 }
 
+struct ImageColliderAttribute {
+    _paren_token: token::Paren,
+    literal: LitStr,
+    settings: Vec<(Ident, Expr)>,
+}
+
+impl Parse for ImageColliderAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let _paren_token = parenthesized!(content in input);
+        let literal = content.parse()?;
+        let mut settings = vec![];
+        while !content.is_empty() {
+            content.parse::<Comma>()?;
+            if content.is_empty() {
+                break;
+            }
+            let key: Ident = content.parse()?;
+            if !["alpha_threshold", "tolerance", "min_area"].contains(&key.to_string().as_str()) {
+                return Err(Error::new(key.span(), "unknown image collider setting"));
+            }
+            content.parse::<Token![=]>()?;
+            settings.push((key, content.parse()?));
+        }
+        Ok(ImageColliderAttribute {
+            _paren_token,
+            literal,
+            settings,
+        })
+    }
+}
+
 pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
     let mut load_quotes = vec![];
     let mut unload_quotes = vec![];
     let mut handles_quotes = vec![];
+    let mut on_loaded_quotes = vec![];
     let mut load_dependencies = WorldDependencies::new();
     let mut handles_dependencies = WorldDependencies::new();
+    let mut on_loaded_dependencies = WorldDependencies::new();
     match input.data {
         Data::Struct(asset_struct) => {
             for field in asset_struct.fields.iter() {
                 let field_ident = field.ident.clone().unwrap();
                 let mut asset_attribute = None;
                 let mut spine_asset_attribute = None;
+                let mut image_collider_attribute = None;
                 let mut found_asset = false;
                 for attr in field.attrs.iter() {
                     let Some(attr_path) = attr.path.get_ident().map(|ident| ident.to_string()) else { continue };
@@ -76,6 +113,15 @@ pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
                             spine_asset_attribute =
                                 Some(parse_macro_input!(tokens as SpineAssetAttribute));
                         }
+                        "image_collider" => {
+                            if found_asset {
+                                panic!("multiple asset attributes for {}", field_ident.to_string());
+                            }
+                            found_asset = true;
+                            let tokens: TokenStream = attr.tokens.clone().into();
+                            image_collider_attribute =
+                                Some(parse_macro_input!(tokens as ImageColliderAttribute));
+                        }
                         _ => unreachable!(),
                     }
                 }
@@ -149,6 +195,57 @@ pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
                             }
                         });
                     }
+                } else if let Some(image_collider_attribute) = image_collider_attribute {
+                    let path = image_collider_attribute.literal.value();
+
+                    // load()
+                    {
+                        let asset_server = load_dependencies.depend_on(quote! { Res<AssetServer> });
+                        load_quotes.push(quote! {
+                            self.#field_ident.image = #asset_server.load(#path);
+                        });
+                    }
+
+                    // unload()
+                    {
+                        unload_quotes.push(quote! {
+                            self.#field_ident = Default::default();
+                        });
+                    }
+
+                    // handles()
+                    {
+                        let sub_assets = handles_dependencies
+                            .depend_on(quote! { Res<tinae::sub_assets::SubAssets>});
+                        handles_quotes.push(quote! {
+                            handles.insert(self.#field_ident.image.id());
+                            for child in #sub_assets.children(self.#field_ident.image.id()).iter() {
+                                handles.insert(*child);
+                            }
+                        });
+                    }
+
+                    // on_loaded()
+                    {
+                        let images = on_loaded_dependencies
+                            .depend_on(quote! { Res<Assets<bevy::prelude::Image>> });
+                        let (keys, values): (Vec<Ident>, Vec<Expr>) =
+                            image_collider_attribute.settings.into_iter().unzip();
+                        on_loaded_quotes.push(quote! {
+                            {
+                                let image = #images
+                                    .get(&self.#field_ident.image)
+                                    .ok_or_else(|| format!("image {} is not loaded", #path))?;
+                                let settings = tinae::geometry::ImageShapeSettings {
+                                    #(#keys: #values,)*
+                                    ..Default::default()
+                                };
+                                self.#field_ident.shape =
+                                    tinae::geometry::Shape::from_image(image, &settings)
+                                        .map_err(|error| format!("collider of {}: {}", #path, error))?;
+                            }
+                        });
+                    }
                 }
             }
         }
@@ -156,6 +253,19 @@ pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
     }
     let load_dependencies = load_dependencies.tokens(Ident::new("world", Span::call_site()));
     let handles_dependencies = handles_dependencies.tokens(Ident::new("world", Span::call_site()));
+    let on_loaded = if on_loaded_quotes.is_empty() {
+        quote! {}
+    } else {
+        let on_loaded_dependencies =
+            on_loaded_dependencies.tokens(Ident::new("world", Span::call_site()));
+        quote! {
+            fn on_loaded(&mut self, world: &mut bevy::ecs::world::World) -> Result<(), String> {
+                #on_loaded_dependencies
+                #(#on_loaded_quotes)*
+                Ok(())
+            }
+        }
+    };
     let expanded = quote! {
         impl tinae::asset_struct::AssetStruct for #name {
             fn load(&mut self, world: &mut bevy::ecs::world::World) {
@@ -171,6 +281,7 @@ pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
                 #(#handles_quotes)*
                 handles.into_iter().collect()
             }
+            #on_loaded
         }
     };
     TokenStream::from(expanded)
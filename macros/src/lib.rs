@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(AssetStruct, attributes(asset, spine_asset))]
+#[proc_macro_derive(AssetStruct, attributes(asset, spine_asset, image_collider))]
 pub fn derive_asset_struct(input: TokenStream) -> TokenStream {
     asset_struct::derive_asset_struct(input)
 }
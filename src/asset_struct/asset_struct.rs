@@ -60,6 +60,12 @@ pub trait AssetStruct: Resource {
     /// of loading the assets.
     fn handles(&self, world: &mut World) -> Vec<HandleId>;
 
+    /// Called once every handle is loaded, before [`AssetStructLoadedEvent`] is sent, to build
+    /// values from the loaded assets. Returning an error fails loading instead.
+    fn on_loaded(&mut self, _world: &mut World) -> Result<(), String> {
+        Ok(())
+    }
+
     fn status(&self, world: &mut World) -> AssetStructStatus {
         let handles = self.handles(world);
         let mut system_state: SystemState<Res<AssetServer>> = SystemState::new(world);
@@ -199,14 +205,23 @@ fn asset_struct_update<T: AssetStruct>(world: &mut World) {
             let (handles_hash, status) = world.resource_scope(|world, asset_struct: Mut<T>| {
                 (asset_struct.handles_hash(world), asset_struct.status(world))
             });
-            let mut system_state: SystemState<(
-                EventWriter<AssetStructLoadedEvent<T>>,
-                EventWriter<AssetStructFailedEvent<T>>,
-            )> = SystemState::new(world);
-            let (mut asset_struct_loaded_events, mut asset_struct_failed_events) =
-                system_state.get_mut(world);
             if state.frame_delay.can_complete_loading(handles_hash) {
                 state.status = status;
+                if let AssetStructStatus::Loaded = state.status {
+                    let loaded = world.resource_scope(|world, mut asset_struct: Mut<T>| {
+                        asset_struct.on_loaded(world)
+                    });
+                    if let Err(error) = loaded {
+                        error!("{error}");
+                        state.status = AssetStructStatus::Failed;
+                    }
+                }
+                let mut system_state: SystemState<(
+                    EventWriter<AssetStructLoadedEvent<T>>,
+                    EventWriter<AssetStructFailedEvent<T>>,
+                )> = SystemState::new(world);
+                let (mut asset_struct_loaded_events, mut asset_struct_failed_events) =
+                    system_state.get_mut(world);
                 match state.status {
                     AssetStructStatus::Loaded => {
                         asset_struct_loaded_events.send(AssetStructLoadedEvent::<T> {
@@ -17,6 +17,7 @@ impl Plugin for CursorPlugin {
     }
 }
 
+/// World position of the mouse cursor, or of the first touch while the screen is touched.
 #[derive(Default, Resource, Debug)]
 pub struct Cursor {
     pub position: Vec2,
@@ -26,9 +27,14 @@ fn cursor_position(
     mut cursor: ResMut<Cursor>,
     window_query: Query<&Window>,
     camera: Query<(&Camera, &GlobalTransform)>,
+    touches: Option<Res<Touches>>,
 ) {
     if let Some(window) = window_query.get_single().ok() {
-        if let Some(position) = window.cursor_position() {
+        // Touch positions start from the top of the window, unlike the mouse cursor.
+        let touch_position = touches
+            .and_then(|touches| touches.iter().next().map(|touch| touch.position()))
+            .map(|position| Vec2::new(position.x, window.height() - position.y));
+        if let Some(position) = touch_position.or_else(|| window.cursor_position()) {
             if let Ok((camera, camera_transform)) = camera.get_single() {
                 let window_size = Vec2::new(window.width() as f32, window.height() as f32);
                 let ndc = (position / window_size) * 2.0 - Vec2::ONE;
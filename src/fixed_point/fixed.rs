@@ -0,0 +1,434 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use bevy::prelude::*;
+
+const FRAC_BITS: u32 = 16;
+
+/// Signed fixed-point number with 16 integer and 16 fractional bits.
+///
+/// Every operation is done on integers with wrapping arithmetic, so results are bit-for-bit the
+/// same on every platform and optimization level, unlike `f32` whose results can change with the
+/// compiler's choice of instructions. Use it for simulations that must stay in sync, such as
+/// lockstep multiplayer or replays, and convert to `f32` with [`Fixed::to_f32`] for rendering.
+///
+/// Multiplication and division round toward negative infinity and zero respectively, and division
+/// by zero saturates to [`Fixed::MAX`] or [`Fixed::MIN`] instead of panicking.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << FRAC_BITS);
+    pub const HALF: Self = Self(1 << (FRAC_BITS - 1));
+    pub const MIN: Self = Self(i32::MIN);
+    pub const MAX: Self = Self(i32::MAX);
+    /// Smallest positive value, `1 / 65536`.
+    pub const EPSILON: Self = Self(1);
+    pub const PI: Self = Self(205887);
+    pub const TAU: Self = Self(411775);
+    pub const FRAC_PI_2: Self = Self(102944);
+
+    /// Creates a number from its raw bits, where `1 << 16` is one.
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i32) -> Self {
+        Self(value.wrapping_shl(FRAC_BITS))
+    }
+
+    /// Rounds `value` to the nearest fixed-point number, saturating outside of the range.
+    pub fn from_f32(value: f32) -> Self {
+        Self((value * (1 << FRAC_BITS) as f32).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / (1 << FRAC_BITS) as f32
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self::from_int(self.0.signum())
+    }
+
+    pub fn floor(self) -> Self {
+        Self(self.0 & !((1 << FRAC_BITS) - 1))
+    }
+
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Square root, or zero for negative numbers.
+    pub fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(isqrt((self.0 as u128) << FRAC_BITS) as i32)
+    }
+
+    /// Sine of an angle in radians, from a polynomial accurate to about `1e-4`.
+    pub fn sin(self) -> Self {
+        // Wraps to [-PI, PI), then folds to [-PI / 2, PI / 2] where the series converges quickly.
+        let wrapped = (self.0 as i64 + Self::PI.0 as i64).rem_euclid(Self::TAU.0 as i64);
+        let mut angle = Self(wrapped as i32 - Self::PI.0);
+        if angle > Self::FRAC_PI_2 {
+            angle = Self::PI - angle;
+        } else if angle < -Self::FRAC_PI_2 {
+            angle = -Self::PI - angle;
+        }
+        let squared = angle * angle;
+        let mut series = Self::ONE;
+        for divisor in [72, 42, 20, 6] {
+            series = Self::ONE - squared * series / Self::from_int(divisor);
+        }
+        angle * series
+    }
+
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+}
+
+/// Square root rounded down, computed one bit at a time with integer operations only, so the
+/// result never depends on the standard library or floating point.
+fn isqrt(value: u128) -> u128 {
+    if value == 0 {
+        return 0;
+    }
+    // Highest power of four not above `value`.
+    let mut bit = 1 << ((127 - value.leading_zeros()) & !1);
+    let mut remainder = value;
+    let mut root = 0;
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+impl From<i32> for Fixed {
+    fn from(value: i32) -> Self {
+        Self::from_int(value)
+    }
+}
+
+impl From<Fixed> for f32 {
+    fn from(value: Fixed) -> Self {
+        value.to_f32()
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as i64 * rhs.0 as i64) >> FRAC_BITS) as i32)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return if self.0 < 0 { Self::MIN } else { Self::MAX };
+        }
+        Self((((self.0 as i64) << FRAC_BITS) / rhs.0 as i64) as i32)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+/// Two-dimensional vector of [`Fixed`] numbers, the deterministic counterpart of [`Vec2`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub const ZERO: Self = Self::splat(Fixed::ZERO);
+    pub const ONE: Self = Self::splat(Fixed::ONE);
+    pub const X: Self = Self::new(Fixed::ONE, Fixed::ZERO);
+    pub const Y: Self = Self::new(Fixed::ZERO, Fixed::ONE);
+
+    pub const fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn splat(value: Fixed) -> Self {
+        Self::new(value, value)
+    }
+
+    pub const fn from_ints(x: i32, y: i32) -> Self {
+        Self::new(Fixed::from_int(x), Fixed::from_int(y))
+    }
+
+    pub fn from_vec2(value: Vec2) -> Self {
+        Self::new(Fixed::from_f32(value.x), Fixed::from_f32(value.y))
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    /// Unit vector pointing at `angle` radians, like [`Vec2::from_angle`].
+    pub fn from_angle(angle: Fixed) -> Self {
+        Self::new(angle.cos(), angle.sin())
+    }
+
+    /// Rotates `other` by the angle of `self`, which should be a unit vector.
+    pub fn rotate(self, other: Self) -> Self {
+        Self::new(
+            self.x * other.x - self.y * other.y,
+            self.y * other.x + self.x * other.y,
+        )
+    }
+
+    pub fn perp(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    pub fn dot(self, other: Self) -> Fixed {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length_squared(self) -> Fixed {
+        self.dot(self)
+    }
+
+    /// Length computed on the raw bits, so it doesn't overflow even when the squared length
+    /// does.
+    pub fn length(self) -> Fixed {
+        Fixed::from_bits(isqrt(self.wide_length_squared()).min(i32::MAX as u128) as i32)
+    }
+
+    pub fn distance(self, other: Self) -> Fixed {
+        (self - other).length()
+    }
+
+    /// Scales the vector to a length of one, or returns zero for a zero vector.
+    pub fn normalize_or_zero(self) -> Self {
+        let length = self.length();
+        if length == Fixed::ZERO {
+            return Self::ZERO;
+        }
+        self / length
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Squared length of the raw bits, exact for any vector.
+    pub(crate) fn wide_length_squared(self) -> u128 {
+        let x = self.x.to_bits() as i128;
+        let y = self.y.to_bits() as i128;
+        (x * x + y * y) as u128
+    }
+}
+
+impl From<Vec2> for FixedVec2 {
+    fn from(value: Vec2) -> Self {
+        Self::from_vec2(value)
+    }
+}
+
+impl From<FixedVec2> for Vec2 {
+    fn from(value: FixedVec2) -> Self {
+        value.to_vec2()
+    }
+}
+
+impl Add for FixedVec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for FixedVec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul for FixedVec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.x * rhs.x, self.y * rhs.y)
+    }
+}
+
+impl Mul<Fixed> for FixedVec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Fixed) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<Fixed> for FixedVec2 {
+    type Output = Self;
+
+    fn div(self, rhs: Fixed) -> Self {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl Neg for FixedVec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for FixedVec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for FixedVec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<Fixed> for FixedVec2 {
+    fn mul_assign(&mut self, rhs: Fixed) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use super::{isqrt, Fixed, FixedVec2};
+
+    #[test]
+    fn fixed_arithmetic() {
+        let a = Fixed::from_f32(2.5);
+        let b = Fixed::from_int(-4);
+        assert_eq!((a + b).to_f32(), -1.5);
+        assert_eq!((a * b).to_f32(), -10.);
+        assert_eq!((b / a).to_f32(), -1.5999908);
+        assert_eq!(Fixed::ONE / Fixed::ZERO, Fixed::MAX);
+        assert_eq!(-Fixed::ONE / Fixed::ZERO, Fixed::MIN);
+        assert_eq!(Fixed::MAX + Fixed::EPSILON, Fixed::MIN);
+        assert_eq!(Fixed::from_int(9).sqrt(), Fixed::from_int(3));
+        assert_eq!(Fixed::from_f32(-1.25).floor(), Fixed::from_int(-2));
+        assert_eq!(FixedVec2::from_ints(3, 4).length(), Fixed::from_int(5));
+        assert_eq!(
+            Vec2::from(FixedVec2::from(Vec2::new(0.5, -0.25))),
+            Vec2::new(0.5, -0.25)
+        );
+    }
+
+    #[test]
+    fn integer_square_root() {
+        let values = (0..1000_u128)
+            .chain((0..128).map(|shift| 1 << shift))
+            .chain((1..128).map(|shift| (1 << shift) - 1))
+            .chain([u64::MAX as u128 * u64::MAX as u128, u128::MAX]);
+        for value in values {
+            let root = isqrt(value);
+            assert!(root * root <= value, "{value}");
+            let next = (root + 1).checked_mul(root + 1);
+            assert!(next.is_none() || next > Some(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn fixed_trigonometry() {
+        for step in -40..=40 {
+            let angle = step as f32 * 0.2;
+            let fixed = Fixed::from_f32(angle);
+            assert!(
+                (fixed.sin().to_f32() - angle.sin()).abs() < 0.001,
+                "{angle}"
+            );
+            assert!(
+                (fixed.cos().to_f32() - angle.cos()).abs() < 0.001,
+                "{angle}"
+            );
+        }
+    }
+}
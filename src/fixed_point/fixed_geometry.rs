@@ -0,0 +1,292 @@
+use crate::geometry::{Aabb, Circle, CollidingWith};
+
+use super::{Fixed, FixedVec2};
+
+/// Deterministic counterpart of [`Circle`]. Like [`Circle`], `radius` is twice the actual radius.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedCircle {
+    pub position: FixedVec2,
+    pub radius: Fixed,
+}
+
+/// Deterministic counterpart of [`Aabb`], centered on `position`.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FixedAabb {
+    pub position: FixedVec2,
+    pub size: FixedVec2,
+}
+
+impl FixedAabb {
+    pub fn min(&self) -> FixedVec2 {
+        self.position - self.size * Fixed::HALF
+    }
+
+    pub fn max(&self) -> FixedVec2 {
+        self.position + self.size * Fixed::HALF
+    }
+
+    pub fn from_min_max(min: FixedVec2, max: FixedVec2) -> Self {
+        Self {
+            position: (min + max) * Fixed::HALF,
+            size: max - min,
+        }
+    }
+
+    /// Edges in doubled coordinates, which keeps halving the size exact.
+    fn doubled_bounds(&self) -> [[i64; 2]; 2] {
+        [
+            [self.position.x, self.size.x],
+            [self.position.y, self.size.y],
+        ]
+        .map(|[position, size]| {
+            let position = position.to_bits() as i64 * 2;
+            let half = size.to_bits() as i64;
+            [position - half, position + half]
+        })
+    }
+}
+
+impl From<Circle> for FixedCircle {
+    fn from(circle: Circle) -> Self {
+        Self {
+            position: circle.position.into(),
+            radius: Fixed::from_f32(circle.radius),
+        }
+    }
+}
+
+impl From<FixedCircle> for Circle {
+    fn from(circle: FixedCircle) -> Self {
+        Self {
+            position: circle.position.into(),
+            radius: circle.radius.to_f32(),
+        }
+    }
+}
+
+impl From<Aabb> for FixedAabb {
+    fn from(aabb: Aabb) -> Self {
+        Self {
+            position: aabb.position.into(),
+            size: aabb.size.into(),
+        }
+    }
+}
+
+impl From<FixedAabb> for Aabb {
+    fn from(aabb: FixedAabb) -> Self {
+        Self {
+            position: aabb.position.into(),
+            size: aabb.size.into(),
+        }
+    }
+}
+
+/// Deterministic counterpart of [`ContainsPoint`](crate::geometry::ContainsPoint), with the same
+/// edge rules.
+pub trait FixedContainsPoint {
+    fn contains_point(&self, point: FixedVec2) -> bool;
+}
+
+impl FixedContainsPoint for FixedCircle {
+    fn contains_point(&self, point: FixedVec2) -> bool {
+        let radius = self.radius.to_bits() as i128;
+        (self.position - point).wide_length_squared() as i128 * 4 < radius * radius
+    }
+}
+
+impl FixedContainsPoint for FixedAabb {
+    fn contains_point(&self, point: FixedVec2) -> bool {
+        let [[min_x, max_x], [min_y, max_y]] = self.doubled_bounds();
+        let x = point.x.to_bits() as i64 * 2;
+        let y = point.y.to_bits() as i64 * 2;
+        x > min_x && x < max_x && y > min_y && y < max_y
+    }
+}
+
+impl CollidingWith<FixedCircle> for FixedCircle {
+    fn colliding_with(&self, other: &FixedCircle) -> bool {
+        let radius = self.radius.to_bits() as i128 + other.radius.to_bits() as i128;
+        (self.position - other.position).wide_length_squared() as i128 * 4 < radius * radius
+    }
+}
+
+impl CollidingWith<FixedAabb> for FixedCircle {
+    fn colliding_with(&self, other: &FixedAabb) -> bool {
+        if other.contains_point(self.position) {
+            return true;
+        }
+        let [bounds_x, bounds_y] = other.doubled_bounds();
+        let outside = |position: Fixed, [min, max]: [i64; 2]| {
+            let position = position.to_bits() as i64 * 2;
+            (position - position.clamp(min, max)) as i128
+        };
+        let x = outside(self.position.x, bounds_x);
+        let y = outside(self.position.y, bounds_y);
+        let radius = self.radius.to_bits() as i128;
+        x * x + y * y < radius * radius
+    }
+}
+
+impl CollidingWith<FixedCircle> for FixedAabb {
+    fn colliding_with(&self, other: &FixedCircle) -> bool {
+        other.colliding_with(self)
+    }
+}
+
+impl CollidingWith<FixedAabb> for FixedAabb {
+    fn colliding_with(&self, other: &FixedAabb) -> bool {
+        let [[a_min_x, a_max_x], [a_min_y, a_max_y]] = self.doubled_bounds();
+        let [[b_min_x, b_max_x], [b_min_y, b_max_y]] = other.doubled_bounds();
+        a_min_x <= b_max_x && a_max_x >= b_min_x && a_min_y <= b_max_y && a_max_y >= b_min_y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{fixed_point::prelude::*, geometry::prelude::*};
+
+    #[test]
+    fn fixed_shapes_match_f32_shapes() {
+        let circle = Circle {
+            position: Vec2::new(0.5, 0.25),
+            radius: 1.,
+        };
+        let aabb = Aabb {
+            position: Vec2::new(1.5, 0.),
+            size: Vec2::new(1., 2.),
+        };
+        let shapes = [
+            (circle, aabb),
+            (
+                Circle {
+                    radius: 0.96875,
+                    ..circle
+                },
+                aabb,
+            ),
+            (
+                circle,
+                Aabb {
+                    position: Vec2::new(1.625, 1.375),
+                    ..aabb
+                },
+            ),
+            (
+                Circle {
+                    position: Vec2::new(1.5, 0.5),
+                    ..circle
+                },
+                aabb,
+            ),
+        ];
+        for (circle, aabb) in shapes {
+            let fixed_circle = FixedCircle::from(circle);
+            let fixed_aabb = FixedAabb::from(aabb);
+            assert!(Circle::from(fixed_circle) == circle);
+            assert_eq!(Aabb::from(fixed_aabb), aabb);
+            assert_eq!(
+                fixed_circle.colliding_with(&fixed_aabb),
+                circle.colliding_with(&aabb),
+                "{aabb:?}"
+            );
+            let bounds = Aabb {
+                position: circle.position,
+                size: Vec2::splat(circle.radius),
+            };
+            assert_eq!(
+                fixed_aabb.colliding_with(&FixedAabb::from(bounds)),
+                aabb.colliding_with(&bounds)
+            );
+            for point in [
+                Vec2::new(1.25, 0.375),
+                Vec2::new(0.9, 0.),
+                Vec2::new(2., 1.),
+            ] {
+                let fixed_point = FixedVec2::from(point);
+                assert_eq!(
+                    fixed_circle.contains_point(fixed_point),
+                    circle.contains_point(point)
+                );
+                assert_eq!(
+                    fixed_aabb.contains_point(fixed_point),
+                    aabb.contains_point(point)
+                );
+            }
+        }
+        let other = FixedCircle {
+            position: FixedVec2::from_ints(2, 0),
+            radius: Fixed::from_int(2),
+        };
+        assert!(!FixedCircle::from(circle).colliding_with(&other));
+        assert!(!other.colliding_with(&FixedCircle::from(circle)));
+        assert!(FixedCircle {
+            radius: Fixed::from_int(3),
+            ..FixedCircle::from(circle)
+        }
+        .colliding_with(&other));
+    }
+
+    /// Bounces circles around an arena and off each other. The checksum is hard-coded, so running
+    /// this in both debug and release (`cargo test --release`) checks that the results are
+    /// identical at every optimization level.
+    #[test]
+    fn deterministic_simulation() {
+        let arena = FixedAabb {
+            position: FixedVec2::ZERO,
+            size: FixedVec2::from_ints(20, 20),
+        };
+        let delta = Fixed::from_f32(1. / 60.);
+        let mut bodies = (0..16)
+            .map(|index| {
+                let angle = Fixed::from_int(index) * Fixed::from_f32(0.7);
+                let position = FixedVec2::from_angle(angle) * Fixed::from_int(2 + index % 5);
+                let velocity = FixedVec2::from_angle(angle * Fixed::from_int(3))
+                    * Fixed::from_f32(3.5 + index as f32 * 0.25);
+                let circle = FixedCircle {
+                    position,
+                    radius: Fixed::from_f32(0.6 + (index % 3) as f32 * 0.3),
+                };
+                (circle, velocity)
+            })
+            .collect::<Vec<_>>();
+        let mut collisions = 0_u32;
+        for _ in 0..600 {
+            for (circle, velocity) in bodies.iter_mut() {
+                circle.position += *velocity * delta;
+                let half = circle.radius * Fixed::HALF;
+                let (min, max) = (arena.min(), arena.max());
+                if circle.position.x - half < min.x || circle.position.x + half > max.x {
+                    velocity.x = -velocity.x;
+                }
+                if circle.position.y - half < min.y || circle.position.y + half > max.y {
+                    velocity.y = -velocity.y;
+                }
+            }
+            for a in 0..bodies.len() {
+                for b in a + 1..bodies.len() {
+                    if !bodies[a].0.colliding_with(&bodies[b].0) {
+                        continue;
+                    }
+                    collisions += 1;
+                    let normal = (bodies[b].0.position - bodies[a].0.position).normalize_or_zero();
+                    let relative = (bodies[b].1 - bodies[a].1).dot(normal);
+                    if relative < Fixed::ZERO {
+                        bodies[a].1 += normal * relative;
+                        bodies[b].1 -= normal * relative;
+                    }
+                }
+            }
+        }
+        let checksum = bodies.iter().fold(0_u64, |hash, (circle, velocity)| {
+            [circle.position.x, circle.position.y, velocity.x, velocity.y]
+                .into_iter()
+                .fold(hash, |hash, value| {
+                    hash.wrapping_mul(0x100000001b3) ^ value.to_bits() as u32 as u64
+                })
+        });
+        assert_eq!((collisions, checksum), (52, 0x8f8b_f6b5_7a19_10d7));
+    }
+}
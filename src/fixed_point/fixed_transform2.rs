@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+
+use crate::{
+    fixed_timestep::CoreFixedSet,
+    flow::FlowSet,
+    transform2::{Transform2, Transform2System},
+};
+
+use super::{Fixed, FixedVec2};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum FixedPointSystem {
+    Integrate,
+    Sync,
+}
+
+pub struct FixedTransform2Plugin;
+
+impl Plugin for FixedTransform2Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            fixed_velocity_integrate
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(FixedPointSystem::Integrate)
+                .in_base_set(FlowSet::EntityMovement),
+        )
+        .add_system(
+            fixed_transform2_sync
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(FixedPointSystem::Sync)
+                .in_base_set(CoreFixedSet::PostUpdate)
+                .before(Transform2System::TransformVisualPropagate),
+        );
+    }
+}
+
+/// Deterministic counterpart of [`Transform2`]. The entity's [`Transform2`] is overwritten with
+/// this one at the end of every fixed update, so rendering and other `f32` code keep working.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedTransform2 {
+    pub translation: FixedVec2,
+    pub rotation: Fixed,
+    pub scale: FixedVec2,
+}
+
+impl Default for FixedTransform2 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl FixedTransform2 {
+    pub const IDENTITY: Self = Self {
+        translation: FixedVec2::ZERO,
+        rotation: Fixed::ZERO,
+        scale: FixedVec2::ONE,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_translation(translation: FixedVec2) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn with_translation(self, translation: FixedVec2) -> Self {
+        Self {
+            translation,
+            ..self
+        }
+    }
+
+    pub fn with_rotation(self, rotation: Fixed) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn with_scale(self, scale: FixedVec2) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Same as [`Transform2::mul_transform`].
+    pub fn mul_transform(&self, transform: FixedTransform2) -> Self {
+        Self {
+            translation: self.transform_point(transform.translation),
+            rotation: self.rotation + transform.rotation,
+            scale: self.scale * transform.scale,
+        }
+    }
+
+    pub fn transform_point(&self, point: FixedVec2) -> FixedVec2 {
+        self.translation + self.transform_vector(point)
+    }
+
+    /// Transforms a vector, applying scale and rotation but not translation.
+    pub fn transform_vector(&self, vector: FixedVec2) -> FixedVec2 {
+        FixedVec2::from_angle(self.rotation).rotate(vector * self.scale)
+    }
+}
+
+impl From<Transform2> for FixedTransform2 {
+    fn from(transform: Transform2) -> Self {
+        Self {
+            translation: transform.translation.into(),
+            rotation: Fixed::from_f32(transform.rotation),
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+impl From<FixedTransform2> for Transform2 {
+    fn from(transform: FixedTransform2) -> Self {
+        Self {
+            translation: transform.translation.into(),
+            rotation: transform.rotation.to_f32(),
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+/// Linear velocity in units per second, moving the entity's [`FixedTransform2`] every fixed
+/// update.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FixedVelocity2(pub FixedVec2);
+
+fn fixed_velocity_integrate(
+    mut query: Query<(&mut FixedTransform2, &FixedVelocity2)>,
+    time: Res<FixedTime>,
+) {
+    let delta_seconds = Fixed::from_f32(time.period.as_secs_f32());
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.translation += velocity.0 * delta_seconds;
+    }
+}
+
+fn fixed_transform2_sync(
+    mut query: Query<(&FixedTransform2, &mut Transform2), Changed<FixedTransform2>>,
+) {
+    for (fixed_transform, mut transform) in query.iter_mut() {
+        *transform = (*fixed_transform).into();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    use crate::{fixed_point::prelude::*, transform2::Transform2};
+
+    use super::{fixed_transform2_sync, fixed_velocity_integrate};
+
+    #[test]
+    fn velocity_moves_and_syncs_transform() {
+        let mut app = App::new();
+        app.insert_resource(FixedTime::new(Duration::from_secs_f32(0.25)))
+            .add_systems((fixed_velocity_integrate, fixed_transform2_sync).chain());
+        let entity = app
+            .world
+            .spawn((
+                FixedTransform2::from_translation(FixedVec2::from_ints(1, 0))
+                    .with_rotation(Fixed::FRAC_PI_2),
+                FixedVelocity2(FixedVec2::from_ints(2, -4)),
+                Transform2::default(),
+            ))
+            .id();
+        app.update();
+        app.update();
+        let fixed = app.world.get::<FixedTransform2>(entity).unwrap();
+        assert_eq!(fixed.translation, FixedVec2::from_ints(2, -2));
+        let transform = app.world.get::<Transform2>(entity).unwrap();
+        assert_eq!(transform.translation, Vec2::new(2., -2.));
+        assert!((transform.rotation - std::f32::consts::FRAC_PI_2).abs() < 0.0001);
+        let point = fixed.transform_point(FixedVec2::X).to_vec2();
+        assert!(point.abs_diff_eq(Vec2::new(2., -1.), 0.001), "{point}");
+    }
+}
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+pub struct FixedPointPlugin;
+
+impl Plugin for FixedPointPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(FixedTransform2Plugin);
+    }
+}
+
+mod fixed;
+mod fixed_geometry;
+mod fixed_transform2;
+
+pub use fixed::*;
+pub use fixed_geometry::*;
+pub use fixed_transform2::*;
+
+pub mod prelude {
+    pub use super::{
+        Fixed, FixedAabb, FixedCircle, FixedContainsPoint, FixedPointSystem, FixedTransform2,
+        FixedVec2, FixedVelocity2,
+    };
+}
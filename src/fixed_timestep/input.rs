@@ -16,6 +16,7 @@ impl AddFixedInput for App {
         self.init_resource::<FixedInput<T>>()
             .add_system(
                 update_fixed_input::<T>
+                    .in_set(FixedInputSystem)
                     .in_base_set(CoreSet::PreUpdate)
                     .after(InputSystem),
             )
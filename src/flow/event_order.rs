@@ -0,0 +1,186 @@
+use std::any::{type_name, TypeId};
+
+use bevy::{
+    ecs::{component::Components, schedule::ScheduleGraph},
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
+
+use super::{
+    system_order::{build_graph, SystemOrder},
+    EventSet,
+};
+
+/// Adds systems sending or reading events of type `T` to the fixed update schedule, in
+/// [`EventSet::Sender`] or [`EventSet::Reader`]. Readers are ordered after every sender, so events
+/// are read in the tick they're sent.
+///
+/// A reader can't be in an earlier [`FlowSet`](super::FlowSet) than a sender, since the schedule
+/// would then fail to build with a dependency cycle.
+pub trait AddEventSystem {
+    fn add_event_sender<T: Event, M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self;
+
+    fn add_event_reader<T: Event, M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self;
+}
+
+impl AddEventSystem for App {
+    fn add_event_sender<T: Event, M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        add_event_system::<T, _>(self, system.in_set(EventSet::<T>::Sender))
+    }
+
+    fn add_event_reader<T: Event, M>(&mut self, system: impl IntoSystemConfig<M>) -> &mut Self {
+        add_event_system::<T, _>(self, system.in_set(EventSet::<T>::Reader))
+    }
+}
+
+fn add_event_system<T: Event, M>(app: &mut App, system: impl IntoSystemConfig<M>) -> &mut App {
+    app.world
+        .get_resource_or_insert_with(EventOrderChecks::default)
+        .events
+        .insert(TypeId::of::<Events<T>>(), get_short_name(type_name::<T>()));
+    app.get_schedule_mut(CoreSchedule::FixedUpdate)
+        .unwrap()
+        .configure_set(EventSet::<T>::Reader.after(EventSet::<T>::Sender));
+    app.add_system(system.in_schedule(CoreSchedule::FixedUpdate))
+}
+
+/// Event types added with [`AddEventSystem`], whose readers are checked on startup.
+#[derive(Resource, Default)]
+pub(crate) struct EventOrderChecks {
+    events: HashMap<TypeId, String>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct UnorderedEventReader {
+    event: String,
+    reader: String,
+    sender: String,
+}
+
+/// Warns about fixed update systems reading checked events that can run before one of their
+/// senders, which makes them see the events a tick late or not at all.
+pub(crate) fn validate_event_order(world: &mut World) {
+    for unordered in unordered_event_readers(world) {
+        warn!(
+            "{} reads {} events but can run before {}, which sends them. Add it with \
+            `add_event_reader` or order it after `EventSet::<{}>::Sender`.",
+            unordered.reader, unordered.event, unordered.sender, unordered.event
+        );
+    }
+}
+
+fn unordered_event_readers(world: &mut World) -> Vec<UnorderedEventReader> {
+    world.resource_scope(|world, mut schedules: Mut<Schedules>| {
+        let Some(schedule) = schedules.get_mut(&CoreSchedule::FixedUpdate) else {
+            return Vec::new();
+        };
+        if !build_graph(schedule, world) {
+            return Vec::new();
+        }
+        let Some(checks) = world.get_resource::<EventOrderChecks>() else {
+            return Vec::new();
+        };
+        find_unordered_event_readers(schedule.graph(), world.components(), checks)
+    })
+}
+
+fn find_unordered_event_readers(
+    graph: &ScheduleGraph,
+    components: &Components,
+    checks: &EventOrderChecks,
+) -> Vec<UnorderedEventReader> {
+    let order = SystemOrder::new(graph);
+    let mut unordered = Vec::new();
+    for (type_id, event) in checks.events.iter() {
+        let Some(events_id) = components.get_resource_id(*type_id) else {
+            continue;
+        };
+        let (senders, readers): (Vec<_>, Vec<_>) = graph
+            .systems()
+            .filter(|(_, system, ..)| {
+                let access = system.component_access();
+                access.has_read(events_id) && !access.has_read_all()
+            })
+            .partition(|(_, system, ..)| system.component_access().has_write(events_id));
+        for (reader, reader_system, ..) in readers.iter() {
+            for (sender, sender_system, ..) in senders.iter() {
+                if !order.runs_before(*sender, *reader) {
+                    unordered.push(UnorderedEventReader {
+                        event: event.clone(),
+                        reader: get_short_name(&reader_system.name()),
+                        sender: get_short_name(&sender_system.name()),
+                    });
+                }
+            }
+        }
+    }
+    unordered.sort();
+    unordered
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::flow::{prelude::*, FlowPlugin};
+
+    use super::{unordered_event_readers, UnorderedEventReader};
+
+    struct Ping;
+
+    fn send(mut pings: EventWriter<Ping>) {
+        pings.send(Ping);
+    }
+
+    fn read(mut pings: EventReader<Ping>) {
+        for _ in pings.iter() {}
+    }
+
+    fn read_later(mut pings: EventReader<Ping>) {
+        for _ in pings.iter() {}
+    }
+
+    fn read_unordered(mut pings: EventReader<Ping>) {
+        for _ in pings.iter() {}
+    }
+
+    fn read_before(mut pings: EventReader<Ping>) {
+        for _ in pings.iter() {}
+    }
+
+    #[test]
+    fn event_readers_run_after_senders() {
+        let mut app = App::new();
+        app.add_plugin(FlowPlugin)
+            .add_event::<Ping>()
+            .add_event_sender::<Ping, _>(send.in_base_set(FlowSet::EntityUpdate))
+            .add_event_reader::<Ping, _>(read.in_base_set(FlowSet::EntityUpdate))
+            .add_system(
+                read_later
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntitySpawn),
+            )
+            .add_system(
+                read_unordered
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::MechanicUpdate),
+            )
+            .add_system(
+                read_before
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntityUpdate)
+                    .before(EventSet::<Ping>::Sender),
+            );
+        let unordered = |reader: &str| UnorderedEventReader {
+            event: "Ping".to_string(),
+            reader: reader.to_string(),
+            sender: "send".to_string(),
+        };
+        assert_eq!(
+            unordered_event_readers(&mut app.world),
+            vec![unordered("read_before"), unordered("read_unordered")]
+        );
+        app.world.run_schedule(CoreSchedule::FixedUpdate);
+        assert_eq!(unordered_event_readers(&mut app.world), vec![]);
+    }
+}
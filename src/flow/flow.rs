@@ -8,6 +8,8 @@ use bevy::prelude::*;
 
 use crate::fixed_timestep::CoreFixedSet;
 
+use super::{validate_event_order, EventOrderChecks};
+
 pub struct FlowPlugin;
 
 impl Plugin for FlowPlugin {
@@ -42,7 +44,9 @@ impl Plugin for FlowPlugin {
             FlowSet::Debug
                 .after(CoreSet::PostUpdate)
                 .before(CoreSet::PostUpdateFlush),
-        );
+        )
+        .init_resource::<EventOrderChecks>()
+        .add_startup_system(validate_event_order);
     }
 }
 
@@ -58,16 +62,24 @@ pub enum FlowSet {
     Debug,
 }
 
+/// Orders the systems sending and reading events of type `T`. Systems in [`EventSet::Reader`]
+/// run after the ones in [`EventSet::Sender`] when both are added with
+/// [`AddEventSystem`](super::AddEventSystem).
 #[derive(Copy, SystemSet)]
 pub enum EventSet<T: Send + Sync + 'static> {
     Sender,
+    Reader,
     #[system_set(ignore_field)]
     _Data(PhantomData<T>),
 }
 
 impl<T: Send + Sync + 'static> Clone for EventSet<T> {
     fn clone(&self) -> Self {
-        Self::Sender
+        match self {
+            Self::Sender => Self::Sender,
+            Self::Reader => Self::Reader,
+            Self::_Data(..) => unreachable!(),
+        }
     }
 }
 
@@ -77,6 +89,9 @@ impl<T: Send + Sync + 'static> Debug for EventSet<T> {
             Self::Sender => {
                 f.write_str("Sender")?;
             }
+            Self::Reader => {
+                f.write_str("Reader")?;
+            }
             Self::_Data(..) => unreachable!(),
         }
         Ok(())
@@ -89,6 +104,9 @@ impl<T: Send + Sync + 'static> Hash for EventSet<T> {
             Self::Sender => {
                 state.write_u32(0);
             }
+            Self::Reader => {
+                state.write_u32(1);
+            }
             Self::_Data(..) => unreachable!(),
         }
     }
@@ -96,12 +114,10 @@ impl<T: Send + Sync + 'static> Hash for EventSet<T> {
 
 impl<T: Send + Sync + 'static> PartialEq for EventSet<T> {
     fn eq(&self, other: &Self) -> bool {
-        match self {
-            Self::Sender => match other {
-                Self::Sender => true,
-                Self::_Data(..) => unreachable!(),
-            },
-            Self::_Data(..) => unreachable!(),
+        match (self, other) {
+            (Self::Sender, Self::Sender) | (Self::Reader, Self::Reader) => true,
+            (Self::Sender, Self::Reader) | (Self::Reader, Self::Sender) => false,
+            _ => unreachable!(),
         }
     }
 }
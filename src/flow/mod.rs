@@ -1,6 +1,10 @@
+mod event_order;
 mod flow;
+pub(crate) mod system_order;
+
+pub use event_order::*;
 pub use flow::*;
 
 pub mod prelude {
-    pub use super::{EventSet, FlowSet};
+    pub use super::{AddEventSystem, EventSet, FlowSet};
 }
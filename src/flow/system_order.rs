@@ -0,0 +1,73 @@
+use bevy::{
+    ecs::schedule::{NodeId, ScheduleGraph},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+/// Initializes the systems of `schedule` and resolves its base sets, so that its graph can be
+/// inspected. Unlike [`Schedule::initialize`], this leaves the systems in the graph.
+///
+/// Returns `false` if the schedule fails to build, in which case the error is reported when it
+/// first runs, or if it already ran, since running moves the systems out of the graph.
+pub(crate) fn build_graph(schedule: &mut Schedule, world: &mut World) -> bool {
+    let graph = schedule.graph_mut();
+    if graph
+        .dependency()
+        .graph()
+        .nodes()
+        .any(|id| matches!(id, NodeId::System(_)) && graph.get_system_at(id).is_none())
+    {
+        return false;
+    }
+    graph.initialize(world);
+    graph.build_schedule(world.components()).is_ok()
+}
+
+/// Ordering between the systems of a built [`ScheduleGraph`], following `before`/`after`
+/// constraints through the sets that contain them.
+///
+/// Every set and system is split into a start and an end node: a set starts before its children
+/// start and ends after they end, and an ordering edge goes from the end of one node to the start
+/// of the other. A system is then guaranteed to run before another exactly when the end of the
+/// first reaches the start of the second.
+pub(crate) struct SystemOrder {
+    edges: HashMap<(NodeId, bool), Vec<(NodeId, bool)>>,
+}
+
+impl SystemOrder {
+    pub(crate) fn new(graph: &ScheduleGraph) -> Self {
+        let mut edges = HashMap::<_, Vec<_>>::new();
+        let mut add_edge = |from, to| edges.entry(from).or_default().push(to);
+        let sets = graph.system_sets().map(|(id, ..)| id);
+        for id in graph.systems().map(|(id, ..)| id).chain(sets) {
+            add_edge((id, false), (id, true));
+        }
+        for (parent, child, _) in graph.hierarchy().graph().all_edges() {
+            add_edge((parent, false), (child, false));
+            add_edge((child, true), (parent, true));
+        }
+        for (before, after, _) in graph.dependency().graph().all_edges() {
+            add_edge((before, true), (after, false));
+        }
+        Self { edges }
+    }
+
+    /// Whether `before` always finishes before `after` starts.
+    pub(crate) fn runs_before(&self, before: NodeId, after: NodeId) -> bool {
+        let target = (after, false);
+        let mut visited = HashSet::new();
+        let mut stack = vec![(before, true)];
+        while let Some(node) = stack.pop() {
+            if node == target {
+                return true;
+            }
+            if !visited.insert(node) {
+                continue;
+            }
+            if let Some(next) = self.edges.get(&node) {
+                stack.extend(next.iter().copied());
+            }
+        }
+        false
+    }
+}
@@ -2,12 +2,15 @@ use bevy::{prelude::*, transform::TransformSystem};
 
 use crate::Persistent;
 
+use super::ScreenAnchorPlugin;
+
 const RATIO_BAR_SIZE: f32 = 100000.;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum ForceRatioSystem {
     Setup,
     Update,
+    ScreenAnchor,
 }
 
 pub struct ForceRatioPlugin;
@@ -15,6 +18,7 @@ pub struct ForceRatioPlugin;
 impl Plugin for ForceRatioPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ForceRatio>()
+            .add_plugin(ScreenAnchorPlugin)
             .add_startup_system(force_ratio_setup.in_set(ForceRatioSystem::Setup))
             .add_system(
                 force_ratio_update
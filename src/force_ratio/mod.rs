@@ -1,6 +1,11 @@
 mod force_ratio;
+mod screen_anchor;
+
 pub use force_ratio::*;
+pub use screen_anchor::*;
 
 pub mod prelude {
-    pub use super::{ForceRatio, ForceRatioSystem};
+    pub use super::{
+        ForceRatio, ForceRatioSystem, ScreenAnchor, ScreenAnchorCamera, ScreenAnchorPoint,
+    };
 }
@@ -0,0 +1,242 @@
+use bevy::{ecs::query::QuerySingleError, prelude::*};
+
+use crate::transform2::{Transform2, Transform2System, VisualTransform2};
+
+use super::{ForceRatio, ForceRatioSystem};
+
+pub(crate) struct ScreenAnchorPlugin;
+
+impl Plugin for ScreenAnchorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            screen_anchor_update
+                .in_set(ForceRatioSystem::ScreenAnchor)
+                .in_base_set(CoreSet::PostUpdate)
+                .after(ForceRatioSystem::Update)
+                .before(Transform2System::TransformPropagate),
+        );
+    }
+}
+
+/// Keeps an entity attached to a point on the edge of the virtual screen. When [`ForceRatio`] is
+/// enabled, the virtual screen is the forced rectangle centered on the camera. Otherwise it's the
+/// area of the window covered by the camera.
+///
+/// The [`Transform2`] translation is overwritten every frame, so anchored entities should not be
+/// parented. With several cameras, mark the one anchors follow with [`ScreenAnchorCamera`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScreenAnchor {
+    pub anchor: ScreenAnchorPoint,
+    pub offset: Vec2,
+}
+
+impl ScreenAnchor {
+    pub fn new(anchor: ScreenAnchorPoint) -> Self {
+        Self {
+            anchor,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_offset(self, offset: Vec2) -> Self {
+        Self { offset, ..self }
+    }
+
+    pub fn position(&self, screen: Rect) -> Vec2 {
+        screen.center() + self.anchor.direction() * screen.half_size() + self.offset
+    }
+}
+
+/// Marks the camera whose view [`ScreenAnchor`]s follow, when there are several cameras such as a
+/// UI camera next to the game camera.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenAnchorCamera;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreenAnchorPoint {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl ScreenAnchorPoint {
+    /// The anchor's position relative to the screen center, where each axis is in `-1..=1`.
+    pub fn direction(&self) -> Vec2 {
+        match *self {
+            ScreenAnchorPoint::TopLeft => Vec2::new(-1., 1.),
+            ScreenAnchorPoint::Top => Vec2::new(0., 1.),
+            ScreenAnchorPoint::TopRight => Vec2::new(1., 1.),
+            ScreenAnchorPoint::Left => Vec2::new(-1., 0.),
+            ScreenAnchorPoint::Center => Vec2::new(0., 0.),
+            ScreenAnchorPoint::Right => Vec2::new(1., 0.),
+            ScreenAnchorPoint::BottomLeft => Vec2::new(-1., -1.),
+            ScreenAnchorPoint::Bottom => Vec2::new(0., -1.),
+            ScreenAnchorPoint::BottomRight => Vec2::new(1., -1.),
+        }
+    }
+}
+
+impl ForceRatio {
+    /// The world space rectangle of the virtual screen, given the camera position and scale and
+    /// the window size.
+    pub fn screen_rect(
+        &self,
+        camera_translation: Vec2,
+        camera_scale: Vec2,
+        window_size: Vec2,
+    ) -> Rect {
+        match *self {
+            ForceRatio::Disabled => {
+                Rect::from_center_size(camera_translation, window_size * camera_scale)
+            }
+            ForceRatio::Enabled { width, height } => {
+                Rect::from_center_size(camera_translation, Vec2::new(width, height))
+            }
+        }
+    }
+}
+
+type AnchorCameraQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, Option<&'static Transform2>),
+    (With<Camera>, With<ScreenAnchorCamera>),
+>;
+
+fn screen_anchor_update(
+    mut screen_anchor_query: Query<
+        (
+            &mut Transform2,
+            Option<&mut VisualTransform2>,
+            &ScreenAnchor,
+        ),
+        Without<Camera>,
+    >,
+    camera_query: Query<(&Transform, Option<&Transform2>), With<Camera>>,
+    anchor_camera_query: AnchorCameraQuery,
+    window_query: Query<&Window>,
+    force_ratio: Res<ForceRatio>,
+    mut warned: Local<bool>,
+) {
+    let camera = if anchor_camera_query.is_empty() {
+        camera_query.get_single()
+    } else {
+        anchor_camera_query.get_single()
+    };
+    let (camera_transform, camera_transform2) = match camera {
+        Ok(camera) => camera,
+        Err(QuerySingleError::MultipleEntities(_)) => {
+            if !*warned {
+                warn!(
+                    "Screen anchors need a single camera to follow, add `ScreenAnchorCamera` to \
+                    one of the cameras"
+                );
+                *warned = true;
+            }
+            return;
+        }
+        Err(QuerySingleError::NoEntities(_)) => return,
+    };
+    if let Ok(window) = window_query.get_single() {
+        let camera_translation = camera_transform2
+            .map(|camera_transform2| camera_transform2.translation)
+            .unwrap_or(camera_transform.translation.truncate());
+        let screen = force_ratio.screen_rect(
+            camera_translation,
+            camera_transform.scale.truncate(),
+            Vec2::new(window.width(), window.height()),
+        );
+        for (mut transform, visual_transform, screen_anchor) in screen_anchor_query.iter_mut() {
+            transform.translation = screen_anchor.position(screen);
+            if let Some(mut visual_transform) = visual_transform {
+                visual_transform.translation = transform.translation;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{force_ratio::prelude::*, transform2::Transform2};
+
+    use super::screen_anchor_update;
+
+    #[test]
+    fn screen_rect_disabled() {
+        let rect = ForceRatio::Disabled.screen_rect(
+            Vec2::new(10., 0.),
+            Vec2::splat(2.),
+            Vec2::new(800., 600.),
+        );
+        assert_eq!(rect.min, Vec2::new(-790., -600.));
+        assert_eq!(rect.max, Vec2::new(810., 600.));
+    }
+
+    #[test]
+    fn screen_rect_enabled() {
+        let force_ratio = ForceRatio::Enabled {
+            width: 1024.,
+            height: 768.,
+        };
+        let rect =
+            force_ratio.screen_rect(Vec2::new(0., 100.), Vec2::splat(3.), Vec2::new(800., 600.));
+        assert_eq!(rect.min, Vec2::new(-512., -284.));
+        assert_eq!(rect.max, Vec2::new(512., 484.));
+    }
+
+    #[test]
+    fn screen_anchor_position() {
+        let screen = Rect::from_center_size(Vec2::ZERO, Vec2::new(100., 50.));
+        let top_left =
+            ScreenAnchor::new(ScreenAnchorPoint::TopLeft).with_offset(Vec2::new(5., -5.));
+        let center = ScreenAnchor::new(ScreenAnchorPoint::Center);
+        let bottom = ScreenAnchor::new(ScreenAnchorPoint::Bottom);
+        assert_eq!(top_left.position(screen), Vec2::new(-45., 20.));
+        assert_eq!(center.position(screen), Vec2::ZERO);
+        assert_eq!(bottom.position(screen), Vec2::new(0., -25.));
+    }
+
+    #[test]
+    fn screen_anchor_follows_marked_camera() {
+        let mut world = World::new();
+        world.insert_resource(ForceRatio::Enabled {
+            width: 100.,
+            height: 50.,
+        });
+        world.spawn(Window::default());
+        world.spawn((Camera::default(), Transform::from_xyz(-500., 0., 0.)));
+        let anchor = world
+            .spawn((
+                Transform2::default(),
+                ScreenAnchor::new(ScreenAnchorPoint::TopRight),
+            ))
+            .id();
+        let mut schedule = Schedule::new();
+        schedule.add_system(screen_anchor_update);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(anchor).unwrap().translation,
+            Vec2::new(-450., 25.)
+        );
+
+        world.spawn((
+            Camera::default(),
+            Transform::from_xyz(200., 100., 0.),
+            ScreenAnchorCamera,
+        ));
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(anchor).unwrap().translation,
+            Vec2::new(250., 125.)
+        );
+    }
+}
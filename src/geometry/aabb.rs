@@ -1,7 +1,188 @@
 use bevy::prelude::*;
 
-#[derive(Default, Clone, Copy, PartialEq)]
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
 pub struct Aabb {
     pub position: Vec2,
     pub size: Vec2,
 }
+
+impl Aabb {
+    pub fn min(&self) -> Vec2 {
+        self.position - self.size * 0.5
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.position + self.size * 0.5
+    }
+
+    pub fn from_min_max(min: Vec2, max: Vec2) -> Self {
+        Self {
+            position: (min + max) * 0.5,
+            size: max - min,
+        }
+    }
+
+    /// Smallest box containing all `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.min(point), max.max(point))
+        });
+        Some(Self::from_min_max(min, max))
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn merge(&self, other: &Aabb) -> Aabb {
+        Self::from_min_max(self.min().min(other.min()), self.max().max(other.max()))
+    }
+
+    /// Overlapping part of both boxes, or `None` if they don't overlap. Boxes that only touch
+    /// intersect in a box with a zero size.
+    pub fn intersection(&self, other: &Aabb) -> Option<Aabb> {
+        let min = self.min().max(other.min());
+        let max = self.max().min(other.max());
+        if min.cmple(max).all() {
+            Some(Self::from_min_max(min, max))
+        } else {
+            None
+        }
+    }
+
+    /// Grows the box by `amount` on every side. Negative amounts shrink it, down to a zero size.
+    pub fn expand(&self, amount: f32) -> Aabb {
+        Self {
+            position: self.position,
+            size: (self.size + amount * 2.).max(Vec2::ZERO),
+        }
+    }
+
+    /// Whether `other` is entirely inside this box, edges included.
+    pub fn contains_aabb(&self, other: &Aabb) -> bool {
+        self.min().cmple(other.min()).all() && other.max().cmple(self.max()).all()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use bevy::prelude::*;
+
+    use crate::{geometry::prelude::*, transform2::Transform2};
+
+    const EPSILON: f32 = 0.0001;
+
+    fn assert_aabb_eq(a: Option<Aabb>, min: Vec2, max: Vec2) {
+        let a = a.unwrap();
+        assert!(a.min().abs_diff_eq(min, EPSILON), "{a:?}");
+        assert!(a.max().abs_diff_eq(max, EPSILON), "{a:?}");
+    }
+
+    #[test]
+    fn aabb_from_points() {
+        let aabb =
+            Aabb::from_points([Vec2::new(1., 2.), Vec2::new(-3., 4.), Vec2::new(0., -1.)]).unwrap();
+        assert_eq!(aabb.min(), Vec2::new(-3., -1.));
+        assert_eq!(aabb.max(), Vec2::new(1., 4.));
+        assert_eq!(
+            Aabb::from_points([Vec2::ONE]),
+            Some(Aabb {
+                position: Vec2::ONE,
+                size: Vec2::ZERO
+            })
+        );
+        assert_eq!(Aabb::from_points([]), None);
+    }
+
+    #[test]
+    fn aabb_merge_and_intersection() {
+        let a = Aabb::from_min_max(Vec2::ZERO, Vec2::new(2., 2.));
+        let b = Aabb::from_min_max(Vec2::new(1., -1.), Vec2::new(4., 1.));
+        assert_eq!(
+            a.merge(&b),
+            Aabb::from_min_max(Vec2::new(0., -1.), Vec2::new(4., 2.))
+        );
+        assert_eq!(
+            a.intersection(&b),
+            Some(Aabb::from_min_max(Vec2::new(1., 0.), Vec2::new(2., 1.)))
+        );
+        let touching = Aabb::from_min_max(Vec2::new(2., 0.), Vec2::new(3., 1.));
+        assert_eq!(a.intersection(&touching).unwrap().size, Vec2::new(0., 1.));
+        let apart = Aabb::from_min_max(Vec2::new(5., 5.), Vec2::new(6., 6.));
+        assert_eq!(a.intersection(&apart), None);
+    }
+
+    #[test]
+    fn aabb_expand_and_contains() {
+        let a = Aabb::from_min_max(Vec2::ZERO, Vec2::new(2., 2.));
+        let expanded = a.expand(1.);
+        assert_eq!(expanded.min(), Vec2::splat(-1.));
+        assert_eq!(expanded.max(), Vec2::splat(3.));
+        assert_eq!(a.expand(-5.).size, Vec2::ZERO);
+        assert!(expanded.contains_aabb(&a));
+        assert!(a.contains_aabb(&a));
+        assert!(!a.contains_aabb(&expanded));
+        assert!(!a.contains_aabb(&Aabb::from_min_max(Vec2::new(1., 1.), Vec2::new(3., 2.))));
+    }
+
+    #[test]
+    fn shape_bounding_aabb() {
+        let rectangle = Shape::Aabb {
+            size: Vec2::new(4., 2.),
+        };
+        assert_aabb_eq(
+            rectangle.bounding_aabb(),
+            Vec2::new(-2., -1.),
+            Vec2::new(2., 1.),
+        );
+        assert_aabb_eq(
+            rectangle
+                .transformed_by(Transform2::from_xy(1., 0.).with_rotation(FRAC_PI_2))
+                .bounding_aabb(),
+            Vec2::new(0., -2.),
+            Vec2::new(2., 2.),
+        );
+        let diagonal = 3. * 2_f32.sqrt();
+        assert_aabb_eq(
+            rectangle
+                .transformed_by(Transform2::from_rotation(FRAC_PI_4))
+                .bounding_aabb(),
+            Vec2::splat(-diagonal / 2.),
+            Vec2::splat(diagonal / 2.),
+        );
+
+        let circle = Shape::Circle { radius: 2. };
+        assert_aabb_eq(
+            circle
+                .transformed_by(Transform2::from_scale(Vec2::new(3., 1.)))
+                .bounding_aabb(),
+            Vec2::new(-3., -1.),
+            Vec2::new(3., 1.),
+        );
+        let ellipse = circle
+            .transformed_by(Transform2::from_scale(Vec2::new(3., 1.)).with_rotation(FRAC_PI_4))
+            .bounding_aabb()
+            .unwrap();
+        assert!((ellipse.size.x - 2. * 5_f32.sqrt()).abs() < EPSILON);
+        assert!((ellipse.size.y - 2. * 5_f32.sqrt()).abs() < EPSILON);
+
+        let capsule = Shape::Capsule {
+            height: 4.,
+            radius: 2.,
+        };
+        assert_aabb_eq(
+            capsule.bounding_aabb(),
+            Vec2::new(-1., -2.),
+            Vec2::new(1., 2.),
+        );
+        assert_aabb_eq(
+            capsule
+                .transformed_by(Transform2::from_rotation(FRAC_PI_2).with_scale(Vec2::splat(2.)))
+                .bounding_aabb(),
+            Vec2::new(-4., -2.),
+            Vec2::new(4., 2.),
+        );
+        assert_eq!(Shape::None.bounding_aabb(), None);
+    }
+}
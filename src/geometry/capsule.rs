@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+use super::{Polygon, Segment};
+
+/// A segment from `start` to `end` grown by `radius`, with `radius` following the same
+/// convention as [`Circle`](super::Circle). A capsule whose ends are equal is a circle.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Capsule {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// An upright capsule centered on `position`. Like [`Aabb`](super::Aabb), `height` is the
+    /// full height, including the caps.
+    pub fn vertical(position: Vec2, height: f32, radius: f32) -> Self {
+        let half_length = (height * 0.5 - radius * 0.5).max(0.);
+        Self {
+            start: position - Vec2::new(0., half_length),
+            end: position + Vec2::new(0., half_length),
+            radius,
+        }
+    }
+
+    pub fn segment(&self) -> Segment {
+        Segment {
+            start: self.start,
+            end: self.end,
+        }
+    }
+
+    /// Polygon approximation of the capsule, with each cap made of `segments / 2` edges.
+    pub(crate) fn to_polygon(self, segments: usize) -> Polygon {
+        let radius = self.radius * 0.5;
+        let direction = (self.end - self.start).try_normalize().unwrap_or(Vec2::Y);
+        let half_segments = (segments / 2).max(1);
+        let mut points = Vec::with_capacity((half_segments + 1) * 2);
+        for (center, normal) in [
+            (self.end, -direction.perp()),
+            (self.start, direction.perp()),
+        ] {
+            for i in 0..=half_segments {
+                let angle = i as f32 / half_segments as f32 * std::f32::consts::PI;
+                points.push(center + Vec2::from_angle(angle).rotate(normal) * radius);
+            }
+        }
+        Polygon::new_unchecked(points)
+    }
+}
@@ -0,0 +1,550 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{fixed_timestep::AddFixedEvent, flow::FlowSet, transform2::Transform2};
+
+use super::{Aabb, CollidingWith, Contact, ContactWith, Shape, TransformedShape};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum GeometrySystem {
+    KinematicBody,
+    SpatialIndex,
+    Collisions,
+    ShapeMesh,
+    Debug,
+}
+
+pub(crate) struct ColliderPlugin;
+
+impl Plugin for ColliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Collisions>()
+            .add_fixed_event::<CollisionStarted>()
+            .add_fixed_event::<CollisionEnded>()
+            .add_fixed_event::<TriggerEnter>()
+            .add_fixed_event::<TriggerExit>()
+            .add_system(
+                collisions_update
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(GeometrySystem::Collisions)
+                    .in_base_set(FlowSet::EntitySpawn)
+                    .after(FlowSet::EntityMovement),
+            );
+    }
+}
+
+/// A shape that takes part in collision detection, placed at the entity's [`Transform2`]. Since
+/// only the entity's own transform is used, colliders should not be parented.
+///
+/// Two colliders are tested against each other when each one's `layers` share a bit with the
+/// other's `mask`. Sensors don't collide: overlaps with them are only reported as
+/// [`TriggerEnter`] and [`TriggerExit`], without contacts, and character movement passes through
+/// them.
+#[derive(Component, Debug, Clone)]
+pub struct Collider {
+    pub shape: Shape,
+    pub layers: u32,
+    pub mask: u32,
+    pub sensor: bool,
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Self {
+            shape: Shape::None,
+            layers: 1,
+            mask: u32::MAX,
+            sensor: false,
+        }
+    }
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_layers(self, layers: u32) -> Self {
+        Self { layers, ..self }
+    }
+
+    pub fn with_mask(self, mask: u32) -> Self {
+        Self { mask, ..self }
+    }
+
+    pub fn sensor(self) -> Self {
+        Self {
+            sensor: true,
+            ..self
+        }
+    }
+
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+
+    pub fn at(&self, transform: Transform2) -> TransformedShape {
+        self.shape.transformed_by(transform)
+    }
+}
+
+/// Sent when two colliders that aren't sensors start touching. `a` is always the lesser entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionStarted {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Sent when two colliders stop touching, or when either of them is despawned or loses its
+/// [`Collider`]. `a` is always the lesser entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionEnded {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// Sent when a collider starts overlapping a [sensor](Collider::sensor). When both colliders are
+/// sensors, each of them gets an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEnter {
+    pub sensor: Entity,
+    pub other: Entity,
+}
+
+/// Sent when a collider stops overlapping a [sensor](Collider::sensor), including when either of
+/// them is despawned, for example by `TimeToLive` or a scene change. The entities may no longer
+/// exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerExit {
+    pub sensor: Entity,
+    pub other: Entity,
+}
+
+/// Every pair of colliders touching as of the last fixed update. Pairs with a sensor are only
+/// tracked as triggers.
+#[derive(Resource, Debug, Default)]
+pub struct Collisions {
+    contacts: HashMap<(Entity, Entity), Contact>,
+    /// Pairs of a sensor and a collider overlapping it.
+    triggers: HashSet<(Entity, Entity)>,
+}
+
+impl Collisions {
+    /// The contact between `a` and `b`, with its normal pointing from `a` to `b`.
+    pub fn get(&self, a: Entity, b: Entity) -> Option<Contact> {
+        if a <= b {
+            self.contacts.get(&(a, b)).cloned()
+        } else {
+            self.contacts.get(&(b, a)).cloned().map(Contact::flipped)
+        }
+    }
+
+    pub fn contains(&self, a: Entity, b: Entity) -> bool {
+        self.contacts.contains_key(&(a.min(b), a.max(b)))
+    }
+
+    /// Iterates over all touching pairs. The contact normals point from the first entity to the
+    /// second.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity, &Contact)> + '_ {
+        self.contacts
+            .iter()
+            .map(|((a, b), contact)| (*a, *b, contact))
+    }
+
+    /// Iterates over the entities touching `entity`, and the contacts with their normals pointing
+    /// away from `entity`.
+    pub fn colliding_with(&self, entity: Entity) -> impl Iterator<Item = (Entity, Contact)> + '_ {
+        self.contacts.iter().filter_map(move |((a, b), contact)| {
+            if *a == entity {
+                Some((*b, contact.clone()))
+            } else if *b == entity {
+                Some((*a, contact.clone().flipped()))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether `other` overlaps the sensor `sensor`, having entered it in an earlier update.
+    pub fn is_triggering(&self, sensor: Entity, other: Entity) -> bool {
+        self.triggers.contains(&(sensor, other))
+    }
+
+    /// Iterates over the colliders overlapping the sensor `sensor`.
+    pub fn triggering(&self, sensor: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.triggers
+            .iter()
+            .filter(move |(trigger_sensor, _)| *trigger_sensor == sensor)
+            .map(|(_, other)| *other)
+    }
+
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+}
+
+/// Finds every pair of interacting colliders whose bounding boxes overlap, by sorting the boxes
+/// along the x axis and sweeping over them. Pairs are ordered with the lesser entity first.
+pub(crate) fn broadphase<'a>(
+    colliders: impl IntoIterator<Item = (Entity, &'a Collider, Aabb)>,
+) -> Vec<(Entity, Entity)> {
+    let mut boxes: Vec<(Entity, &Collider, Aabb)> = colliders.into_iter().collect();
+    boxes.sort_by(|(_, _, a), (_, _, b)| a.min().x.total_cmp(&b.min().x));
+    let mut pairs = vec![];
+    for (i, (a_entity, a_collider, a_aabb)) in boxes.iter().enumerate() {
+        for (b_entity, b_collider, b_aabb) in boxes[(i + 1)..].iter() {
+            if b_aabb.min().x > a_aabb.max().x {
+                break;
+            }
+            if a_aabb.min().y <= b_aabb.max().y
+                && b_aabb.min().y <= a_aabb.max().y
+                && a_collider.interacts_with(b_collider)
+            {
+                pairs.push((*a_entity.min(b_entity), *a_entity.max(b_entity)));
+            }
+        }
+    }
+    pairs
+}
+
+fn collisions_update(
+    collider_query: Query<(Entity, &Collider, &Transform2)>,
+    mut collisions: ResMut<Collisions>,
+    mut collision_started_events: EventWriter<CollisionStarted>,
+    mut collision_ended_events: EventWriter<CollisionEnded>,
+    mut trigger_enter_events: EventWriter<TriggerEnter>,
+    mut trigger_exit_events: EventWriter<TriggerExit>,
+) {
+    let shapes: HashMap<Entity, TransformedShape> = collider_query
+        .iter()
+        .map(|(entity, collider, transform)| (entity, collider.at(*transform)))
+        .collect();
+    let pairs = broadphase(collider_query.iter().filter_map(|(entity, collider, _)| {
+        shapes[&entity]
+            .bounding_aabb()
+            .map(|aabb| (entity, collider, aabb))
+    }));
+    let sensors: HashSet<Entity> = collider_query
+        .iter()
+        .filter(|(_, collider, _)| collider.sensor)
+        .map(|(entity, ..)| entity)
+        .collect();
+    let mut contacts = HashMap::with_capacity(pairs.len());
+    let mut triggers = HashSet::new();
+    for (a, b) in pairs.into_iter() {
+        let (a_sensor, b_sensor) = (sensors.contains(&a), sensors.contains(&b));
+        if a_sensor || b_sensor {
+            if shapes[&a].colliding_with(&shapes[&b]) {
+                if a_sensor {
+                    triggers.insert((a, b));
+                }
+                if b_sensor {
+                    triggers.insert((b, a));
+                }
+            }
+        } else if let Some(contact) = shapes[&a].contact_with(&shapes[&b]) {
+            contacts.insert((a, b), contact);
+        }
+    }
+    let mut ended: Vec<_> = collisions
+        .contacts
+        .keys()
+        .filter(|pair| !contacts.contains_key(pair))
+        .copied()
+        .collect();
+    ended.sort();
+    for (a, b) in ended.into_iter() {
+        collision_ended_events.send(CollisionEnded { a, b });
+    }
+    let mut started: Vec<_> = contacts
+        .keys()
+        .filter(|pair| !collisions.contacts.contains_key(pair))
+        .copied()
+        .collect();
+    started.sort();
+    for (a, b) in started.into_iter() {
+        collision_started_events.send(CollisionStarted { a, b });
+    }
+
+    let mut exited: Vec<_> = collisions.triggers.difference(&triggers).copied().collect();
+    exited.sort();
+    for (sensor, other) in exited.into_iter() {
+        trigger_exit_events.send(TriggerExit { sensor, other });
+    }
+    let mut entered: Vec<_> = triggers.difference(&collisions.triggers).copied().collect();
+    entered.sort();
+    for (sensor, other) in entered.into_iter() {
+        trigger_enter_events.send(TriggerEnter { sensor, other });
+    }
+
+    collisions.contacts = contacts;
+    collisions.triggers = triggers;
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    use super::{broadphase, collisions_update};
+
+    #[test]
+    fn collider_layers() {
+        let player = Collider::new(Shape::Point)
+            .with_layers(0b01)
+            .with_mask(0b10);
+        let wall = Collider::new(Shape::Point).with_layers(0b10);
+        let other_player = player.clone();
+        assert!(player.interacts_with(&wall));
+        assert!(wall.interacts_with(&player));
+        assert!(!player.interacts_with(&other_player));
+    }
+
+    #[test]
+    fn broadphase_matches_brute_force() {
+        let mut world = World::new();
+        let collider = Collider::new(Shape::Capsule {
+            height: 3.,
+            radius: 1.,
+        });
+        let shapes: Vec<(Entity, TransformedShape)> = (0..60)
+            .map(|i| {
+                let i = i as f32;
+                let transform = Transform2::from_xy((i * 7.3) % 20., (i * 3.1) % 15.)
+                    .with_rotation(i * 0.4)
+                    .with_scale(Vec2::new(1. + i % 3., 1.));
+                (world.spawn_empty().id(), collider.at(transform))
+            })
+            .collect();
+        let mut pairs = broadphase(
+            shapes
+                .iter()
+                .map(|(entity, shape)| (*entity, &collider, shape.bounding_aabb().unwrap())),
+        );
+        pairs.retain(|(a, b)| {
+            let a = &shapes.iter().find(|(entity, _)| entity == a).unwrap().1;
+            let b = &shapes.iter().find(|(entity, _)| entity == b).unwrap().1;
+            a.colliding_with(b)
+        });
+        pairs.sort();
+        let mut expected = vec![];
+        for (i, (a_entity, a)) in shapes.iter().enumerate() {
+            for (b_entity, b) in shapes[(i + 1)..].iter() {
+                if a.colliding_with(b) {
+                    expected.push((*a_entity.min(b_entity), *a_entity.max(b_entity)));
+                }
+            }
+        }
+        expected.sort();
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+    }
+
+    /// A world with the resources of [`collisions_update`] and a schedule running it.
+    fn collisions_world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Collisions>();
+        world.init_resource::<Events<CollisionStarted>>();
+        world.init_resource::<Events<CollisionEnded>>();
+        world.init_resource::<Events<TriggerEnter>>();
+        world.init_resource::<Events<TriggerExit>>();
+        let mut schedule = Schedule::new();
+        schedule.add_system(collisions_update);
+        (world, schedule)
+    }
+
+    #[test]
+    fn collision_events() {
+        let (mut world, mut schedule) = collisions_world();
+        let a = world
+            .spawn((
+                Collider::new(Shape::Circle { radius: 2. }),
+                Transform2::from_xy(0., 0.),
+            ))
+            .id();
+        let b = world
+            .spawn((
+                Collider::new(Shape::Aabb { size: Vec2::ONE }),
+                Transform2::from_xy(5., 0.),
+            ))
+            .id();
+        schedule.run(&mut world);
+        assert!(world.resource::<Collisions>().is_empty());
+
+        world.get_mut::<Transform2>(b).unwrap().translation = Vec2::new(1., 0.);
+        schedule.run(&mut world);
+        let collisions = world.resource::<Collisions>();
+        assert!(collisions.contains(b, a));
+        assert_eq!(collisions.get(a, b).unwrap().normal, Vec2::X);
+        assert_eq!(collisions.get(b, a).unwrap().normal, -Vec2::X);
+        assert_eq!(collisions.colliding_with(b).next().unwrap().0, a);
+        let started: Vec<_> = world
+            .resource_mut::<Events<CollisionStarted>>()
+            .drain()
+            .collect();
+        assert_eq!(
+            started,
+            vec![CollisionStarted {
+                a: a.min(b),
+                b: a.max(b)
+            }]
+        );
+
+        schedule.run(&mut world);
+        assert!(world
+            .resource_mut::<Events<CollisionStarted>>()
+            .drain()
+            .next()
+            .is_none());
+
+        world.despawn(a);
+        schedule.run(&mut world);
+        assert!(world.resource::<Collisions>().is_empty());
+        let ended: Vec<_> = world
+            .resource_mut::<Events<CollisionEnded>>()
+            .drain()
+            .collect();
+        assert_eq!(
+            ended,
+            vec![CollisionEnded {
+                a: a.min(b),
+                b: a.max(b)
+            }]
+        );
+    }
+
+    #[test]
+    fn trigger_events() {
+        let (mut world, mut schedule) = collisions_world();
+        let door = world
+            .spawn((
+                Collider::new(Shape::Aabb {
+                    size: Vec2::splat(4.),
+                })
+                .sensor(),
+                Transform2::new(),
+            ))
+            .id();
+        let player = world
+            .spawn((
+                Collider::new(Shape::Circle { radius: 1. }),
+                Transform2::from_xy(1., 0.),
+            ))
+            .id();
+        let wall = world
+            .spawn((
+                Collider::new(Shape::Aabb { size: Vec2::ONE }),
+                Transform2::from_xy(1., 1.),
+            ))
+            .id();
+        let drain = |world: &mut World| {
+            let entered: Vec<_> = world
+                .resource_mut::<Events<TriggerEnter>>()
+                .drain()
+                .collect();
+            let exited: Vec<_> = world
+                .resource_mut::<Events<TriggerExit>>()
+                .drain()
+                .collect();
+            (entered, exited)
+        };
+
+        schedule.run(&mut world);
+        let (mut entered, exited) = drain(&mut world);
+        entered.sort_by_key(|event| event.other);
+        let mut expected = vec![
+            TriggerEnter {
+                sensor: door,
+                other: player,
+            },
+            TriggerEnter {
+                sensor: door,
+                other: wall,
+            },
+        ];
+        expected.sort_by_key(|event| event.other);
+        assert_eq!(entered, expected);
+        assert!(exited.is_empty());
+        let collisions = world.resource::<Collisions>();
+        assert!(collisions.is_triggering(door, player));
+        assert!(!collisions.is_triggering(player, door));
+        assert!(!collisions.is_triggering(player, wall));
+        assert_eq!(collisions.triggering(door).count(), 2);
+        assert!(!collisions.contains(door, player) && !collisions.contains(door, wall));
+        assert!(world
+            .resource::<Events<CollisionStarted>>()
+            .iter_current_update_events()
+            .all(|event| event.a != door && event.b != door));
+
+        // Staying inside doesn't send anything.
+        schedule.run(&mut world);
+        assert_eq!(drain(&mut world), (vec![], vec![]));
+
+        world.get_mut::<Transform2>(player).unwrap().translation = Vec2::new(10., 0.);
+        schedule.run(&mut world);
+        assert_eq!(
+            drain(&mut world),
+            (
+                vec![],
+                vec![TriggerExit {
+                    sensor: door,
+                    other: player,
+                }]
+            )
+        );
+
+        world.despawn(door);
+        schedule.run(&mut world);
+        assert_eq!(
+            drain(&mut world),
+            (
+                vec![],
+                vec![TriggerExit {
+                    sensor: door,
+                    other: wall,
+                }]
+            )
+        );
+        assert_eq!(world.resource::<Collisions>().triggering(door).count(), 0);
+    }
+
+    #[test]
+    fn trigger_between_sensors() {
+        let (mut world, mut schedule) = collisions_world();
+        let a = world
+            .spawn((
+                Collider::new(Shape::Circle { radius: 2. }).sensor(),
+                Transform2::new(),
+            ))
+            .id();
+        let b = world
+            .spawn((
+                Collider::new(Shape::Circle { radius: 2. }).sensor(),
+                Transform2::from_xy(1., 0.),
+            ))
+            .id();
+        schedule.run(&mut world);
+        let collisions = world.resource::<Collisions>();
+        assert!(collisions.is_triggering(a, b) && collisions.is_triggering(b, a));
+        assert!(collisions.is_empty());
+        assert_eq!(world.resource::<Events<TriggerEnter>>().len(), 2);
+        assert!(world.resource::<Events<CollisionStarted>>().is_empty());
+
+        world.despawn(b);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Events<TriggerExit>>().len(), 2);
+        assert!(world.resource::<Events<CollisionEnded>>().is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use super::{Aabb, Circle, TransformedShape};
+use super::{
+    convex::ToConvex, Aabb, Capsule, Circle, DistanceToPoint, Ellipse, Obb, Point, Polygon,
+    Segment, TransformedShape,
+};
 
 pub trait CollidingWith<T> {
     fn colliding_with(&self, other: &T) -> bool;
@@ -14,12 +17,7 @@ impl CollidingWith<Circle> for Circle {
 
 impl CollidingWith<Aabb> for Circle {
     fn colliding_with(&self, other: &Aabb) -> bool {
-        let relative_center = self.position - other.position;
-        let offset_from_corner = (relative_center).abs() - other.size * 0.5;
-        offset_from_corner.x.max(offset_from_corner.y).min(0.)
-            + (offset_from_corner.max(Vec2::ZERO)).length()
-            - self.radius * 0.5
-            < 0.
+        other.signed_distance(self.position) - self.radius * 0.5 < 0.
     }
 }
 
@@ -38,34 +36,88 @@ impl CollidingWith<Aabb> for Aabb {
     }
 }
 
-impl CollidingWith<Circle> for TransformedShape {
-    fn colliding_with(&self, other: &Circle) -> bool {
-        self.colliding_with(&TransformedShape::from(*other))
-    }
+macro_rules! impl_colliding_with_convex {
+    ($($a:ty => [$($b:ty),+]),+ $(,)?) => {
+        $($(
+            impl CollidingWith<$b> for $a {
+                fn colliding_with(&self, other: &$b) -> bool {
+                    self.to_convex().colliding_with(&other.to_convex())
+                }
+            }
+        )+)+
+    };
 }
 
-impl CollidingWith<Aabb> for TransformedShape {
-    fn colliding_with(&self, other: &Aabb) -> bool {
-        self.colliding_with(&TransformedShape::from(*other))
-    }
+impl_colliding_with_convex!(
+    Circle => [Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Aabb => [Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Obb => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Ellipse => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Polygon => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Point => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Segment => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+    Capsule => [Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule],
+);
+
+macro_rules! impl_transformed_shape_colliding_with {
+    ($($primitive:ty),+) => {
+        $(
+            impl CollidingWith<$primitive> for TransformedShape {
+                fn colliding_with(&self, other: &$primitive) -> bool {
+                    transformed_shape_to_shape!(
+                        self,
+                        a,
+                        a.colliding_with(other),
+                        false,
+                        near Some(other.bounding_aabb()),
+                        |parts| parts.any(|part| part.colliding_with(other))
+                    )
+                }
+            }
+
+            impl CollidingWith<TransformedShape> for $primitive {
+                fn colliding_with(&self, other: &TransformedShape) -> bool {
+                    other.colliding_with(self)
+                }
+            }
+        )+
+    };
 }
 
+impl_transformed_shape_colliding_with!(
+    Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule
+);
+
 impl CollidingWith<TransformedShape> for TransformedShape {
     fn colliding_with(&self, other: &TransformedShape) -> bool {
         transformed_shape_to_shape!(
             self,
             a,
-            transformed_shape_to_shape!(other, b, a.colliding_with(&b), return false),
-            false
+            transformed_shape_to_shape!(
+                other,
+                b,
+                a.colliding_with(&b),
+                false,
+                near Some(a.bounding_aabb()),
+                |parts| parts.any(|part| a.colliding_with(&part))
+            ),
+            false,
+            near other.bounding_aabb(),
+            |parts| parts.any(|part| part.colliding_with(other))
         )
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
     use bevy::prelude::*;
 
-    use crate::geometry::prelude::*;
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
 
     #[test]
     fn colliding_circle_circle() {
@@ -154,4 +206,261 @@ mod test {
         assert!(a.colliding_with(&b));
         assert!(!a.colliding_with(&c));
     }
+
+    #[test]
+    fn colliding_obb_obb() {
+        let a = Obb {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 0.5),
+            rotation: FRAC_PI_4,
+        };
+        let b = Obb {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 0.5),
+            rotation: -FRAC_PI_4,
+        };
+        let c = Obb {
+            position: Vec2::new(1., -1.),
+            size: Vec2::new(4., 0.5),
+            rotation: FRAC_PI_4,
+        };
+        assert!(a.colliding_with(&b));
+        assert!(b.colliding_with(&c));
+        assert!(!a.colliding_with(&c));
+    }
+
+    #[test]
+    fn colliding_ellipse() {
+        let a = Ellipse {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 1.),
+            rotation: 0.,
+        };
+        let b = Circle {
+            position: Vec2::new(2.2, 0.),
+            radius: 0.5,
+        };
+        let c = Aabb {
+            position: Vec2::new(0., 0.8),
+            size: Vec2::splat(0.5),
+        };
+        assert!(a.colliding_with(&b));
+        assert!(!a.colliding_with(&c));
+        let a = Ellipse {
+            rotation: FRAC_PI_2,
+            ..a
+        };
+        assert!(!a.colliding_with(&b));
+        assert!(a.colliding_with(&c));
+        assert!(a.colliding_with(&a));
+    }
+
+    #[test]
+    fn transformed_shape_colliding_rotated_aabb() {
+        let a = Shape::Aabb {
+            size: Vec2::new(2., 0.2),
+        };
+        let b = Shape::Circle { radius: 0.2 }.at(Vec2::splat(0.6));
+        assert!(!a.at(Vec2::ZERO).colliding_with(&b));
+        assert!(a
+            .transformed_by(Transform2::from_rotation(FRAC_PI_4))
+            .colliding_with(&b));
+        assert!(!a
+            .transformed_by(Transform2::from_rotation(-FRAC_PI_4))
+            .colliding_with(&b));
+    }
+
+    #[test]
+    fn transformed_shape_colliding_scaled() {
+        let a = Shape::Circle { radius: 1. };
+        let b = Shape::Aabb {
+            size: Vec2::splat(0.1),
+        }
+        .at(Vec2::new(1.4, 0.));
+        assert!(!a.at(Vec2::ZERO).colliding_with(&b));
+        assert!(a
+            .transformed_by(Transform2::from_scale(Vec2::splat(3.)))
+            .colliding_with(&b));
+        assert!(a
+            .transformed_by(Transform2::from_scale(Vec2::new(3., 1.)))
+            .colliding_with(&b));
+        assert!(!a
+            .transformed_by(Transform2::from_scale(Vec2::new(3., 1.)).with_rotation(FRAC_PI_2))
+            .colliding_with(&b));
+        let c = Shape::Aabb { size: Vec2::ONE }
+            .transformed_by(Transform2::from_scale(Vec2::new(-3., 1.)))
+            .colliding_with(&b);
+        assert!(c);
+    }
+
+    #[test]
+    fn transformed_shape_colliding_primitives() {
+        let a = Shape::Aabb {
+            size: Vec2::new(2., 0.2),
+        }
+        .transformed_by(Transform2::from_rotation(FRAC_PI_2));
+        let b = Obb {
+            position: Vec2::new(0., 0.8),
+            size: Vec2::splat(0.2),
+            rotation: 1.,
+        };
+        assert!(a.colliding_with(&b));
+        assert!(b.colliding_with(&a));
+        assert!(!a.colliding_with(&Circle {
+            position: Vec2::new(0.8, 0.),
+            radius: 0.2,
+        }));
+    }
+
+    #[test]
+    fn colliding_polygon() {
+        let triangle = Polygon::new([Vec2::ZERO, Vec2::new(2., 0.), Vec2::new(0., 2.)]).unwrap();
+        let near = Circle {
+            position: Vec2::splat(0.9),
+            radius: 0.4,
+        };
+        let far = Circle {
+            position: Vec2::splat(1.2),
+            radius: 0.4,
+        };
+        assert!(triangle.colliding_with(&near));
+        assert!(!triangle.colliding_with(&far));
+        assert!(far.colliding_with(&Aabb {
+            position: Vec2::splat(1.2),
+            size: Vec2::ONE,
+        }));
+        let aabb = Aabb {
+            position: Vec2::new(1.5, 1.5),
+            size: Vec2::splat(0.9),
+        };
+        assert!(!triangle.colliding_with(&aabb));
+        assert!(triangle.colliding_with(&Obb {
+            rotation: FRAC_PI_4,
+            position: Vec2::new(1.5, 1.5),
+            size: Vec2::new(2., 0.2),
+        }));
+        assert!(triangle.colliding_with(&Ellipse {
+            position: Vec2::new(-1., 0.5),
+            size: Vec2::new(2.2, 0.5),
+            rotation: 0.,
+        }));
+        let other =
+            Polygon::new([Vec2::new(1.1, 1.1), Vec2::new(2., 1.), Vec2::new(1., 2.)]).unwrap();
+        assert!(!triangle.colliding_with(&other));
+        assert!(triangle.colliding_with(&triangle));
+    }
+
+    #[test]
+    fn transformed_shape_colliding_polygon() {
+        let slope = Shape::polygon([Vec2::ZERO, Vec2::new(4., 0.), Vec2::new(4., 2.)]).unwrap();
+        let player = Shape::Aabb { size: Vec2::ONE };
+        assert!(!slope
+            .at(Vec2::ZERO)
+            .colliding_with(&player.at(Vec2::new(1., 2.))));
+        assert!(slope
+            .at(Vec2::ZERO)
+            .colliding_with(&player.at(Vec2::new(3., 1.))));
+        assert!(slope
+            .transformed_by(Transform2::from_scale(Vec2::new(1., 3.)))
+            .colliding_with(&player.at(Vec2::new(1.5, 1.5))));
+        assert!(!slope
+            .transformed_by(Transform2::from_scale(Vec2::new(-1., 1.)))
+            .colliding_with(&player.at(Vec2::new(3., 1.))));
+        assert!(slope
+            .transformed_by(Transform2::from_scale(Vec2::new(-1., 1.)))
+            .colliding_with(&player.at(Vec2::new(-3., 1.))));
+    }
+
+    #[test]
+    fn colliding_capsule_segment_point() {
+        let capsule = Capsule {
+            start: Vec2::new(0., -1.),
+            end: Vec2::new(0., 1.),
+            radius: 1.,
+        };
+        let laser = Segment {
+            start: Vec2::new(-2., 1.2),
+            end: Vec2::new(2., 1.2),
+        };
+        let high_laser = Segment {
+            start: Vec2::new(-2., 1.6),
+            end: Vec2::new(2., 1.6),
+        };
+        assert!(capsule.colliding_with(&laser));
+        assert!(laser.colliding_with(&capsule));
+        assert!(!capsule.colliding_with(&high_laser));
+        assert!(capsule.colliding_with(&Point {
+            position: Vec2::new(0.4, -1.2),
+        }));
+        assert!(!capsule.colliding_with(&Point {
+            position: Vec2::new(0.4, -1.4),
+        }));
+        assert!(capsule.colliding_with(&Circle {
+            position: Vec2::new(1., 0.),
+            radius: 1.1,
+        }));
+        assert!(!capsule.colliding_with(&Aabb {
+            position: Vec2::new(1.5, 0.),
+            size: Vec2::ONE,
+        }));
+        let tripwire = Segment {
+            start: Vec2::new(-1., -1.),
+            end: Vec2::new(1., 1.),
+        };
+        assert!(tripwire.colliding_with(&Segment {
+            start: Vec2::new(-1., 1.),
+            end: Vec2::new(1., -1.),
+        }));
+        assert!(!tripwire.colliding_with(&Segment {
+            start: Vec2::new(2., 2.),
+            end: Vec2::new(3., 3.),
+        }));
+        assert!(tripwire.colliding_with(&Point {
+            position: Vec2::ZERO
+        }));
+        assert!(!tripwire.colliding_with(&Point { position: Vec2::X }));
+        assert!(Point { position: Vec2::X }.colliding_with(&Aabb {
+            position: Vec2::ZERO,
+            size: Vec2::splat(2.),
+        }));
+    }
+
+    #[test]
+    fn transformed_shape_colliding_capsule() {
+        let body = Shape::Capsule {
+            height: 4.,
+            radius: 2.,
+        };
+        let wall = Shape::Segment {
+            start: Vec2::new(0., -10.),
+            end: Vec2::new(0., 10.),
+        };
+        assert!(!body
+            .at(Vec2::new(1.5, 0.))
+            .colliding_with(&wall.at(Vec2::ZERO)));
+        assert!(body
+            .transformed_by(Transform2::from_xy(1.5, 0.).with_rotation(FRAC_PI_2))
+            .colliding_with(&wall.at(Vec2::ZERO)));
+        assert!(body
+            .transformed_by(Transform2::from_xy(1.5, 0.).with_scale(Vec2::new(2., 1.)))
+            .colliding_with(&wall.at(Vec2::ZERO)));
+        assert!(body
+            .at(Vec2::new(0., 1.9))
+            .colliding_with(&Shape::Point.at(Vec2::ZERO)));
+        assert!(!body
+            .at(Vec2::new(0., 2.1))
+            .colliding_with(&Shape::Point.at(Vec2::ZERO)));
+        let capsule = Capsule {
+            start: Vec2::ZERO,
+            end: Vec2::new(2., 2.),
+            radius: 1.,
+        };
+        let point = Point {
+            position: Vec2::new(2.2, 2.2),
+        };
+        assert!(TransformedShape::from(capsule).colliding_with(&point));
+        assert!(!TransformedShape::from(capsule).colliding_with(&Point {
+            position: Vec2::new(2.4, 2.4),
+        }));
+    }
 }
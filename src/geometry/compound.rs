@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+
+use crate::transform2::Transform2;
+
+use super::{
+    Aabb, CollidingWith, Contact, ContactWith, Ray2, RayHit, Raycast, Shape, TransformedShape,
+};
+
+/// A shape made of several parts, each with its own transform relative to the compound.
+///
+/// Queries against a [`TransformedShape`] holding a compound test every part. The bounding box of
+/// the parts is computed once when the compound is built, so broadphase checks don't have to visit
+/// them.
+#[derive(Default, Clone, Debug)]
+pub struct CompoundShape {
+    parts: Vec<(Transform2, Shape)>,
+    local_aabb: Option<Aabb>,
+}
+
+impl CompoundShape {
+    pub fn new(parts: impl IntoIterator<Item = (Transform2, Shape)>) -> Self {
+        let mut compound = Self::default();
+        for (transform, shape) in parts {
+            compound.push(transform, shape);
+        }
+        compound
+    }
+
+    pub fn with(mut self, transform: Transform2, shape: Shape) -> Self {
+        self.push(transform, shape);
+        self
+    }
+
+    pub fn push(&mut self, transform: Transform2, shape: Shape) {
+        let part_aabb = shape.transformed_by(transform).bounding_aabb();
+        self.local_aabb = match (self.local_aabb, part_aabb) {
+            (Some(a), Some(b)) => Some(a.merge(&b)),
+            (a, b) => a.or(b),
+        };
+        self.parts.push((transform, shape));
+    }
+
+    pub fn parts(&self) -> &[(Transform2, Shape)] {
+        &self.parts
+    }
+
+    /// Bounding box of all the parts, relative to the compound.
+    pub fn local_aabb(&self) -> Option<Aabb> {
+        self.local_aabb
+    }
+
+    /// The parts placed in world space by the compound's `transform`.
+    pub fn transformed_parts<'a>(
+        &'a self,
+        transform: &'a Transform2,
+    ) -> impl Iterator<Item = TransformedShape> + 'a {
+        self.parts.iter().map(|(part_transform, shape)| {
+            shape.transformed_by(transform.mul_transform(*part_transform))
+        })
+    }
+
+    /// Uses the cached local box when the scale is uniform. A non-uniform scale stretches rotated
+    /// parts differently from the box around them, so each placed part is boxed instead.
+    pub(crate) fn bounding_aabb(&self, transform: &Transform2) -> Option<Aabb> {
+        if transform.scale.x != transform.scale.y {
+            return self
+                .transformed_parts(transform)
+                .filter_map(|part| part.bounding_aabb())
+                .reduce(|a, b| a.merge(&b));
+        }
+        let local_aabb = self.local_aabb?;
+        let (min, max) = (local_aabb.min(), local_aabb.max());
+        Aabb::from_points(
+            [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+                .map(|corner| transform.transform_point(corner)),
+        )
+    }
+}
+
+/// Keeps the contact with the largest depth, which is the one that needs resolving first.
+pub(crate) fn deepest_contact(contacts: impl Iterator<Item = Contact>) -> Option<Contact> {
+    contacts.max_by(|a, b| a.depth.total_cmp(&b.depth))
+}
+
+impl TransformedShape {
+    /// The shape's parts in world space: the parts of a [`Shape::Compound`], the merged rectangles
+    /// of a [`Shape::TileGrid`], or the shape itself.
+    pub fn parts(&self) -> Vec<TransformedShape> {
+        match &self.shape {
+            Shape::Compound(compound) => compound.transformed_parts(&self.transform).collect(),
+            Shape::TileGrid(grid) => grid.compound().transformed_parts(&self.transform).collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Indices of the parts colliding with `other`. A shape that isn't a compound is its only
+    /// part, with index zero.
+    pub fn colliding_parts<T>(&self, other: &T) -> Vec<usize>
+    where
+        TransformedShape: CollidingWith<T>,
+    {
+        self.parts()
+            .iter()
+            .enumerate()
+            .filter(|(_, part)| part.colliding_with(other))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Like [`ContactWith::contact_with`], also returning the index of the deepest part hit.
+    pub fn contact_part<T>(&self, other: &T) -> Option<(usize, Contact)>
+    where
+        TransformedShape: ContactWith<T>,
+    {
+        self.parts()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, part)| Some((index, part.contact_with(other)?)))
+            .max_by(|(_, a), (_, b)| a.depth.total_cmp(&b.depth))
+    }
+
+    /// Like [`Raycast::raycast`], also returning the index of the part hit first.
+    pub fn raycast_part(&self, ray: &Ray2) -> Option<(usize, RayHit)> {
+        self.parts()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, part)| Some((index, part.raycast(ray)?)))
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    const EPSILON: f32 = 0.0001;
+
+    /// A boss with a body and a weak spot sticking out to the right.
+    fn boss() -> Shape {
+        Shape::compound([
+            (
+                Transform2::new(),
+                Shape::Aabb {
+                    size: Vec2::splat(2.),
+                },
+            ),
+            (Transform2::from_xy(2., 0.), Shape::Circle { radius: 1. }),
+        ])
+    }
+
+    #[test]
+    fn compound_bounding_aabb() {
+        let Shape::Compound(compound) = boss() else {
+            unreachable!()
+        };
+        let local_aabb = compound.local_aabb().unwrap();
+        assert!(local_aabb.min().abs_diff_eq(Vec2::new(-1., -1.), EPSILON));
+        assert!(local_aabb.max().abs_diff_eq(Vec2::new(2.5, 1.), EPSILON));
+
+        let aabb = boss()
+            .transformed_by(Transform2::from_xy(10., 0.).with_rotation(FRAC_PI_2))
+            .bounding_aabb()
+            .unwrap();
+        assert!(aabb.min().abs_diff_eq(Vec2::new(9., -1.), EPSILON));
+        assert!(aabb.max().abs_diff_eq(Vec2::new(11., 2.5), EPSILON));
+        let stretched = Shape::compound([(
+            Transform2::from_rotation(FRAC_PI_2),
+            Shape::Aabb {
+                size: Vec2::new(1., 0.1),
+            },
+        )])
+        .transformed_by(Transform2::from_scale(Vec2::new(10., 1.)))
+        .bounding_aabb()
+        .unwrap();
+        assert!(stretched.max().y >= 5. - EPSILON);
+        assert!(stretched.min().y <= -5. + EPSILON);
+        assert!(Shape::Compound(CompoundShape::default())
+            .at(Vec2::ZERO)
+            .bounding_aabb()
+            .is_none());
+    }
+
+    #[test]
+    fn compound_queries() {
+        let boss = boss().at(Vec2::new(10., 0.));
+        let weak_spot = Vec2::new(12., 0.2);
+        assert!(boss.contains_point(Vec2::new(10.5, 0.)));
+        assert!(boss.contains_point(weak_spot));
+        assert!(!boss.contains_point(Vec2::new(11.2, 0.9)));
+
+        let bullet = Circle {
+            position: weak_spot,
+            radius: 0.2,
+        };
+        assert!(boss.colliding_with(&bullet));
+        assert!(bullet.colliding_with(&boss));
+        assert_eq!(boss.colliding_parts(&bullet), vec![1]);
+        assert_eq!(
+            boss.colliding_parts(&Point {
+                position: weak_spot
+            }),
+            vec![1]
+        );
+        assert!(boss
+            .colliding_parts(&Circle {
+                position: Vec2::new(20., 0.),
+                radius: 1.,
+            })
+            .is_empty());
+
+        let (part, contact) = boss
+            .contact_part(&Shape::Point.at(Vec2::new(10., 0.8)))
+            .unwrap();
+        assert_eq!(part, 0);
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPSILON));
+        assert!((contact.depth - 0.2).abs() < EPSILON);
+        assert!(boss.contact_with(&bullet).is_some());
+        assert!(bullet.contact_with(&boss).is_some());
+    }
+
+    #[test]
+    fn compound_against_compound() {
+        let a = boss().at(Vec2::ZERO);
+        let b = boss().at(Vec2::new(3.4, 0.));
+        assert!(a.colliding_with(&b));
+        assert!(b.colliding_with(&a));
+        assert_eq!(a.colliding_parts(&b), vec![1]);
+        assert_eq!(b.colliding_parts(&a), vec![0]);
+        assert!(!a.colliding_with(&boss().at(Vec2::new(0., 5.))));
+    }
+
+    #[test]
+    fn compound_raycast_and_shape_cast() {
+        let boss = boss().at(Vec2::new(10., 0.));
+        let (part, hit) = boss
+            .raycast_part(&Ray2::new(Vec2::new(20., 0.), Vec2::NEG_X))
+            .unwrap();
+        assert_eq!(part, 1);
+        assert!((hit.distance - 7.5).abs() < EPSILON);
+        let (part, hit) = boss.raycast_part(&Ray2::new(Vec2::ZERO, Vec2::X)).unwrap();
+        assert_eq!(part, 0);
+        assert!((hit.distance - 9.).abs() < EPSILON);
+
+        let wall = TransformedShape::from(Aabb {
+            position: Vec2::new(20., 0.),
+            size: Vec2::splat(2.),
+        });
+        let hit = boss.shape_cast(Vec2::new(10., 0.), &wall).unwrap();
+        assert!((hit.distance - 6.5).abs() < EPSILON);
+        assert!(hit.normal.abs_diff_eq(Vec2::NEG_X, EPSILON));
+        let hit = wall.shape_cast(Vec2::new(-10., 0.), &boss).unwrap();
+        assert!((hit.distance - 6.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn compound_parts_follow_transform() {
+        let boss = boss().transformed_by(
+            Transform2::from_xy(0., 0.)
+                .with_rotation(FRAC_PI_2)
+                .with_scale(Vec2::splat(2.)),
+        );
+        let parts = boss.parts();
+        assert_eq!(parts.len(), 2);
+        assert!(parts[1]
+            .transform
+            .translation
+            .abs_diff_eq(Vec2::new(0., 4.), EPSILON));
+        assert!(boss.contains_point(Vec2::new(0., 4.5)));
+        assert!(!boss.contains_point(Vec2::new(4., 0.)));
+    }
+}
@@ -0,0 +1,292 @@
+use bevy::prelude::*;
+
+use super::{
+    compound::deepest_contact, convex::ToConvex, Aabb, Capsule, Circle, Ellipse, Obb, Point,
+    Polygon, Segment, TransformedShape,
+};
+
+/// How two colliding shapes overlap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    /// Unit vector pointing from the first shape towards the second. Moving the second shape by
+    /// `normal * depth`, or the first by `-normal * depth`, separates them.
+    pub normal: Vec2,
+    /// How far the shapes overlap along `normal`.
+    pub depth: f32,
+    /// One or two points where the shapes touch, halfway between their surfaces.
+    pub points: Vec<Vec2>,
+}
+
+impl Contact {
+    /// The same contact seen from the second shape.
+    pub fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            ..self
+        }
+    }
+}
+
+/// Like [`CollidingWith`](super::CollidingWith), but returns how the shapes overlap. Returns
+/// `Some` exactly when `colliding_with` returns `true`.
+pub trait ContactWith<T> {
+    fn contact_with(&self, other: &T) -> Option<Contact>;
+}
+
+macro_rules! impl_contact_with_convex {
+    ($($a:ty),+) => {
+        impl_contact_with_convex!(@outer [$($a),+] [$($a),+]);
+    };
+    (@outer [$($a:ty),+] $b:tt) => {
+        $(impl_contact_with_convex!(@inner $a $b);)+
+    };
+    (@inner $a:ty [$($b:ty),+]) => {
+        $(
+            impl ContactWith<$b> for $a {
+                fn contact_with(&self, other: &$b) -> Option<Contact> {
+                    self.to_convex().contact(&other.to_convex())
+                }
+            }
+        )+
+    };
+}
+
+impl_contact_with_convex!(Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule);
+
+macro_rules! impl_transformed_shape_contact_with {
+    ($($primitive:ty),+) => {
+        $(
+            impl ContactWith<$primitive> for TransformedShape {
+                fn contact_with(&self, other: &$primitive) -> Option<Contact> {
+                    transformed_shape_to_shape!(
+                        self,
+                        a,
+                        a.contact_with(other),
+                        None,
+                        near Some(other.bounding_aabb()),
+                        |parts| deepest_contact(parts.filter_map(|part| part.contact_with(other)))
+                    )
+                }
+            }
+
+            impl ContactWith<TransformedShape> for $primitive {
+                fn contact_with(&self, other: &TransformedShape) -> Option<Contact> {
+                    other.contact_with(self).map(Contact::flipped)
+                }
+            }
+        )+
+    };
+}
+
+impl_transformed_shape_contact_with!(Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule);
+
+impl ContactWith<TransformedShape> for TransformedShape {
+    fn contact_with(&self, other: &TransformedShape) -> Option<Contact> {
+        transformed_shape_to_shape!(
+            self,
+            a,
+            transformed_shape_to_shape!(
+                other,
+                b,
+                a.contact_with(&b),
+                None,
+                near Some(a.bounding_aabb()),
+                |parts| deepest_contact(parts.filter_map(|part| a.contact_with(&part)))
+            ),
+            None,
+            near other.bounding_aabb(),
+            |parts| deepest_contact(parts.filter_map(|part| part.contact_with(other)))
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_4;
+
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    const EPSILON: f32 = 0.0001;
+
+    #[test]
+    fn contact_aabb() {
+        let a = Aabb {
+            position: Vec2::ZERO,
+            size: Vec2::splat(2.),
+        };
+        let b = Aabb {
+            position: Vec2::new(1.5, 0.5),
+            size: Vec2::splat(2.),
+        };
+        let contact = a.contact_with(&b).unwrap();
+        assert_eq!(contact.normal, Vec2::X);
+        assert!((contact.depth - 0.5).abs() < EPSILON);
+        assert_eq!(contact.points.len(), 2);
+        assert!(contact.points[0].abs_diff_eq(Vec2::new(0.75, -0.5), EPSILON));
+        assert!(contact.points[1].abs_diff_eq(Vec2::new(0.75, 1.), EPSILON));
+        assert_eq!(b.contact_with(&a).unwrap().normal, -Vec2::X);
+    }
+
+    #[test]
+    fn contact_circle() {
+        let a = Circle {
+            position: Vec2::ZERO,
+            radius: 2.,
+        };
+        let b = Circle {
+            position: Vec2::new(0., 1.5),
+            radius: 2.,
+        };
+        let contact = a.contact_with(&b).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPSILON));
+        assert!((contact.depth - 0.5).abs() < EPSILON);
+        assert_eq!(contact.points.len(), 1);
+        assert!(contact.points[0].abs_diff_eq(Vec2::new(0., 0.75), EPSILON));
+        let wall = Aabb {
+            position: Vec2::new(1.5, 0.),
+            size: Vec2::new(2., 10.),
+        };
+        let contact = a.contact_with(&wall).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::X, EPSILON));
+        assert!((contact.depth - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn contact_capsule_resting() {
+        let floor = Aabb {
+            position: Vec2::new(0., -1.),
+            size: Vec2::new(10., 2.),
+        };
+        let body = Capsule {
+            start: Vec2::new(-1., 0.4),
+            end: Vec2::new(1., 0.4),
+            radius: 1.,
+        };
+        let contact = floor.contact_with(&body).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPSILON));
+        assert!((contact.depth - 0.1).abs() < EPSILON);
+        assert_eq!(contact.points.len(), 2);
+        for point in [Vec2::new(-1., -0.05), Vec2::new(1., -0.05)] {
+            assert!(contact
+                .points
+                .iter()
+                .any(|contact_point| contact_point.abs_diff_eq(point, EPSILON)));
+        }
+    }
+
+    #[test]
+    fn contact_transformed_shape() {
+        let a = Shape::Aabb {
+            size: Vec2::splat(2.),
+        }
+        .transformed_by(Transform2::from_rotation(FRAC_PI_4));
+        let b = Shape::Point.at(Vec2::new(1.2, 0.));
+        let contact = a.contact_with(&b).unwrap();
+        assert!(
+            contact
+                .normal
+                .abs_diff_eq(Vec2::new(1., 1.).normalize(), EPSILON)
+                || contact
+                    .normal
+                    .abs_diff_eq(Vec2::new(1., -1.).normalize(), EPSILON)
+        );
+        assert!((contact.depth - (2_f32.sqrt() - 1.2) / 2_f32.sqrt()).abs() < EPSILON);
+        assert_eq!(
+            b.contact_with(&a).unwrap().normal,
+            -a.contact_with(&b).unwrap().normal
+        );
+    }
+
+    /// Every shape type in a few positions around the origin.
+    fn samples(offset: Vec2) -> Vec<TransformedShape> {
+        let mut samples = vec![];
+        for position in [Vec2::ZERO, Vec2::new(0.9, 0.3), Vec2::new(1.6, -1.1)] {
+            let position = position + offset;
+            samples.push(
+                Circle {
+                    position,
+                    radius: 1.2,
+                }
+                .into(),
+            );
+            samples.push(
+                Aabb {
+                    position,
+                    size: Vec2::new(1., 0.6),
+                }
+                .into(),
+            );
+            samples.push(
+                Shape::Aabb {
+                    size: Vec2::new(1., 0.6),
+                }
+                .transformed_by(Transform2::from_translation(position).with_rotation(0.7)),
+            );
+            samples.push(Shape::Circle { radius: 1. }.transformed_by(
+                Transform2::from_translation(position).with_scale(Vec2::new(1.5, 0.5)),
+            ));
+            samples.push(
+                Polygon::new([position, position + Vec2::X, position + Vec2::Y])
+                    .unwrap()
+                    .into(),
+            );
+            samples.push(Point { position }.into());
+            samples.push(
+                Segment {
+                    start: position - Vec2::new(0.5, 0.2),
+                    end: position + Vec2::new(0.5, 0.2),
+                }
+                .into(),
+            );
+            samples.push(
+                Capsule {
+                    start: position - Vec2::new(0.2, 0.4),
+                    end: position + Vec2::new(0.2, 0.4),
+                    radius: 0.5,
+                }
+                .into(),
+            );
+        }
+        samples
+    }
+
+    #[test]
+    fn contact_agrees_with_colliding_with() {
+        let a_samples = samples(Vec2::ZERO);
+        let b_samples = samples(Vec2::new(0.2, 0.1));
+        let mut contacts = 0;
+        for a in a_samples.iter() {
+            for b in b_samples.iter() {
+                let contact = a.contact_with(b);
+                assert_eq!(contact.is_some(), a.colliding_with(b), "{:?} {:?}", a, b);
+                if let Some(contact) = contact {
+                    contacts += 1;
+                    assert!((contact.normal.length() - 1.).abs() < EPSILON);
+                    assert!(contact.depth >= 0.);
+                    assert!(!contact.points.is_empty() && contact.points.len() <= 2);
+                }
+            }
+        }
+        assert!(contacts > 0);
+    }
+
+    #[test]
+    fn contact_separates() {
+        let a_samples = samples(Vec2::ZERO);
+        let b_samples = samples(Vec2::new(0.5, 0.5));
+        for a in a_samples.iter() {
+            for b in b_samples.iter() {
+                if let Some(contact) = a.contact_with(b) {
+                    let mut separated = b.clone();
+                    separated.transform.translation += contact.normal * (contact.depth + 0.001);
+                    assert!(!a.colliding_with(&separated), "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use super::{Aabb, Circle, TransformedShape};
+use super::{
+    convex::closest_point_on_segment, Aabb, Capsule, Circle, Ellipse, Obb, Point, Polygon, Segment,
+    Shape, TransformedShape,
+};
 
 pub trait ContainsPoint {
     fn contains_point(&self, point: Vec2) -> bool;
@@ -21,17 +24,73 @@ impl ContainsPoint for Aabb {
     }
 }
 
+impl ContainsPoint for Obb {
+    fn contains_point(&self, point: Vec2) -> bool {
+        let local = Vec2::from_angle(-self.rotation).rotate(point - self.position);
+        local.x.abs() < self.size.x * 0.5 && local.y.abs() < self.size.y * 0.5
+    }
+}
+
+impl ContainsPoint for Ellipse {
+    fn contains_point(&self, point: Vec2) -> bool {
+        let local = Vec2::from_angle(-self.rotation).rotate(point - self.position);
+        (local / (self.size * 0.5)).length_squared() < 1.
+    }
+}
+
+impl ContainsPoint for Polygon {
+    fn contains_point(&self, point: Vec2) -> bool {
+        let points = self.points();
+        (0..points.len()).all(|i| {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            (b - a).perp_dot(point - a) > 0.
+        })
+    }
+}
+
+impl ContainsPoint for Point {
+    fn contains_point(&self, _point: Vec2) -> bool {
+        false
+    }
+}
+
+impl ContainsPoint for Segment {
+    fn contains_point(&self, _point: Vec2) -> bool {
+        false
+    }
+}
+
+impl ContainsPoint for Capsule {
+    fn contains_point(&self, point: Vec2) -> bool {
+        closest_point_on_segment(point, self.start, self.end).distance(point) < self.radius * 0.5
+    }
+}
+
 impl ContainsPoint for TransformedShape {
     fn contains_point(&self, point: Vec2) -> bool {
-        transformed_shape_to_shape!(self, shape, shape.contains_point(point), false)
+        if let Shape::TileGrid(grid) = &self.shape {
+            return grid.contains_point(&self.transform, point);
+        }
+        transformed_shape_to_shape!(
+            self,
+            shape,
+            shape.contains_point(point),
+            false,
+            |compound| compound
+                .transformed_parts(&self.transform)
+                .any(|part| part.contains_point(point))
+        )
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
     use bevy::prelude::*;
 
-    use crate::geometry::prelude::*;
+    use crate::{geometry::prelude::*, transform2::Transform2};
 
     #[test]
     fn contains_point_circle() {
@@ -59,4 +118,68 @@ mod test {
         assert!(a.contains_point(Vec2::splat(0.25)));
         assert!(!a.contains_point(Vec2::splat(0.75)));
     }
+
+    #[test]
+    fn contains_point_obb() {
+        let a = Obb {
+            position: Vec2::ZERO,
+            size: Vec2::new(2., 0.2),
+            rotation: FRAC_PI_4,
+        };
+        assert!(a.contains_point(Vec2::splat(0.5)));
+        assert!(!a.contains_point(Vec2::new(0.5, -0.5)));
+    }
+
+    #[test]
+    fn contains_point_ellipse() {
+        let a = Ellipse {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 1.),
+            rotation: FRAC_PI_2,
+        };
+        assert!(a.contains_point(Vec2::new(0., 1.9)));
+        assert!(!a.contains_point(Vec2::new(1.9, 0.)));
+    }
+
+    #[test]
+    fn transformed_shape_contains_point_rotated_scaled() {
+        let a = Shape::Aabb { size: Vec2::ONE }
+            .transformed_by(Transform2::from_rotation(FRAC_PI_4).with_scale(Vec2::new(4., 1.)));
+        assert!(a.contains_point(Vec2::splat(1.2)));
+        assert!(!a.contains_point(Vec2::new(1.2, -1.2)));
+        let b = Shape::Circle { radius: 1. }
+            .transformed_by(Transform2::from_xy(1., 1.).with_scale(Vec2::new(1., 4.)));
+        assert!(b.contains_point(Vec2::new(1., 2.9)));
+        assert!(!b.contains_point(Vec2::new(1.6, 1.)));
+    }
+
+    #[test]
+    fn contains_point_polygon() {
+        let a = Polygon::new([Vec2::ZERO, Vec2::new(2., 0.), Vec2::new(0., 2.)]).unwrap();
+        assert!(a.contains_point(Vec2::splat(0.5)));
+        assert!(!a.contains_point(Vec2::splat(1.5)));
+        let b = Shape::Polygon(a).transformed_by(Transform2::from_rotation(FRAC_PI_2));
+        assert!(b.contains_point(Vec2::new(-0.5, 0.5)));
+        assert!(!b.contains_point(Vec2::splat(0.5)));
+    }
+
+    #[test]
+    fn contains_point_capsule() {
+        let a = Capsule {
+            start: Vec2::new(-1., 0.),
+            end: Vec2::new(1., 0.),
+            radius: 1.,
+        };
+        assert!(a.contains_point(Vec2::new(1.4, 0.)));
+        assert!(a.contains_point(Vec2::new(0., -0.4)));
+        assert!(!a.contains_point(Vec2::new(1.4, 0.4)));
+        let b = Shape::Capsule {
+            height: 3.,
+            radius: 1.,
+        }
+        .transformed_by(Transform2::from_rotation(FRAC_PI_2));
+        assert!(b.contains_point(Vec2::new(1.4, 0.)));
+        assert!(!b.contains_point(Vec2::new(0., 0.6)));
+        assert!(!Shape::Point.at(Vec2::ZERO).contains_point(Vec2::ZERO));
+    }
 }
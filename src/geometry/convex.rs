@@ -0,0 +1,644 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use super::{Aabb, Capsule, Circle, Contact, Ellipse, Obb, Point, Polygon, Segment};
+
+/// Number of vertices used when an ellipse is approximated by a polygon.
+pub(crate) const ELLIPSE_SEGMENTS: usize = 32;
+
+/// Internal representation shared by every primitive for the generic collision algorithms: a
+/// convex core (a point, a segment, or a counter-clockwise polygon) grown by a radius.
+///
+/// Circles are a point with a radius, boxes are polygons with no radius, and so on. Shapes that
+/// don't fit this model exactly, such as ellipses, are approximated by a polygon.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Convex {
+    pub points: Vec<Vec2>,
+    pub radius: f32,
+}
+
+pub(crate) trait ToConvex {
+    fn to_convex(&self) -> Convex;
+
+    fn bounding_aabb(&self) -> Aabb {
+        self.to_convex().aabb()
+    }
+}
+
+impl Convex {
+    pub fn point(point: Vec2, radius: f32) -> Self {
+        Self {
+            points: vec![point],
+            radius,
+        }
+    }
+
+    pub fn polygon(points: Vec<Vec2>) -> Self {
+        Self { points, radius: 0. }
+    }
+
+    /// Polygon approximation of an ellipse with semi-axes `radii`, rotated by `rotation`.
+    pub fn ellipse(position: Vec2, radii: Vec2, rotation: f32) -> Self {
+        let rotation = Vec2::from_angle(rotation);
+        Self::polygon(
+            (0..ELLIPSE_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / ELLIPSE_SEGMENTS as f32 * TAU;
+                    position + rotation.rotate(Vec2::from_angle(angle) * radii)
+                })
+                .collect(),
+        )
+    }
+
+    /// Iterates over the edges of the core. A point has a single zero length edge, and a segment
+    /// has a single edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let count = match self.points.len() {
+            1 => 1,
+            2 => 1,
+            count => count,
+        };
+        (0..count).map(|i| (self.points[i], self.points[(i + 1) % self.points.len()]))
+    }
+
+    /// Candidate separating axes of the core. Segments also use their direction, since two
+    /// collinear segments can only be separated along it.
+    fn axes(&self) -> Vec<Vec2> {
+        match self.points.len() {
+            1 => vec![],
+            2 => {
+                let direction = (self.points[1] - self.points[0]).normalize_or_zero();
+                if direction == Vec2::ZERO {
+                    vec![]
+                } else {
+                    vec![direction.perp(), direction]
+                }
+            }
+            _ => self
+                .edges()
+                .filter_map(|(a, b)| {
+                    let normal = (b - a).perp().normalize_or_zero();
+                    (normal != Vec2::ZERO).then(|| -normal)
+                })
+                .collect(),
+        }
+    }
+
+    /// Minimum and maximum of the core projected onto `axis`.
+    pub fn project(&self, axis: Vec2) -> (f32, f32) {
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        for point in self.points.iter() {
+            let projection = point.dot(axis);
+            min = min.min(projection);
+            max = max.max(projection);
+        }
+        (min, max)
+    }
+
+    /// Runs the separating axis test on the cores, ignoring the radii. Returns the axis of least
+    /// penetration, pointing from `self` to `other`, and how far `other` has to move along it to
+    /// separate the cores. Touching cores overlap by zero.
+    pub fn sat(&self, other: &Convex) -> Option<(Vec2, f32)> {
+        self.penetration(other, true)
+    }
+
+    /// Like [`Convex::sat`], but also returns the axis of least separation, with a negative
+    /// overlap, when the cores are apart. Returns `None` for two separate points.
+    fn penetration(&self, other: &Convex, stop_when_separated: bool) -> Option<(Vec2, f32)> {
+        let mut axes = self.axes();
+        axes.extend(other.axes());
+        if axes.is_empty() {
+            let difference = other.points[0] - self.points[0];
+            return (difference == Vec2::ZERO).then_some((Vec2::X, 0.));
+        }
+        let mut best: Option<(Vec2, f32)> = None;
+        for axis in axes.into_iter() {
+            let (self_min, self_max) = self.project(axis);
+            let (other_min, other_max) = other.project(axis);
+            let forward = self_max - other_min;
+            let backward = other_max - self_min;
+            let overlap = forward.min(backward);
+            if overlap < 0. && stop_when_separated {
+                return None;
+            }
+            if best
+                .map(|(_, best_overlap)| overlap < best_overlap)
+                .unwrap_or(true)
+            {
+                let direction = if forward <= backward { axis } else { -axis };
+                best = Some((direction, overlap));
+            }
+        }
+        best
+    }
+
+    /// Closest points between the cores, assuming they don't intersect. Returns the distance and
+    /// the closest point on each core.
+    pub fn closest_points(&self, other: &Convex) -> (f32, Vec2, Vec2) {
+        let mut best = (f32::INFINITY, Vec2::ZERO, Vec2::ZERO);
+        for point in self.points.iter() {
+            for (a, b) in other.edges() {
+                let closest = closest_point_on_segment(*point, a, b);
+                let distance = point.distance(closest);
+                if distance < best.0 {
+                    best = (distance, *point, closest);
+                }
+            }
+        }
+        for point in other.points.iter() {
+            for (a, b) in self.edges() {
+                let closest = closest_point_on_segment(*point, a, b);
+                let distance = point.distance(closest);
+                if distance < best.0 {
+                    best = (distance, closest, *point);
+                }
+            }
+        }
+        best
+    }
+
+    /// Distance between the cores, or zero if they intersect.
+    pub fn core_distance(&self, other: &Convex) -> f32 {
+        if self.sat(other).is_some() {
+            0.
+        } else {
+            self.closest_points(other).0
+        }
+    }
+
+    /// Points of the core that are furthest along `direction`, as a range of positions along
+    /// `direction.perp()`. A single point has an empty range.
+    fn feature(&self, direction: Vec2) -> (f32, f32) {
+        let (_, max) = self.project(direction);
+        let tolerance = 0.0001 * (1. + max.abs());
+        let tangent = direction.perp();
+        let mut range = (f32::INFINITY, f32::NEG_INFINITY);
+        for point in self.points.iter() {
+            if point.dot(direction) >= max - tolerance {
+                let position = point.dot(tangent);
+                range = (range.0.min(position), range.1.max(position));
+            }
+        }
+        range
+    }
+
+    /// Contact between the shapes, if they collide. Agrees with [`Convex::colliding_with`].
+    pub fn contact(&self, other: &Convex) -> Option<Contact> {
+        let radius = self.radius + other.radius;
+        let (normal, depth) = if let Some((axis, overlap)) = self.sat(other) {
+            (axis, overlap + radius)
+        } else if radius > 0. {
+            let (distance, a, b) = self.closest_points(other);
+            if distance >= radius {
+                return None;
+            }
+            match self.penetration(other, false) {
+                // The direction between the closest points is unreliable when the cores almost
+                // touch, so the separating axis is used instead.
+                Some((axis, overlap)) if distance < radius * 0.001 => (axis, overlap + radius),
+                _ => ((b - a) / distance, radius - distance),
+            }
+        } else {
+            return None;
+        };
+        let self_feature = self.feature(normal);
+        let other_feature = other.feature(-normal);
+        // `feature` measures positions along `(-normal).perp()` for `other`, which is flipped.
+        let other_feature = (-other_feature.1, -other_feature.0);
+        let start = self_feature.0.max(other_feature.0);
+        let end = self_feature.1.min(other_feature.1);
+        let surface =
+            (self.project(normal).1 + self.radius + other.project(normal).0 - other.radius) * 0.5;
+        let tangent = normal.perp();
+        let points = if end - start > 0.0001 * (1. + start.abs().max(end.abs())) {
+            vec![
+                normal * surface + tangent * start,
+                normal * surface + tangent * end,
+            ]
+        } else {
+            vec![normal * surface + tangent * (start + end) * 0.5]
+        };
+        Some(Contact {
+            normal,
+            depth,
+            points,
+        })
+    }
+
+    pub fn colliding_with(&self, other: &Convex) -> bool {
+        let radius = self.radius + other.radius;
+        if radius > 0. {
+            self.core_distance(other) < radius
+        } else {
+            self.sat(other).is_some()
+        }
+    }
+
+    /// Bounding box of the shape.
+    pub fn aabb(&self) -> Aabb {
+        let (min_x, max_x) = self.project(Vec2::X);
+        let (min_y, max_y) = self.project(Vec2::Y);
+        Aabb::from_min_max(
+            Vec2::new(min_x, min_y) - self.radius,
+            Vec2::new(max_x, max_y) + self.radius,
+        )
+    }
+
+    /// Whether `point` is strictly inside the shape. Points and segments without a radius contain
+    /// nothing.
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        let inside_core =
+            self.points.len() >= 3 && self.edges().all(|(a, b)| (b - a).perp_dot(point - a) > 0.);
+        inside_core
+            || self.radius > 0.
+                && self.edges().any(|(a, b)| {
+                    closest_point_on_segment(point, a, b).distance(point) < self.radius
+                })
+    }
+
+    /// Distance from `point` to the surface of the shape, negative when the point is inside.
+    pub fn signed_distance(&self, point: Vec2) -> f32 {
+        let core_distance = self
+            .edges()
+            .map(|(a, b)| closest_point_on_segment(point, a, b).distance(point))
+            .fold(f32::INFINITY, f32::min);
+        if self.core_contains_point(point) {
+            -core_distance - self.radius
+        } else {
+            core_distance - self.radius
+        }
+    }
+
+    /// Point of the shape closest to `point`, which is `point` itself when it is inside.
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        if self.core_contains_point(point) {
+            return point;
+        }
+        let closest = self
+            .edges()
+            .map(|(a, b)| closest_point_on_segment(point, a, b))
+            .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+            .unwrap_or(point);
+        let offset = point - closest;
+        if offset.length() <= self.radius {
+            point
+        } else {
+            closest + offset.normalize() * self.radius
+        }
+    }
+
+    /// Distance between the surfaces of the shapes, or zero if they intersect.
+    pub fn distance(&self, other: &Convex) -> f32 {
+        (self.core_distance(other) - self.radius - other.radius).max(0.)
+    }
+
+    fn core_contains_point(&self, point: Vec2) -> bool {
+        self.points.len() >= 3 && self.edges().all(|(a, b)| (b - a).perp_dot(point - a) >= 0.)
+    }
+
+    /// Casts a ray with a unit `direction` against the shape. Returns the distance along the ray
+    /// and the surface normal at the hit. Rays starting inside the shape hit at distance zero,
+    /// with the normal facing back along the ray.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<(f32, Vec2)> {
+        if self.contains_point(origin) {
+            return Some((0., -direction));
+        }
+        let mut best: Option<(f32, Vec2)> = None;
+        let mut consider = |hit: Option<(f32, Vec2)>| {
+            if let Some((distance, normal)) = hit {
+                if distance <= max_distance
+                    && best
+                        .map(|(best_distance, _)| distance < best_distance)
+                        .unwrap_or(true)
+                {
+                    best = Some((distance, normal));
+                }
+            }
+        };
+        if self.radius > 0. || self.points.len() < 3 {
+            for point in self.points.iter() {
+                consider(raycast_circle(origin, direction, *point, self.radius));
+            }
+        }
+        for (a, b) in self.edges() {
+            let normal = -(b - a).perp().normalize_or_zero();
+            if normal == Vec2::ZERO {
+                continue;
+            }
+            consider(raycast_segment(
+                origin,
+                direction,
+                a + normal * self.radius,
+                b + normal * self.radius,
+                normal,
+            ));
+            if self.points.len() == 2 {
+                consider(raycast_segment(
+                    origin,
+                    direction,
+                    a - normal * self.radius,
+                    b - normal * self.radius,
+                    -normal,
+                ));
+            }
+        }
+        best
+    }
+
+    /// Counter-clockwise outline of the shape. The rounded corners of shapes with a radius are
+    /// approximated with up to [`ELLIPSE_SEGMENTS`] points per full turn. Points and segments
+    /// without a radius return their core.
+    pub fn outline(&self) -> Vec<Vec2> {
+        if self.radius == 0. {
+            return self.points.clone();
+        }
+        let count = self.points.len();
+        if count == 1 {
+            return arc(self.points[0], self.radius, Vec2::X, TAU);
+        }
+        // Segments are treated as a polygon going back and forth, so each end gets a half turn.
+        let normal = |i: usize| {
+            let direction = self.points[(i + 1) % count] - self.points[i];
+            Vec2::new(direction.y, -direction.x).normalize()
+        };
+        let mut outline = vec![];
+        for i in 0..count {
+            let from = normal((i + count - 1) % count);
+            let to = normal(i);
+            // Convex corners turn by less than a half turn, and segment ends by exactly one.
+            let angle = from.angle_between(to).abs();
+            outline.extend(arc(self.points[i], self.radius, from, angle));
+        }
+        outline
+    }
+
+    /// Minkowski difference `other - self`: the shape containing every offset by which `self`
+    /// can be moved to overlap `other`.
+    pub fn minkowski_difference(&self, other: &Convex) -> Convex {
+        let mut points = Vec::with_capacity(self.points.len() * other.points.len());
+        for a in self.points.iter() {
+            for b in other.points.iter() {
+                points.push(*b - *a);
+            }
+        }
+        Convex {
+            points: convex_hull(points),
+            radius: self.radius + other.radius,
+        }
+    }
+}
+
+/// Ray against the side of a segment facing `normal`.
+fn raycast_segment(
+    origin: Vec2,
+    direction: Vec2,
+    a: Vec2,
+    b: Vec2,
+    normal: Vec2,
+) -> Option<(f32, Vec2)> {
+    if direction.dot(normal) >= 0. {
+        return None;
+    }
+    let edge = b - a;
+    let denominator = direction.perp_dot(edge);
+    if denominator == 0. {
+        return None;
+    }
+    let distance = (a - origin).perp_dot(edge) / denominator;
+    let along = (a - origin).perp_dot(direction) / denominator;
+    (distance >= 0. && (0. ..=1.).contains(&along)).then_some((distance, normal))
+}
+
+fn raycast_circle(origin: Vec2, direction: Vec2, center: Vec2, radius: f32) -> Option<(f32, Vec2)> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.length_squared() - radius * radius;
+    if c > 0. && b > 0. {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < 0. {
+        return None;
+    }
+    let distance = (-b - discriminant.sqrt()).max(0.);
+    let normal = (origin + direction * distance - center)
+        .try_normalize()
+        .unwrap_or(-direction);
+    Some((distance, normal))
+}
+
+/// Convex hull of a set of points in counter-clockwise order, using the monotone chain
+/// algorithm. Collinear points are dropped, so the hull may be a segment or a single point.
+pub(crate) fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() + 1);
+    for chain in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in chain {
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2])
+                    .perp_dot(point - hull[hull.len() - 2])
+                    <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+impl ToConvex for Circle {
+    fn to_convex(&self) -> Convex {
+        Convex::point(self.position, self.radius * 0.5)
+    }
+}
+
+impl ToConvex for Aabb {
+    fn to_convex(&self) -> Convex {
+        Obb {
+            position: self.position,
+            size: self.size,
+            rotation: 0.,
+        }
+        .to_convex()
+    }
+}
+
+impl ToConvex for Obb {
+    fn to_convex(&self) -> Convex {
+        Convex::polygon(self.corners().to_vec())
+    }
+}
+
+impl ToConvex for Ellipse {
+    fn to_convex(&self) -> Convex {
+        if self.size.x == self.size.y {
+            Convex::point(self.position, self.size.x * 0.5)
+        } else {
+            Convex::ellipse(self.position, self.size * 0.5, self.rotation)
+        }
+    }
+
+    /// Exact, unlike the box of the polygon approximation which can be slightly too small.
+    fn bounding_aabb(&self) -> Aabb {
+        let (sin, cos) = self.rotation.sin_cos();
+        let radii = self.size * 0.5;
+        let half_size = Vec2::new(
+            (radii.x * cos).hypot(radii.y * sin),
+            (radii.x * sin).hypot(radii.y * cos),
+        );
+        Aabb {
+            position: self.position,
+            size: half_size * 2.,
+        }
+    }
+}
+
+impl ToConvex for Polygon {
+    fn to_convex(&self) -> Convex {
+        Convex::polygon(self.points().to_vec())
+    }
+}
+
+impl ToConvex for Point {
+    fn to_convex(&self) -> Convex {
+        Convex::point(self.position, 0.)
+    }
+}
+
+impl ToConvex for Segment {
+    fn to_convex(&self) -> Convex {
+        if self.start == self.end {
+            Convex::point(self.start, 0.)
+        } else {
+            Convex::polygon(vec![self.start, self.end])
+        }
+    }
+}
+
+impl ToConvex for Capsule {
+    fn to_convex(&self) -> Convex {
+        Convex {
+            radius: self.radius * 0.5,
+            ..self.segment().to_convex()
+        }
+    }
+}
+
+/// Points on a circle around `center`, turning counter-clockwise by `angle` from `start`.
+fn arc(center: Vec2, radius: f32, start: Vec2, angle: f32) -> Vec<Vec2> {
+    let steps = (angle / TAU * ELLIPSE_SEGMENTS as f32).ceil().max(1.) as usize;
+    // A full turn would repeat its first point.
+    let last = if angle >= TAU { steps - 1 } else { steps };
+    (0..=last)
+        .map(|step| {
+            center + start.rotate(Vec2::from_angle(angle * step as f32 / steps as f32)) * radius
+        })
+        .collect()
+}
+
+pub(crate) fn closest_point_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared == 0. {
+        return a;
+    }
+    a + ab * ((point - a).dot(ab) / length_squared).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use super::Convex;
+
+    fn square(center: Vec2, half_size: f32) -> Convex {
+        Convex::polygon(vec![
+            center + Vec2::new(-half_size, -half_size),
+            center + Vec2::new(half_size, -half_size),
+            center + Vec2::new(half_size, half_size),
+            center + Vec2::new(-half_size, half_size),
+        ])
+    }
+
+    #[test]
+    fn convex_sat_polygons() {
+        let a = square(Vec2::ZERO, 1.);
+        let b = square(Vec2::new(1.5, 0.2), 1.);
+        let c = square(Vec2::new(2.5, 0.), 0.4);
+        let (axis, overlap) = a.sat(&b).unwrap();
+        assert_eq!(axis, Vec2::X);
+        assert!((overlap - 0.5).abs() < 0.0001);
+        assert!(a.sat(&c).is_none());
+        assert!(a.colliding_with(&b));
+        assert!(!a.colliding_with(&c));
+    }
+
+    #[test]
+    fn convex_degenerate_cores() {
+        let point = Convex::point(Vec2::ZERO, 0.);
+        let same_point = Convex::point(Vec2::ZERO, 0.);
+        let other_point = Convex::point(Vec2::X, 0.);
+        let segment = Convex::polygon(vec![Vec2::new(-1., -1.), Vec2::new(1., 1.)]);
+        let crossing = Convex::polygon(vec![Vec2::new(-1., 1.), Vec2::new(1., -1.)]);
+        let collinear = Convex::polygon(vec![Vec2::new(2., 2.), Vec2::new(3., 3.)]);
+        assert!(point.colliding_with(&same_point));
+        assert!(!point.colliding_with(&other_point));
+        assert!(point.colliding_with(&segment));
+        assert!(!other_point.colliding_with(&segment));
+        assert!(segment.colliding_with(&crossing));
+        assert!(!segment.colliding_with(&collinear));
+    }
+
+    #[test]
+    fn convex_rounded() {
+        let a = Convex::point(Vec2::ZERO, 1.);
+        let b = Convex::polygon(vec![Vec2::new(1.5, -5.), Vec2::new(1.5, 5.)]);
+        assert!(!a.colliding_with(&b));
+        let b = Convex { radius: 0.6, ..b };
+        assert!(a.colliding_with(&b));
+        assert_eq!(a.core_distance(&b), 1.5);
+    }
+
+    #[test]
+    fn convex_outline() {
+        let circle = Convex::point(Vec2::new(1., 1.), 2.).outline();
+        assert_eq!(circle.len(), super::ELLIPSE_SEGMENTS);
+        assert!(circle
+            .iter()
+            .all(|point| (point.distance(Vec2::new(1., 1.)) - 2.).abs() < 0.0001));
+
+        let capsule = Convex {
+            points: vec![Vec2::new(0., -1.), Vec2::new(0., 1.)],
+            radius: 0.5,
+        };
+        let outline = capsule.outline();
+        assert!(outline.len() > super::ELLIPSE_SEGMENTS);
+        assert!(outline
+            .iter()
+            .all(|point| (capsule.signed_distance(*point)).abs() < 0.0001));
+        for corner in [Vec2::new(0.5, -1.), Vec2::new(-0.5, 1.)] {
+            assert!(outline
+                .iter()
+                .any(|point| point.abs_diff_eq(corner, 0.0001)));
+        }
+
+        let rounded_square = Convex {
+            radius: 0.5,
+            ..square(Vec2::ZERO, 1.)
+        };
+        assert!(rounded_square
+            .outline()
+            .iter()
+            .all(|point| (rounded_square.signed_distance(*point)).abs() < 0.0001));
+        assert_eq!(square(Vec2::ZERO, 1.).outline().len(), 4);
+    }
+}
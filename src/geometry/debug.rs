@@ -0,0 +1,283 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::PrimitiveTopology,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+#[cfg(feature = "tinae_cursor")]
+use crate::cursor::Cursor;
+use crate::{fixed_timestep::CoreFixedSet, flow::FlowSet, transform2::Transform2};
+
+use super::{Collider, Collisions, ContainsPoint, GeometrySystem, Ray2, RayHit};
+
+pub(crate) struct GeometryDebugPlugin;
+
+impl Plugin for GeometryDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GeometryDebug>()
+            .add_system(geometry_debug_toggle.run_if(geometry_debug_has_toggle_key))
+            .add_system(
+                geometry_debug_clear_rays
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(CoreFixedSet::PreUpdate),
+            )
+            .add_system(
+                geometry_debug_draw
+                    .in_set(GeometrySystem::Debug)
+                    .in_base_set(FlowSet::Debug)
+                    .run_if(geometry_debug_enabled),
+            )
+            .add_system(
+                geometry_debug_hide
+                    .in_set(GeometrySystem::Debug)
+                    .in_base_set(FlowSet::Debug)
+                    .run_if(not(geometry_debug_enabled)),
+            );
+    }
+}
+
+/// Settings of the debug overlay, which draws colliders with line meshes. It is disabled by
+/// default.
+#[derive(Resource, Debug, Clone)]
+pub struct GeometryDebug {
+    pub enabled: bool,
+    /// Key that turns the overlay on and off.
+    pub toggle_key: Option<KeyCode>,
+    /// Draw contact points and normals of colliding pairs.
+    pub contacts: bool,
+    /// Highlight colliders under the [`Cursor`](crate::cursor::Cursor). Requires the
+    /// `tinae_cursor` feature.
+    pub cursor: bool,
+    pub collider_color: Color,
+    pub colliding_color: Color,
+    pub sensor_color: Color,
+    pub hovered_color: Color,
+    pub contact_color: Color,
+    pub ray_color: Color,
+    pub ray_hit_color: Color,
+    /// Length of drawn normals and size of drawn points, in world units.
+    pub marker_size: f32,
+    /// Depth of the overlay, which should be above everything else.
+    pub depth: f32,
+    /// Ray queries and their results, drawn until the next fixed update. See [`GeometryDebug::ray`].
+    pub rays: Vec<(Ray2, Option<RayHit>)>,
+}
+
+impl Default for GeometryDebug {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: None,
+            contacts: true,
+            cursor: true,
+            collider_color: Color::GREEN,
+            colliding_color: Color::RED,
+            sensor_color: Color::CYAN,
+            hovered_color: Color::YELLOW,
+            contact_color: Color::WHITE,
+            ray_color: Color::BLUE,
+            ray_hit_color: Color::ORANGE,
+            marker_size: 10.,
+            depth: 999.,
+            rays: vec![],
+        }
+    }
+}
+
+impl GeometryDebug {
+    /// Draws a ray query and its result while the overlay is enabled. Rays are kept until the next
+    /// fixed update, so they should be drawn by the fixed update systems that cast them.
+    pub fn ray(&mut self, ray: Ray2, hit: Option<RayHit>) {
+        if self.enabled {
+            self.rays.push((ray, hit));
+        }
+    }
+}
+
+/// The entity holding the overlay mesh.
+#[derive(Component)]
+struct GeometryDebugMesh;
+
+/// Vertices of a line list mesh.
+#[derive(Default)]
+struct DebugLines {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+}
+
+impl DebugLines {
+    fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+        let color = color.as_linear_rgba_f32();
+        self.positions
+            .extend([start.extend(0.).to_array(), end.extend(0.).to_array()]);
+        self.colors.extend([color, color]);
+    }
+
+    /// Draws the outline of a shape. Points are drawn as a cross, and anything else as a closed
+    /// loop.
+    fn outline(&mut self, points: &[Vec2], size: f32, color: Color) {
+        match points {
+            [] => {}
+            [point] => self.cross(*point, size, color),
+            [start, end] => self.line(*start, *end, color),
+            points => {
+                for i in 0..points.len() {
+                    self.line(points[i], points[(i + 1) % points.len()], color);
+                }
+            }
+        }
+    }
+
+    fn cross(&mut self, point: Vec2, size: f32, color: Color) {
+        let half_size = size * 0.5;
+        self.line(
+            point - Vec2::X * half_size,
+            point + Vec2::X * half_size,
+            color,
+        );
+        self.line(
+            point - Vec2::Y * half_size,
+            point + Vec2::Y * half_size,
+            color,
+        );
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh
+    }
+}
+
+fn geometry_debug_enabled(debug: Res<GeometryDebug>) -> bool {
+    debug.enabled
+}
+
+fn geometry_debug_has_toggle_key(debug: Res<GeometryDebug>) -> bool {
+    debug.toggle_key.is_some()
+}
+
+fn geometry_debug_toggle(mut debug: ResMut<GeometryDebug>, keys: Option<Res<Input<KeyCode>>>) {
+    let Some(keys) = keys else {
+        return;
+    };
+    if debug.toggle_key.is_some_and(|key| keys.just_pressed(key)) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+fn geometry_debug_clear_rays(mut debug: ResMut<GeometryDebug>) {
+    if !debug.rays.is_empty() {
+        debug.rays.clear();
+    }
+}
+
+fn geometry_debug_hide(mut mesh_query: Query<&mut Visibility, With<GeometryDebugMesh>>) {
+    for mut visibility in mesh_query.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn geometry_debug_draw(
+    mut commands: Commands,
+    debug: Res<GeometryDebug>,
+    collider_query: Query<(Entity, &Collider, &Transform2)>,
+    mut mesh_query: Query<
+        (&Mesh2dHandle, &mut Visibility, &mut Transform),
+        With<GeometryDebugMesh>,
+    >,
+    collisions: Res<Collisions>,
+    #[cfg(feature = "tinae_cursor")] cursor: Option<Res<Cursor>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mut lines = DebugLines::default();
+    #[cfg(feature = "tinae_cursor")]
+    let cursor_position = cursor
+        .filter(|_| debug.cursor)
+        .map(|cursor| cursor.position);
+    #[cfg(not(feature = "tinae_cursor"))]
+    let cursor_position: Option<Vec2> = None;
+
+    for (entity, collider, transform) in collider_query.iter() {
+        let shape = collider.at(*transform);
+        let hovered = cursor_position.is_some_and(|position| shape.contains_point(position));
+        let color = if hovered {
+            debug.hovered_color
+        } else if collisions.colliding_with(entity).next().is_some()
+            || collisions.triggering(entity).next().is_some()
+        {
+            debug.colliding_color
+        } else if collider.sensor {
+            debug.sensor_color
+        } else {
+            debug.collider_color
+        };
+        for outline in shape.outlines() {
+            lines.outline(&outline, debug.marker_size, color);
+        }
+    }
+
+    if debug.contacts {
+        for (_, _, contact) in collisions.iter() {
+            for point in contact.points.iter() {
+                lines.cross(*point, debug.marker_size * 0.5, debug.contact_color);
+                lines.line(
+                    *point,
+                    *point + contact.normal * debug.marker_size,
+                    debug.contact_color,
+                );
+            }
+        }
+    }
+
+    for (ray, hit) in debug.rays.iter() {
+        match hit {
+            Some(hit) => {
+                lines.line(ray.origin, hit.point, debug.ray_color);
+                lines.cross(hit.point, debug.marker_size * 0.5, debug.ray_hit_color);
+                lines.line(
+                    hit.point,
+                    hit.point + hit.normal * debug.marker_size,
+                    debug.ray_hit_color,
+                );
+            }
+            None => {
+                // Unbounded rays are drawn far enough to leave any reasonable view.
+                let length = ray.max_distance.min(1e5);
+                lines.line(ray.origin, ray.point_at(length), debug.ray_color);
+            }
+        }
+    }
+
+    if let Some(position) = cursor_position {
+        lines.cross(position, debug.marker_size, debug.hovered_color);
+    }
+
+    let mesh = lines.into_mesh();
+    if let Ok((handle, mut visibility, mut transform)) = mesh_query.get_single_mut() {
+        if let Some(debug_mesh) = meshes.get_mut(&handle.0) {
+            *debug_mesh = mesh;
+        }
+        if *visibility != Visibility::Inherited {
+            *visibility = Visibility::Inherited;
+        }
+        if transform.translation.z != debug.depth {
+            transform.translation.z = debug.depth;
+        }
+    } else {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: meshes.add(mesh).into(),
+                material: materials.add(ColorMaterial::from(Color::WHITE)),
+                transform: Transform::from_xyz(0., 0., debug.depth),
+                ..default()
+            },
+            GeometryDebugMesh,
+        ));
+    }
+}
@@ -0,0 +1,380 @@
+use bevy::prelude::*;
+
+use super::{
+    convex::ToConvex, Aabb, Capsule, Circle, Ellipse, Obb, Point, Polygon, Segment,
+    TransformedShape,
+};
+
+/// Distance queries from a point to a shape.
+pub trait DistanceToPoint {
+    /// Distance from `point` to the surface of the shape, negative when the point is inside.
+    fn signed_distance(&self, point: Vec2) -> f32;
+
+    /// Point of the shape closest to `point`, which is `point` itself when it is inside.
+    fn closest_point(&self, point: Vec2) -> Vec2;
+
+    /// Distance from `point` to the shape, or zero if the point is inside.
+    fn distance_to_point(&self, point: Vec2) -> f32 {
+        self.signed_distance(point).max(0.)
+    }
+}
+
+/// Distance between the surfaces of two shapes, or zero if they collide.
+pub trait DistanceTo<T> {
+    fn distance_to(&self, other: &T) -> f32;
+}
+
+impl DistanceToPoint for Circle {
+    fn signed_distance(&self, point: Vec2) -> f32 {
+        self.position.distance(point) - self.radius * 0.5
+    }
+
+    fn closest_point(&self, point: Vec2) -> Vec2 {
+        let offset = point - self.position;
+        if offset.length() <= self.radius * 0.5 {
+            point
+        } else {
+            self.position + offset.normalize() * self.radius * 0.5
+        }
+    }
+}
+
+impl DistanceToPoint for Aabb {
+    fn signed_distance(&self, point: Vec2) -> f32 {
+        let offset_from_corner = (point - self.position).abs() - self.size * 0.5;
+        offset_from_corner.x.max(offset_from_corner.y).min(0.)
+            + offset_from_corner.max(Vec2::ZERO).length()
+    }
+
+    fn closest_point(&self, point: Vec2) -> Vec2 {
+        point.clamp(self.min(), self.max())
+    }
+}
+
+macro_rules! impl_distance_to_point_convex {
+    ($($primitive:ty),+) => {
+        $(
+            impl DistanceToPoint for $primitive {
+                fn signed_distance(&self, point: Vec2) -> f32 {
+                    self.to_convex().signed_distance(point)
+                }
+
+                fn closest_point(&self, point: Vec2) -> Vec2 {
+                    self.to_convex().closest_point(point)
+                }
+            }
+        )+
+    };
+}
+
+// Ellipses are measured against their polygon approximation.
+impl_distance_to_point_convex!(Obb, Ellipse, Polygon, Point, Segment, Capsule);
+
+impl DistanceToPoint for TransformedShape {
+    /// Infinite for [`Shape::None`](super::Shape::None).
+    fn signed_distance(&self, point: Vec2) -> f32 {
+        transformed_shape_to_shape!(
+            @arms self,
+            shape,
+            shape.signed_distance(point),
+            f32::INFINITY,
+            |compound| compound
+                .transformed_parts(&self.transform)
+                .map(|part| part.signed_distance(point))
+                .fold(f32::INFINITY, f32::min),
+            |grid| grid
+                .nearest_part(&self.transform, Some(point_aabb(point)), |part| {
+                    part.signed_distance(point)
+                })
+                .map_or(f32::INFINITY, |(_, distance)| distance)
+        )
+    }
+
+    /// The shape's translation for [`Shape::None`](super::Shape::None).
+    fn closest_point(&self, point: Vec2) -> Vec2 {
+        transformed_shape_to_shape!(
+            @arms self,
+            shape,
+            shape.closest_point(point),
+            self.transform.translation,
+            |compound| compound
+                .transformed_parts(&self.transform)
+                .min_by(|a, b| {
+                    a.signed_distance(point)
+                        .total_cmp(&b.signed_distance(point))
+                })
+                .map(|part| part.closest_point(point))
+                .unwrap_or(self.transform.translation),
+            |grid| grid
+                .nearest_part(&self.transform, Some(point_aabb(point)), |part| {
+                    part.signed_distance(point)
+                })
+                .map_or(self.transform.translation, |(part, _)| part.closest_point(point))
+        )
+    }
+}
+
+/// Empty box around `point`, for the tile grid searches.
+fn point_aabb(point: Vec2) -> Aabb {
+    Aabb {
+        position: point,
+        size: Vec2::ZERO,
+    }
+}
+
+macro_rules! impl_distance_to_convex {
+    ($($a:ty),+) => {
+        impl_distance_to_convex!(@outer [$($a),+] [$($a),+]);
+    };
+    (@outer [$($a:ty),+] $b:tt) => {
+        $(impl_distance_to_convex!(@inner $a $b);)+
+    };
+    (@inner $a:ty [$($b:ty),+]) => {
+        $(
+            impl DistanceTo<$b> for $a {
+                fn distance_to(&self, other: &$b) -> f32 {
+                    self.to_convex().distance(&other.to_convex())
+                }
+            }
+        )+
+    };
+}
+
+impl_distance_to_convex!(Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule);
+
+macro_rules! impl_transformed_shape_distance_to {
+    ($($primitive:ty),+) => {
+        $(
+            impl DistanceTo<$primitive> for TransformedShape {
+                fn distance_to(&self, other: &$primitive) -> f32 {
+                    transformed_shape_to_shape!(
+                        @arms self,
+                        a,
+                        a.distance_to(other),
+                        f32::INFINITY,
+                        |compound| compound
+                            .transformed_parts(&self.transform)
+                            .map(|part| part.distance_to(other))
+                            .fold(f32::INFINITY, f32::min),
+                        |grid| grid
+                            .nearest_part(&self.transform, Some(other.bounding_aabb()), |part| {
+                                part.distance_to(other)
+                            })
+                            .map_or(f32::INFINITY, |(_, distance)| distance)
+                    )
+                }
+            }
+
+            impl DistanceTo<TransformedShape> for $primitive {
+                fn distance_to(&self, other: &TransformedShape) -> f32 {
+                    other.distance_to(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_transformed_shape_distance_to!(Circle, Aabb, Obb, Ellipse, Polygon, Point, Segment, Capsule);
+
+impl DistanceTo<TransformedShape> for TransformedShape {
+    fn distance_to(&self, other: &TransformedShape) -> f32 {
+        transformed_shape_to_shape!(
+            @arms self,
+            a,
+            transformed_shape_to_shape!(
+                @arms other,
+                b,
+                a.distance_to(&b),
+                f32::INFINITY,
+                |compound| compound
+                    .transformed_parts(&other.transform)
+                    .map(|part| a.distance_to(&part))
+                    .fold(f32::INFINITY, f32::min),
+                |grid| grid
+                    .nearest_part(&other.transform, Some(a.bounding_aabb()), |part| {
+                        a.distance_to(part)
+                    })
+                    .map_or(f32::INFINITY, |(_, distance)| distance)
+            ),
+            f32::INFINITY,
+            |compound| compound
+                .transformed_parts(&self.transform)
+                .map(|part| part.distance_to(other))
+                .fold(f32::INFINITY, f32::min),
+            |grid| grid
+                .nearest_part(&self.transform, other.bounding_aabb(), |part| {
+                    part.distance_to(other)
+                })
+                .map_or(f32::INFINITY, |(_, distance)| distance)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_4;
+
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    const EPSILON: f32 = 0.0001;
+
+    #[test]
+    fn circle_distance() {
+        let circle = Circle {
+            position: Vec2::new(1., 1.),
+            radius: 2.,
+        };
+        assert!((circle.signed_distance(Vec2::new(4., 1.)) - 2.).abs() < EPSILON);
+        assert!((circle.signed_distance(Vec2::new(1., 1.)) + 1.).abs() < EPSILON);
+        assert_eq!(circle.distance_to_point(Vec2::new(1., 1.5)), 0.);
+        assert!(circle
+            .closest_point(Vec2::new(1., -3.))
+            .abs_diff_eq(Vec2::new(1., 0.), EPSILON));
+        assert_eq!(circle.closest_point(Vec2::new(1., 1.5)), Vec2::new(1., 1.5));
+    }
+
+    #[test]
+    fn box_distance() {
+        let aabb = Aabb {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 2.),
+        };
+        let obb = Obb {
+            position: Vec2::ZERO,
+            size: Vec2::new(4., 2.),
+            rotation: 0.,
+        };
+        for (point, distance, closest) in [
+            (Vec2::new(5., 0.), 3., Vec2::new(2., 0.)),
+            (Vec2::new(5., 5.), 5., Vec2::new(2., 1.)),
+            (Vec2::new(0., 0.5), -0.5, Vec2::new(0., 0.5)),
+            (Vec2::new(-1.9, 0.), -0.1, Vec2::new(-1.9, 0.)),
+        ] {
+            assert!((aabb.signed_distance(point) - distance).abs() < EPSILON);
+            assert!((obb.signed_distance(point) - distance).abs() < EPSILON);
+            assert!(aabb.closest_point(point).abs_diff_eq(closest, EPSILON));
+            assert!(obb.closest_point(point).abs_diff_eq(closest, EPSILON));
+        }
+    }
+
+    #[test]
+    fn capsule_and_segment_distance() {
+        let capsule = Capsule::vertical(Vec2::ZERO, 4., 2.);
+        assert!((capsule.signed_distance(Vec2::new(3., 0.5)) - 2.).abs() < EPSILON);
+        assert!((capsule.signed_distance(Vec2::new(0., 3.)) - 1.).abs() < EPSILON);
+        assert!((capsule.signed_distance(Vec2::ZERO) + 1.).abs() < EPSILON);
+        assert!(capsule
+            .closest_point(Vec2::new(0., 5.))
+            .abs_diff_eq(Vec2::new(0., 2.), EPSILON));
+
+        let segment = Segment {
+            start: Vec2::ZERO,
+            end: Vec2::new(2., 0.),
+        };
+        assert_eq!(segment.signed_distance(Vec2::new(1., 0.)), 0.);
+        assert!((segment.signed_distance(Vec2::new(3., 0.)) - 1.).abs() < EPSILON);
+        assert_eq!(
+            DistanceToPoint::closest_point(&segment, Vec2::new(1., 3.)),
+            Vec2::new(1., 0.)
+        );
+    }
+
+    #[test]
+    fn polygon_distance() {
+        let triangle =
+            Polygon::new([Vec2::new(0., 0.), Vec2::new(4., 0.), Vec2::new(0., 4.)]).unwrap();
+        assert!((triangle.signed_distance(Vec2::new(2., -1.)) - 1.).abs() < EPSILON);
+        assert!((triangle.signed_distance(Vec2::new(1., 1.)) + 1.).abs() < EPSILON);
+        assert!((triangle.signed_distance(Vec2::new(3., 3.)) - 2_f32.sqrt()).abs() < EPSILON);
+        assert!(triangle
+            .closest_point(Vec2::new(3., 3.))
+            .abs_diff_eq(Vec2::new(2., 2.), EPSILON));
+    }
+
+    #[test]
+    fn signed_distance_agrees_with_contains_point() {
+        let shapes = [
+            Shape::Circle { radius: 2. },
+            Shape::Aabb {
+                size: Vec2::new(3., 1.),
+            },
+            Shape::Capsule {
+                height: 3.,
+                radius: 1.,
+            },
+            Shape::polygon([Vec2::new(-1., -1.), Vec2::new(2., 0.), Vec2::new(0., 1.5)]).unwrap(),
+        ];
+        let transform = Transform2::from_xy(0.3, -0.2).with_rotation(FRAC_PI_4);
+        for shape in shapes.iter() {
+            let shape = shape.transformed_by(transform);
+            for x in -6..=6 {
+                for y in -6..=6 {
+                    let point = Vec2::new(x as f32, y as f32) * 0.37;
+                    let distance = shape.signed_distance(point);
+                    if distance.abs() > EPSILON {
+                        assert_eq!(
+                            distance < 0.,
+                            shape.contains_point(point),
+                            "{shape:?} {point}"
+                        );
+                    }
+                    let closest = shape.closest_point(point);
+                    assert!(shape.signed_distance(closest) < EPSILON);
+                    assert!((closest.distance(point) - distance.max(0.)).abs() < EPSILON);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shape_distance() {
+        let circle = Circle {
+            position: Vec2::ZERO,
+            radius: 2.,
+        };
+        let aabb = Aabb {
+            position: Vec2::new(5., 0.),
+            size: Vec2::splat(2.),
+        };
+        assert!((circle.distance_to(&aabb) - 3.).abs() < EPSILON);
+        assert!((aabb.distance_to(&circle) - 3.).abs() < EPSILON);
+        assert_eq!(
+            circle.distance_to(&Point {
+                position: Vec2::ZERO
+            }),
+            0.
+        );
+
+        let compound = Shape::compound([
+            (Transform2::from_xy(-3., 0.), Shape::Circle { radius: 2. }),
+            (Transform2::from_xy(3., 0.), Shape::Circle { radius: 2. }),
+        ])
+        .at(Vec2::ZERO);
+        assert!((compound.distance_to(&aabb) - 0.).abs() < EPSILON);
+        assert!(
+            (compound.distance_to(&Point {
+                position: Vec2::new(0., 0.)
+            }) - 2.)
+                .abs()
+                < EPSILON
+        );
+        assert!(
+            (TransformedShape::from(aabb).distance_to(&Shape::Point.at(Vec2::new(5., 4.))) - 3.)
+                .abs()
+                < EPSILON
+        );
+        assert!((compound.signed_distance(Vec2::new(-3., 0.)) + 1.).abs() < EPSILON);
+        assert!(compound
+            .closest_point(Vec2::new(6., 0.))
+            .abs_diff_eq(Vec2::new(4., 0.), EPSILON));
+        assert_eq!(
+            TransformedShape::default().distance_to_point(Vec2::ZERO),
+            f32::INFINITY
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/// An ellipse with its axes rotated by `rotation`. Like [`Aabb`](super::Aabb), `size` is the full
+/// width and height.
+///
+/// Collisions against other shapes approximate the ellipse with a polygon, which is accurate to
+/// within half a percent of its size. [`ContainsPoint`](super::ContainsPoint) is exact.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub rotation: f32,
+}
@@ -0,0 +1,577 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Display},
+};
+
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+use crate::transform2::Transform2;
+
+use super::{Polygon, PolygonError, Shape};
+
+/// Settings for building a [`Shape`] from the alpha channel of an [`Image`], used by
+/// [`Shape::from_image`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageShapeSettings {
+    /// Pixels whose alpha, from zero to one, is at least this are solid.
+    pub alpha_threshold: f32,
+    /// How far, in pixels, the simplified outline may stray from the solid pixels. Zero follows
+    /// the pixels exactly, which gives many small parts.
+    pub tolerance: f32,
+    /// Islands of solid pixels with a smaller area, in pixels, are ignored.
+    pub min_area: f32,
+}
+
+impl Default for ImageShapeSettings {
+    fn default() -> Self {
+        Self {
+            alpha_threshold: 0.5,
+            tolerance: 1.,
+            min_area: 4.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageShapeError {
+    /// Only 8 bit RGBA and BGRA images are supported.
+    UnsupportedFormat(TextureFormat),
+    /// An outline couldn't be split into convex polygons.
+    Polygon(PolygonError),
+}
+
+impl Display for ImageShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageShapeError::UnsupportedFormat(format) => {
+                write!(f, "image format {format:?} is not supported")
+            }
+            ImageShapeError::Polygon(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for ImageShapeError {}
+
+impl From<PolygonError> for ImageShapeError {
+    fn from(error: PolygonError) -> Self {
+        ImageShapeError::Polygon(error)
+    }
+}
+
+/// An image with a [`Shape`] built from its alpha channel, for hitboxes that match irregular
+/// sprites.
+///
+/// Use the `image_collider` attribute of an [`AssetStruct`](crate::asset_struct::AssetStruct)
+/// to build the shape when the image loads, so it is ready when the struct's
+/// [`AssetStructLoadedEvent`](crate::asset_struct::AssetStructLoadedEvent) is sent:
+///
+/// ```ignore
+/// #[derive(Default, Resource, AssetStruct)]
+/// pub struct AssetLibrary {
+///     #[image_collider("rock.png", alpha_threshold = 0.5, tolerance = 2.)]
+///     pub rock: ImageCollider,
+/// }
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct ImageCollider {
+    pub image: Handle<Image>,
+    pub shape: Shape,
+}
+
+impl Shape {
+    /// Builds convex polygons covering the solid pixels of `image`, centered on the image like a
+    /// [`Sprite`] and measured in pixels, with y going up. Returns [`Shape::None`] for an empty
+    /// image, a [`Shape::Polygon`] when one polygon is enough and a [`Shape::Compound`] otherwise.
+    ///
+    /// Holes in the solid pixels are filled.
+    pub fn from_image(
+        image: &Image,
+        settings: &ImageShapeSettings,
+    ) -> Result<Shape, ImageShapeError> {
+        let mut polygons = image_polygons(image, settings)?;
+        Ok(match polygons.len() {
+            0 => Shape::None,
+            1 => Shape::Polygon(polygons.remove(0)),
+            _ => Shape::compound(
+                polygons
+                    .into_iter()
+                    .map(|polygon| (Transform2::default(), Shape::Polygon(polygon))),
+            ),
+        })
+    }
+}
+
+/// Convex polygons covering the solid pixels of `image`. See [`Shape::from_image`].
+pub fn image_polygons(
+    image: &Image,
+    settings: &ImageShapeSettings,
+) -> Result<Vec<Polygon>, ImageShapeError> {
+    let size = image.size().as_uvec2();
+    let format = image.texture_descriptor.format;
+    let alpha_offset = match format {
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => 3,
+        _ => return Err(ImageShapeError::UnsupportedFormat(format)),
+    };
+    let threshold = (settings.alpha_threshold * 255.).ceil().clamp(1., 255.) as u8;
+    // Rows are flipped, so cell y goes up like the world.
+    let solid = |cell: IVec2| {
+        cell.cmpge(IVec2::ZERO).all()
+            && cell.cmplt(size.as_ivec2()).all()
+            && image
+                .data
+                .get(
+                    ((size.y as i32 - 1 - cell.y) * size.x as i32 + cell.x) as usize * 4
+                        + alpha_offset,
+                )
+                .is_some_and(|alpha| *alpha >= threshold)
+    };
+    let offset = size.as_vec2() * 0.5;
+    let mut polygons = vec![];
+    for outline in outlines(size, solid) {
+        let outline: Vec<Vec2> = outline.into_iter().map(|point| point - offset).collect();
+        if signed_area(&outline) < settings.min_area.max(f32::EPSILON) {
+            // Holes run clockwise, so their area is negative.
+            continue;
+        }
+        let simplified = simplify(&outline, settings.tolerance);
+        // Simplifying can make narrow parts intersect, while the pixel outline never does.
+        let parts = Polygon::decompose(simplified).or_else(|_| Polygon::decompose(outline))?;
+        polygons.extend(parts);
+    }
+    Ok(polygons)
+}
+
+/// Closed outlines along the edges of solid cells, with the solid cells on their left: outer
+/// outlines run counter-clockwise and the outlines of holes clockwise.
+fn outlines(size: UVec2, solid: impl Fn(IVec2) -> bool) -> Vec<Vec<Vec2>> {
+    let mut edges: HashMap<IVec2, Vec<IVec2>> = HashMap::new();
+    for y in 0..size.y as i32 {
+        for x in 0..size.x as i32 {
+            let cell = IVec2::new(x, y);
+            if !solid(cell) {
+                continue;
+            }
+            for (neighbour, start, direction) in [
+                (IVec2::NEG_Y, IVec2::ZERO, IVec2::X),
+                (IVec2::X, IVec2::X, IVec2::Y),
+                (IVec2::Y, IVec2::ONE, IVec2::NEG_X),
+                (IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y),
+            ] {
+                if !solid(cell + neighbour) {
+                    edges.entry(cell + start).or_default().push(direction);
+                }
+            }
+        }
+    }
+    let pinches: HashSet<IVec2> = edges
+        .iter()
+        .filter(|(_, directions)| directions.len() > 1)
+        .map(|(point, _)| *point)
+        .collect();
+    let mut starts: Vec<IVec2> = edges.keys().copied().collect();
+    starts.sort_by_key(|start| (start.y, start.x));
+    let mut outlines = vec![];
+    for start in starts {
+        while let Some(first) = edges
+            .get_mut(&start)
+            .and_then(|directions| directions.pop())
+        {
+            let mut outline = vec![];
+            let (mut point, mut direction) = (start + first, first);
+            loop {
+                // Where two cells touch diagonally, turn left to keep them apart, and move the
+                // point slightly into the corner so the outline doesn't touch itself.
+                let next = if pinches.contains(&point) {
+                    let left = direction.perp();
+                    outline.push(point.as_vec2() + (left - direction).as_vec2() * 0.01);
+                    left
+                } else {
+                    outline.push(point.as_vec2());
+                    edges[&point].first().copied().unwrap_or(first)
+                };
+                if point == start && next == first {
+                    break;
+                }
+                edges
+                    .get_mut(&point)
+                    .unwrap()
+                    .retain(|other| *other != next);
+                (point, direction) = (point + next, next);
+            }
+            outlines.push(outline);
+        }
+    }
+    outlines
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.;
+    for i in 0..points.len() {
+        area += points[i].perp_dot(points[(i + 1) % points.len()]);
+    }
+    area * 0.5
+}
+
+/// Simplifies a closed outline with Ramer-Douglas-Peucker, splitting it at the point furthest
+/// from the first one.
+fn simplify(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    if tolerance <= 0. || points.len() < 4 {
+        return points.to_vec();
+    }
+    let far = (1..points.len())
+        .max_by(|a, b| {
+            points[*a]
+                .distance_squared(points[0])
+                .total_cmp(&points[*b].distance_squared(points[0]))
+        })
+        .unwrap();
+    let mut simplified = vec![];
+    simplify_open(&points[..=far], tolerance, &mut simplified);
+    let mut rest = points[far..].to_vec();
+    rest.push(points[0]);
+    simplify_open(&rest, tolerance, &mut simplified);
+    simplified
+}
+
+/// Pushes the simplified points of an open polyline, except for its last point.
+fn simplify_open(points: &[Vec2], tolerance: f32, simplified: &mut Vec<Vec2>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let furthest = (1..points.len() - 1)
+        .map(|index| (index, distance_to_segment(points[index], first, last)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+    match furthest {
+        Some((index, distance)) if distance > tolerance => {
+            simplify_open(&points[..=index], tolerance, simplified);
+            simplify_open(&points[index..], tolerance, simplified);
+        }
+        _ => simplified.push(first),
+    }
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let t = if segment == Vec2::ZERO {
+        0.
+    } else {
+        ((point - start).dot(segment) / segment.length_squared()).clamp(0., 1.)
+    };
+    point.distance(start + segment * t)
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        prelude::*,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use crate::geometry::{prelude::*, ImageShapeError};
+
+    /// An image from rows of text, top row first, where `#` is opaque and anything else is
+    /// transparent.
+    fn image(rows: &[&str]) -> Image {
+        let data = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .flat_map(|pixel| [255, 255, 255, if pixel == '#' { 255 } else { 0 }])
+            .collect();
+        Image::new(
+            Extent3d {
+                width: rows[0].len() as u32,
+                height: rows.len() as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn exact() -> ImageShapeSettings {
+        ImageShapeSettings {
+            tolerance: 0.,
+            min_area: 0.,
+            ..default()
+        }
+    }
+
+    fn area(shape: &Shape) -> f32 {
+        match shape {
+            Shape::Polygon(polygon) => polygon.area(),
+            Shape::Compound(compound) => compound.parts().iter().map(|(_, part)| area(part)).sum(),
+            _ => 0.,
+        }
+    }
+
+    #[test]
+    fn image_shape_box() {
+        let shape = Shape::from_image(&image(&["....", ".##.", ".##.", "...."]), &exact()).unwrap();
+        let Shape::Polygon(polygon) = &shape else {
+            panic!("{shape:?}");
+        };
+        assert_eq!(polygon.points().len(), 4);
+        assert!((polygon.area() - 4.).abs() < 0.001);
+        let shape = shape.at(Vec2::ZERO);
+        assert!(shape.contains_point(Vec2::new(0.9, -0.9)));
+        assert!(!shape.contains_point(Vec2::new(1.1, 0.)));
+    }
+
+    #[test]
+    fn image_shape_concave_and_islands() {
+        let image = image(&["##...#", "##....", "######", "######"]);
+        let shape = Shape::from_image(&image, &exact()).unwrap();
+        let Shape::Compound(compound) = &shape else {
+            panic!("{shape:?}");
+        };
+        assert!(compound.parts().len() >= 2);
+        assert!((area(&shape) - 17.).abs() < 0.001);
+        let shape = shape.at(Vec2::ZERO);
+        assert!(shape.contains_point(Vec2::new(-2.5, 1.5)));
+        assert!(shape.contains_point(Vec2::new(2.5, 1.5)));
+        assert!(!shape.contains_point(Vec2::new(0.5, 1.5)));
+
+        // The lone pixel is dropped below the minimum area, leaving the L.
+        let shape = Shape::from_image(
+            &image,
+            &ImageShapeSettings {
+                min_area: 2.,
+                ..exact()
+            },
+        )
+        .unwrap();
+        assert!((area(&shape) - 16.).abs() < 0.001);
+    }
+
+    #[test]
+    fn image_shape_holes_and_diagonals() {
+        let ring = image(&["###", "#.#", "###"]);
+        let shape = Shape::from_image(&ring, &exact()).unwrap();
+        assert!((area(&shape) - 9.).abs() < 0.001);
+
+        // Pixels touching at a corner are separate. Their outlines are pulled in slightly at the
+        // corner, so they don't touch.
+        let diagonal = image(&["#..", ".#.", "..#"]);
+        let shape = Shape::from_image(&diagonal, &exact()).unwrap();
+        let Shape::Compound(compound) = &shape else {
+            panic!("{shape:?}");
+        };
+        assert_eq!(compound.parts().len(), 3);
+        assert!((area(&shape) - 3.).abs() < 0.1);
+
+        let hook = image(&["###.", "#.#.", "#.##", "#..."]);
+        let shape = Shape::from_image(&hook, &exact()).unwrap();
+        assert!((area(&shape) - 9.).abs() < 0.1);
+    }
+
+    #[test]
+    fn image_shape_simplification() {
+        let rows: Vec<String> = (0..32)
+            .map(|y| {
+                (0..32)
+                    .map(|x| {
+                        let offset = Vec2::new(x as f32 - 15.5, y as f32 - 15.5);
+                        if offset.length() < 14. {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        let circle = image(&rows);
+        let exact_shape = Shape::from_image(&circle, &exact()).unwrap();
+        let simple_shape = Shape::from_image(&circle, &ImageShapeSettings::default()).unwrap();
+        let Shape::Polygon(polygon) = &simple_shape else {
+            panic!("{simple_shape:?}");
+        };
+        assert!(polygon.points().len() < 40);
+        assert!((area(&simple_shape) - area(&exact_shape)).abs() < area(&exact_shape) * 0.05);
+
+        assert!(matches!(
+            Shape::from_image(&image(&["..", ".."]), &exact()),
+            Ok(Shape::None)
+        ));
+        let mut grey = image(&["#"]);
+        grey.texture_descriptor.format = TextureFormat::R8Unorm;
+        assert_eq!(
+            Shape::from_image(&grey, &exact()).unwrap_err(),
+            ImageShapeError::UnsupportedFormat(TextureFormat::R8Unorm)
+        );
+    }
+
+    /// Builds an [`ImageCollider`] through the `image_collider` attribute of an [`AssetStruct`],
+    /// loading images from memory.
+    #[cfg(feature = "tinae_asset_struct")]
+    mod asset_struct {
+        use std::{
+            collections::HashMap,
+            path::{Path, PathBuf},
+            thread,
+            time::Duration,
+        };
+
+        use bevy::{
+            asset::{
+                AssetIo, AssetIoError, AssetLoader, Error, FileType, LoadContext, LoadedAsset,
+                Metadata,
+            },
+            prelude::*,
+            render::render_resource::TextureFormat,
+            utils::BoxedFuture,
+        };
+
+        use super::{area, image};
+        use crate::{
+            asset_struct::{
+                AddAssetStruct, AssetStruct, AssetStructLoadEvent, AssetStructState,
+                AssetStructStatus,
+            },
+            geometry::prelude::*,
+            sub_assets::SubAssetsPlugin,
+        };
+
+        /// Asset sources read from memory, so tests can load images through the [`AssetServer`].
+        struct MemoryAssetIo(HashMap<PathBuf, Vec<u8>>);
+
+        impl AssetIo for MemoryAssetIo {
+            fn load_path<'a>(
+                &'a self,
+                path: &'a Path,
+            ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+                Box::pin(async move {
+                    self.0
+                        .get(path)
+                        .cloned()
+                        .ok_or_else(|| AssetIoError::NotFound(path.to_path_buf()))
+                })
+            }
+
+            fn read_directory(
+                &self,
+                path: &Path,
+            ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+                Err(AssetIoError::NotFound(path.to_path_buf()))
+            }
+
+            fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+                if self.0.contains_key(path) {
+                    Ok(Metadata::new(FileType::File))
+                } else {
+                    Err(AssetIoError::NotFound(path.to_path_buf()))
+                }
+            }
+
+            fn watch_path_for_changes(
+                &self,
+                _to_watch: &Path,
+                _to_reload: Option<PathBuf>,
+            ) -> Result<(), AssetIoError> {
+                Ok(())
+            }
+
+            fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+                Ok(())
+            }
+        }
+
+        /// Loads the text rows of [`image`]. `.gray` files get a format [`Shape::from_image`]
+        /// doesn't support.
+        struct TextImageLoader;
+
+        impl AssetLoader for TextImageLoader {
+            fn load<'a>(
+                &'a self,
+                bytes: &'a [u8],
+                load_context: &'a mut LoadContext,
+            ) -> BoxedFuture<'a, Result<(), Error>> {
+                Box::pin(async move {
+                    let text = std::str::from_utf8(bytes)?;
+                    let mut loaded = image(&text.lines().collect::<Vec<_>>());
+                    if load_context.path().extension() == Some("gray".as_ref()) {
+                        loaded.texture_descriptor.format = TextureFormat::R8Unorm;
+                    }
+                    load_context.set_default_asset(LoadedAsset::new(loaded));
+                    Ok(())
+                })
+            }
+
+            fn extensions(&self) -> &[&str] {
+                &["mask", "gray"]
+            }
+        }
+
+        #[derive(Default, Resource, AssetStruct)]
+        struct Rocks {
+            #[image_collider("rock.mask", tolerance = 0., min_area = 0.)]
+            rock: ImageCollider,
+        }
+
+        #[derive(Default, Resource, AssetStruct)]
+        struct GrayRocks {
+            #[image_collider("rock.gray")]
+            rock: ImageCollider,
+        }
+
+        #[test]
+        fn image_collider_asset_struct() {
+            let rock = b"##..\n####\n####".to_vec();
+            let mut app = App::new();
+            app.add_plugin(TaskPoolPlugin::default())
+                .insert_resource(AssetServer::new(MemoryAssetIo(
+                    [
+                        ("rock.mask".into(), rock.clone()),
+                        ("rock.gray".into(), rock),
+                    ]
+                    .into(),
+                )))
+                .add_plugin(AssetPlugin::default())
+                .add_plugin(SubAssetsPlugin)
+                .add_asset::<Image>()
+                .add_asset_loader(TextImageLoader)
+                .add_asset_struct::<Rocks>()
+                .add_asset_struct::<GrayRocks>();
+            app.world
+                .send_event(AssetStructLoadEvent::<Rocks>::default());
+            app.world
+                .send_event(AssetStructLoadEvent::<GrayRocks>::default());
+
+            let done = |status| {
+                matches!(
+                    status,
+                    AssetStructStatus::Loaded | AssetStructStatus::Failed
+                )
+            };
+            for _ in 0..1000 {
+                app.update();
+                if done(app.world.resource::<AssetStructState<Rocks>>().status())
+                    && done(app.world.resource::<AssetStructState<GrayRocks>>().status())
+                {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            assert!(matches!(
+                app.world.resource::<AssetStructState<Rocks>>().status(),
+                AssetStructStatus::Loaded
+            ));
+            let shape = &app.world.resource::<Rocks>().rock.shape;
+            assert!((area(shape) - 10.).abs() < 1e-3, "{shape:?}");
+            assert!(matches!(
+                app.world.resource::<AssetStructState<GrayRocks>>().status(),
+                AssetStructStatus::Failed
+            ));
+            assert!(matches!(
+                app.world.resource::<GrayRocks>().rock.shape,
+                Shape::None
+            ));
+        }
+    }
+}
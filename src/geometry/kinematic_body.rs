@@ -0,0 +1,560 @@
+use bevy::prelude::*;
+
+use crate::{flow::FlowSet, transform2::Transform2};
+
+use super::{Aabb, Collider, CollidingWith, ContactWith, GeometrySystem, TransformedShape};
+
+pub(crate) struct KinematicBodyPlugin;
+
+impl Plugin for KinematicBodyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            kinematic_body_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(GeometrySystem::KinematicBody)
+                .in_base_set(FlowSet::EntityMovement),
+        );
+    }
+}
+
+/// Moves an entity with a [`Collider`] by `velocity` every fixed update, sliding along the solid
+/// colliders it runs into instead of passing through them.
+///
+/// Surfaces tilted at most `max_slope` away from `up` count as floor. Walking on floors keeps the
+/// horizontal speed and doesn't slide down slopes, and steeper surfaces act as walls. When the
+/// body walks off a floor while not moving up, it snaps down onto any floor within
+/// `snap_distance`, so it follows slopes and steps down instead of launching off them.
+///
+/// After moving, the part of `velocity` going into the surfaces that were hit is removed, so
+/// gravity doesn't build up while standing. Sensors, colliders that don't interact with the
+/// body's layers and other kinematic bodies are ignored.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct KinematicBody {
+    pub velocity: Vec2,
+    pub up: Vec2,
+    /// Steepest floor angle in radians.
+    pub max_slope: f32,
+    pub snap_distance: f32,
+    /// Gap kept between the body and the surfaces it touches, so it doesn't start the next move
+    /// already overlapping them.
+    pub skin: f32,
+    /// Maximum number of surfaces to slide along in a single move.
+    pub max_slides: u32,
+    on_floor: bool,
+    on_ceiling: bool,
+    on_wall: bool,
+    floor_normal: Vec2,
+    floor: Option<Entity>,
+}
+
+impl Default for KinematicBody {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            up: Vec2::Y,
+            max_slope: std::f32::consts::FRAC_PI_4,
+            snap_distance: 0.,
+            skin: 0.01,
+            max_slides: 4,
+            on_floor: false,
+            on_ceiling: false,
+            on_wall: false,
+            floor_normal: Vec2::ZERO,
+            floor: None,
+        }
+    }
+}
+
+impl KinematicBody {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_up(self, up: Vec2) -> Self {
+        Self {
+            up: up.normalize_or_zero(),
+            ..self
+        }
+    }
+
+    pub fn with_max_slope(self, max_slope: f32) -> Self {
+        Self { max_slope, ..self }
+    }
+
+    pub fn with_snap_distance(self, snap_distance: f32) -> Self {
+        Self {
+            snap_distance,
+            ..self
+        }
+    }
+
+    /// Whether the body touched a floor during its last move.
+    pub fn is_on_floor(&self) -> bool {
+        self.on_floor
+    }
+
+    pub fn is_on_ceiling(&self) -> bool {
+        self.on_ceiling
+    }
+
+    pub fn is_on_wall(&self) -> bool {
+        self.on_wall
+    }
+
+    /// Normal of the floor the body is standing on, or zero when it's not on a floor.
+    pub fn floor_normal(&self) -> Vec2 {
+        self.floor_normal
+    }
+
+    /// The entity the body is standing on.
+    pub fn floor(&self) -> Option<Entity> {
+        self.floor
+    }
+
+    fn is_floor(&self, normal: Vec2) -> bool {
+        normal.dot(self.up) >= self.max_slope.cos() - 0.0001
+    }
+
+    fn is_ceiling(&self, normal: Vec2) -> bool {
+        (-normal).dot(self.up) >= self.max_slope.cos() - 0.0001
+    }
+
+    /// How far the body can get from where it starts sliding by `motion`, snapping included.
+    /// Sliding along a floor can lengthen the remaining motion by up to `1 / cos(max_slope)`.
+    fn reach(&self, motion: Vec2) -> f32 {
+        let floor_cos = self.max_slope.cos() - 0.0001;
+        if floor_cos <= 0. {
+            return f32::INFINITY;
+        }
+        let slides: f32 = (0..self.max_slides)
+            .map(|slide| floor_cos.powi(-(slide as i32)))
+            .sum();
+        motion.length() * slides + self.snap_distance + self.skin
+    }
+}
+
+/// Makes a collider only block [`KinematicBody`]s moving against `up`, and only when they don't
+/// already overlap it, so bodies can jump through it from below and land on top.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OneWayPlatform {
+    pub up: Vec2,
+}
+
+impl Default for OneWayPlatform {
+    fn default() -> Self {
+        Self { up: Vec2::Y }
+    }
+}
+
+/// A collider a body can run into.
+struct Obstacle<'a> {
+    entity: Entity,
+    collider: &'a Collider,
+    shape: TransformedShape,
+    aabb: Aabb,
+    one_way: Option<&'a OneWayPlatform>,
+}
+
+struct Hit {
+    entity: Entity,
+    distance: f32,
+    normal: Vec2,
+}
+
+/// Earliest hit of `shape` moving along `motion` against `obstacles`.
+fn first_hit(shape: &TransformedShape, motion: Vec2, obstacles: &[&Obstacle]) -> Option<Hit> {
+    let start = shape.bounding_aabb()?;
+    let swept = start.merge(&Aabb {
+        position: start.position + motion,
+        ..start
+    });
+    let mut best: Option<Hit> = None;
+    for obstacle in obstacles.iter() {
+        if swept.min().cmpgt(obstacle.aabb.max()).any()
+            || obstacle.aabb.min().cmpgt(swept.max()).any()
+        {
+            continue;
+        }
+        if let Some(one_way) = obstacle.one_way {
+            if motion.dot(one_way.up) >= 0. {
+                continue;
+            }
+        }
+        let Some(hit) = shape.shape_cast(motion, &obstacle.shape) else {
+            continue;
+        };
+        if obstacle.one_way.is_some() && hit.time_of_impact == 0. {
+            continue;
+        }
+        if best
+            .as_ref()
+            .map(|best| hit.distance < best.distance)
+            .unwrap_or(true)
+        {
+            best = Some(Hit {
+                entity: obstacle.entity,
+                distance: hit.distance,
+                normal: hit.normal,
+            });
+        }
+    }
+    best
+}
+
+/// Moves `body` from `transform` through `obstacles` by `motion`, updating its contact state.
+///
+/// Only the obstacles whose box overlaps the body are checked for pushing it out, and only those
+/// within its [reach](KinematicBody::reach) are cast against.
+fn move_and_slide(
+    body: &mut KinematicBody,
+    collider: &Collider,
+    transform: &mut Transform2,
+    motion: Vec2,
+    obstacles: &[Obstacle],
+) {
+    let was_on_floor = body.on_floor;
+    body.on_floor = false;
+    body.on_ceiling = false;
+    body.on_wall = false;
+    body.floor_normal = Vec2::ZERO;
+    body.floor = None;
+
+    let obstacles = obstacles
+        .iter()
+        .filter(|obstacle| collider.interacts_with(obstacle.collider));
+    // Push out of solid colliders the body already overlaps, for example ones that moved into it.
+    let mut shape = collider.at(*transform);
+    for obstacle in obstacles
+        .clone()
+        .filter(|obstacle| obstacle.one_way.is_none())
+    {
+        if !shape
+            .bounding_aabb()
+            .is_some_and(|aabb| aabb.colliding_with(&obstacle.aabb))
+        {
+            continue;
+        }
+        if let Some(contact) = shape.contact_with(&obstacle.shape) {
+            transform.translation -= contact.normal * (contact.depth + body.skin);
+            shape.transform = *transform;
+        }
+    }
+    let obstacles: Vec<&Obstacle> = match shape.bounding_aabb() {
+        Some(aabb) => {
+            let reachable = Aabb {
+                position: aabb.position,
+                size: aabb.size + Vec2::splat(body.reach(motion) * 2.),
+            };
+            obstacles
+                .filter(|obstacle| reachable.colliding_with(&obstacle.aabb))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let right = -body.up.perp();
+    let mut remaining = motion;
+    for _ in 0..body.max_slides {
+        let length = remaining.length();
+        if length <= body.skin * 0.01 {
+            break;
+        }
+        let direction = remaining / length;
+        let Some(hit) = first_hit(&collider.at(*transform), remaining, &obstacles) else {
+            transform.translation += remaining;
+            break;
+        };
+        // Back off so the body stops `skin` away from the surface.
+        let approach = -direction.dot(hit.normal);
+        let travel = (hit.distance - body.skin / approach.max(0.1)).max(0.);
+        transform.translation += direction * travel;
+        remaining -= direction * travel;
+        if body.is_floor(hit.normal) {
+            body.on_floor = true;
+            body.floor_normal = hit.normal;
+            body.floor = Some(hit.entity);
+            // Follow the slope while keeping the horizontal distance, dropping the motion into it.
+            let tangent = hit.normal.perp();
+            let horizontal = remaining.dot(right);
+            remaining = tangent * (horizontal / tangent.dot(right));
+            body.velocity -= body.up * body.velocity.dot(body.up).min(0.);
+        } else {
+            let mut normal = hit.normal;
+            if body.is_ceiling(normal) {
+                body.on_ceiling = true;
+            } else {
+                body.on_wall = true;
+                // Walking into a steep slope shouldn't climb it, so it's treated as upright.
+                if body.on_floor || was_on_floor {
+                    normal = (normal - body.up * normal.dot(body.up)).normalize_or_zero();
+                }
+            }
+            remaining -= normal * remaining.dot(normal);
+            body.velocity -= normal * body.velocity.dot(normal).min(0.);
+        }
+    }
+
+    if was_on_floor && !body.on_floor && body.snap_distance > 0. && body.velocity.dot(body.up) <= 0.
+    {
+        let snap = -body.up * body.snap_distance;
+        if let Some(hit) = first_hit(&collider.at(*transform), snap, &obstacles) {
+            if body.is_floor(hit.normal) {
+                transform.translation -= body.up * (hit.distance - body.skin).max(0.);
+                body.on_floor = true;
+                body.floor_normal = hit.normal;
+                body.floor = Some(hit.entity);
+            }
+        }
+    }
+}
+
+fn kinematic_body_update(
+    mut body_query: Query<(&mut Transform2, &mut KinematicBody, &Collider)>,
+    obstacle_query: Query<
+        (Entity, &Transform2, &Collider, Option<&OneWayPlatform>),
+        Without<KinematicBody>,
+    >,
+    time: Res<FixedTime>,
+) {
+    let delta_seconds = time.period.as_secs_f32();
+    // Built once for every body, since placing a collider clones its shape.
+    let obstacles: Vec<Obstacle> = obstacle_query
+        .iter()
+        .filter(|(_, _, collider, _)| !collider.sensor)
+        .filter_map(|(entity, transform, collider, one_way)| {
+            let shape = collider.at(*transform);
+            shape.bounding_aabb().map(|aabb| Obstacle {
+                entity,
+                collider,
+                shape,
+                aabb,
+                one_way,
+            })
+        })
+        .collect();
+    for (mut transform, mut body, collider) in body_query.iter_mut() {
+        let motion = body.velocity * delta_seconds;
+        move_and_slide(&mut body, collider, &mut transform, motion, &obstacles);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{geometry::prelude::*, transform2::Transform2};
+
+    use super::kinematic_body_update;
+
+    const GRAVITY: f32 = -30.;
+
+    struct Scene {
+        world: World,
+        schedule: Schedule,
+        body: Entity,
+    }
+
+    impl Scene {
+        fn new(body: KinematicBody, position: Vec2) -> Self {
+            let mut world = World::new();
+            world.insert_resource(FixedTime::new_from_secs(1. / 60.));
+            let mut schedule = Schedule::new();
+            schedule.add_system(kinematic_body_update);
+            let body = world
+                .spawn((
+                    body,
+                    Collider::new(Shape::Aabb {
+                        size: Vec2::new(1., 2.),
+                    }),
+                    Transform2::from_translation(position),
+                ))
+                .id();
+            Self {
+                world,
+                schedule,
+                body,
+            }
+        }
+
+        fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+            self.world.spawn(bundle).id()
+        }
+
+        /// Runs `ticks` fixed updates, applying gravity and a horizontal walking speed.
+        fn run(&mut self, ticks: usize, walk: f32) {
+            for _ in 0..ticks {
+                let mut body = self.world.get_mut::<KinematicBody>(self.body).unwrap();
+                body.velocity.x = walk;
+                body.velocity.y += GRAVITY / 60.;
+                self.schedule.run(&mut self.world);
+            }
+        }
+
+        fn body(&self) -> KinematicBody {
+            *self.world.get::<KinematicBody>(self.body).unwrap()
+        }
+
+        fn position(&self) -> Vec2 {
+            self.world.get::<Transform2>(self.body).unwrap().translation
+        }
+    }
+
+    fn ground() -> (Collider, Transform2) {
+        (
+            Collider::new(Shape::Aabb {
+                size: Vec2::new(100., 2.),
+            }),
+            Transform2::from_xy(0., -1.),
+        )
+    }
+
+    #[test]
+    fn kinematic_body_lands() {
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(0., 5.));
+        let ground = scene.spawn(ground());
+        scene.run(120, 0.);
+        let body = scene.body();
+        assert!(body.is_on_floor());
+        assert_eq!(body.floor(), Some(ground));
+        assert!(body.floor_normal().abs_diff_eq(Vec2::Y, 0.0001));
+        assert!((scene.position().y - 1.).abs() < 0.05);
+        assert!(body.velocity.y.abs() < 1.);
+    }
+
+    #[test]
+    fn kinematic_body_walls_and_ceilings() {
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(0., 1.01));
+        scene.spawn(ground());
+        scene.spawn((
+            Collider::new(Shape::Aabb {
+                size: Vec2::new(2., 10.),
+            }),
+            Transform2::from_xy(4., 5.),
+        ));
+        scene.run(120, 5.);
+        assert!(scene.body().is_on_wall());
+        assert!(scene.body().is_on_floor());
+        assert!((scene.position().x - 2.5).abs() < 0.05);
+
+        scene.spawn((
+            Collider::new(Shape::Aabb {
+                size: Vec2::new(4., 1.),
+            }),
+            Transform2::from_xy(0., 4.5),
+        ));
+        scene
+            .world
+            .get_mut::<KinematicBody>(scene.body)
+            .unwrap()
+            .velocity
+            .y = 20.;
+        scene.run(1, 0.);
+        scene.run(8, 0.);
+        assert!(scene.position().y < 3.);
+        assert!(scene.body().velocity.y <= 0.);
+    }
+
+    #[test]
+    fn kinematic_body_slopes() {
+        let slope = |angle: f32| {
+            (
+                Collider::new(
+                    Shape::polygon([
+                        Vec2::ZERO,
+                        Vec2::new(20., 0.),
+                        Vec2::new(20., 20. * angle.tan()),
+                    ])
+                    .unwrap(),
+                ),
+                Transform2::from_xy(0., 0.),
+            )
+        };
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(-2., 1.01));
+        scene.spawn(ground());
+        scene.spawn(slope(0.5));
+        scene.run(120, 5.);
+        assert!(scene.body().is_on_floor());
+        assert!(scene.position().x > 6.);
+        assert!(scene.position().y > 3.);
+        let position = scene.position();
+        scene.run(60, 0.);
+        assert!(scene.position().distance(position) < 0.05);
+
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(-2., 1.01));
+        scene.spawn(ground());
+        scene.spawn(slope(1.2));
+        scene.run(120, 5.);
+        assert!(scene.body().is_on_wall());
+        assert!(scene.position().x < 0.);
+    }
+
+    #[test]
+    fn kinematic_body_snaps_to_floor() {
+        let step_down = |scene: &mut Scene| {
+            scene.spawn((
+                Collider::new(Shape::Aabb {
+                    size: Vec2::new(10., 2.),
+                }),
+                Transform2::from_xy(-5., -1.),
+            ));
+            scene.spawn((
+                Collider::new(Shape::Aabb {
+                    size: Vec2::new(10., 2.),
+                }),
+                Transform2::from_xy(5., -1.3),
+            ));
+        };
+        // Returns whether the body stayed on the floor the whole way.
+        let walk = |body: KinematicBody| {
+            let mut scene = Scene::new(body, Vec2::new(-2., 1.01));
+            step_down(&mut scene);
+            scene.run(5, 0.);
+            let mut stayed_on_floor = true;
+            for _ in 0..30 {
+                scene.run(1, 10.);
+                stayed_on_floor &= scene.body().is_on_floor();
+            }
+            assert!(scene.position().x > 1.);
+            stayed_on_floor
+        };
+        assert!(walk(KinematicBody::new().with_snap_distance(0.5)));
+        assert!(!walk(KinematicBody::new()));
+    }
+
+    #[test]
+    fn kinematic_body_one_way_platform() {
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(0., 1.01));
+        scene.spawn(ground());
+        let platform = scene.spawn((
+            Collider::new(Shape::Aabb {
+                size: Vec2::new(10., 0.5),
+            }),
+            Transform2::from_xy(0., 3.),
+            OneWayPlatform::default(),
+        ));
+        scene
+            .world
+            .get_mut::<KinematicBody>(scene.body)
+            .unwrap()
+            .velocity
+            .y = 15.;
+        scene.run(120, 0.);
+        assert_eq!(scene.body().floor(), Some(platform));
+        assert!((scene.position().y - 4.25).abs() < 0.05);
+    }
+
+    #[test]
+    fn kinematic_body_ignores_sensors() {
+        let mut scene = Scene::new(KinematicBody::new(), Vec2::new(0., 5.));
+        scene.spawn(ground());
+        scene.spawn((
+            Collider::new(Shape::Aabb {
+                size: Vec2::new(10., 1.),
+            })
+            .sensor(),
+            Transform2::from_xy(0., 2.),
+        ));
+        scene.run(120, 0.);
+        assert!((scene.position().y - 1.).abs() < 0.05);
+    }
+}
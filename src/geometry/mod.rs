@@ -3,23 +3,72 @@ use bevy::prelude::*;
 pub struct GeometryPlugin;
 
 impl Plugin for GeometryPlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_plugin(ColliderPlugin)
+            .add_plugin(SpatialIndexPlugin)
+            .add_plugin(KinematicBodyPlugin)
+            .add_plugin(ShapeMeshPlugin)
+            .add_plugin(GeometryDebugPlugin);
+    }
 }
 
 #[macro_use]
 mod shape;
 
 mod aabb;
+mod capsule;
 mod circle;
+mod collider;
 mod colliding_with;
+mod compound;
+mod contact;
 mod contains_point;
+mod convex;
+mod debug;
+mod distance;
+mod ellipse;
+mod image_shape;
+mod kinematic_body;
+mod obb;
+mod point;
+mod polygon;
+mod raycast;
+mod segment;
+mod shape_mesh;
+mod spatial_index;
+mod tile_grid;
 
 pub use crate::geometry::shape::*;
 pub use aabb::*;
+pub use capsule::*;
 pub use circle::*;
+pub use collider::*;
 pub use colliding_with::*;
+pub use compound::*;
+pub use contact::*;
 pub use contains_point::*;
+pub(crate) use convex::ELLIPSE_SEGMENTS;
+pub use debug::*;
+pub use distance::*;
+pub use ellipse::*;
+pub use image_shape::*;
+pub use kinematic_body::*;
+pub use obb::*;
+pub use point::*;
+pub use polygon::*;
+pub use raycast::*;
+pub use segment::*;
+pub use shape_mesh::*;
+pub use spatial_index::*;
+pub use tile_grid::*;
 
 pub mod prelude {
-    pub use super::{Aabb, Circle, CollidingWith, ContainsPoint, Shape};
+    pub use super::{
+        Aabb, Capsule, Circle, Collider, CollidingWith, CollisionEnded, CollisionStarted,
+        Collisions, CompoundShape, Contact, ContactWith, ContainsPoint, DistanceTo,
+        DistanceToPoint, Ellipse, GeometryDebug, GeometrySystem, ImageCollider, ImageShapeSettings,
+        KinematicBody, Obb, OneWayPlatform, Point, Polygon, Ray2, RayHit, Raycast, Segment, Shape,
+        ShapeCastHit, ShapeMesh, ShapeMeshBundle, SpatialIndex, TileGridCollider, TriggerEnter,
+        TriggerExit,
+    };
 }
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+/// Oriented bounding box, an [`Aabb`](super::Aabb) rotated around its center.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Obb {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub rotation: f32,
+}
+
+impl Obb {
+    /// Corners in counter-clockwise order.
+    pub fn corners(&self) -> [Vec2; 4] {
+        let half_size = self.size * 0.5;
+        let rotation = Vec2::from_angle(self.rotation);
+        [
+            Vec2::new(-half_size.x, -half_size.y),
+            Vec2::new(half_size.x, -half_size.y),
+            Vec2::new(half_size.x, half_size.y),
+            Vec2::new(-half_size.x, half_size.y),
+        ]
+        .map(|corner| self.position + rotation.rotate(corner))
+    }
+}
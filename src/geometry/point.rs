@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+/// A single point. Points have no area, so they never contain other points, but they collide
+/// with any shape they touch.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub position: Vec2,
+}
@@ -0,0 +1,359 @@
+use std::{
+    error::Error,
+    f32::consts::TAU,
+    fmt::{self, Display},
+};
+
+use bevy::prelude::*;
+
+use crate::transform2::Transform2;
+
+/// A convex polygon with its points in counter-clockwise order.
+///
+/// Polygons are validated when constructed with [`Polygon::new`]: duplicate and collinear points
+/// are removed, clockwise outlines are reversed, and outlines that aren't convex are rejected. Use
+/// [`Polygon::decompose`] to split a concave outline into convex pieces.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    points: Vec<Vec2>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    /// Fewer than three distinct, non-collinear points.
+    TooFewPoints,
+    /// A point is NaN or infinite.
+    NotFinite,
+    /// The outline is concave or self-intersecting.
+    NotConvex,
+    /// The outline intersects itself, so it can't be decomposed.
+    NotSimple,
+}
+
+impl Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::TooFewPoints => f.write_str("polygon has fewer than three points"),
+            PolygonError::NotFinite => f.write_str("polygon has a point that is not finite"),
+            PolygonError::NotConvex => f.write_str("polygon is not convex"),
+            PolygonError::NotSimple => f.write_str("polygon outline intersects itself"),
+        }
+    }
+}
+
+impl Error for PolygonError {}
+
+impl Polygon {
+    pub fn new(points: impl IntoIterator<Item = Vec2>) -> Result<Self, PolygonError> {
+        let mut points = simplify_outline(points.into_iter().collect())?;
+        if signed_area(&points) < 0. {
+            points.reverse();
+        }
+        let mut winding = 0.;
+        for i in 0..points.len() {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            let c = points[(i + 2) % points.len()];
+            let turn = (b - a).perp_dot(c - b);
+            if turn < 0. {
+                return Err(PolygonError::NotConvex);
+            }
+            winding += (b - a).angle_between(c - b);
+        }
+        if (winding - TAU).abs() > 0.001 {
+            return Err(PolygonError::NotConvex);
+        }
+        Ok(Self { points })
+    }
+
+    /// Creates a polygon from points already known to be convex and in counter-clockwise order.
+    pub(crate) fn new_unchecked(points: Vec<Vec2>) -> Self {
+        Self { points }
+    }
+
+    /// Splits a simple (non self-intersecting) outline, which may be concave, into convex
+    /// polygons. The outline is triangulated and the triangles are then merged back together
+    /// wherever the result stays convex.
+    pub fn decompose(points: impl IntoIterator<Item = Vec2>) -> Result<Vec<Polygon>, PolygonError> {
+        let mut points = simplify_outline(points.into_iter().collect())?;
+        if signed_area(&points) < 0. {
+            points.reverse();
+        }
+        if self_intersecting(&points) {
+            return Err(PolygonError::NotSimple);
+        }
+        let mut pieces = triangulate(&points)?;
+        let mut merged = true;
+        while merged {
+            merged = false;
+            'search: for i in 0..pieces.len() {
+                for j in (i + 1)..pieces.len() {
+                    if let Some(piece) = merge_pieces(&pieces[i], &pieces[j], &points) {
+                        pieces[i] = piece;
+                        pieces.swap_remove(j);
+                        merged = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+        pieces
+            .into_iter()
+            .map(|piece| Polygon::new(piece.into_iter().map(|index| points[index])))
+            .collect()
+    }
+
+    /// Points in counter-clockwise order.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns the polygon with every point transformed. Mirroring transforms keep the points in
+    /// counter-clockwise order.
+    pub fn transformed(&self, transform: &Transform2) -> Polygon {
+        let mut points: Vec<Vec2> = self
+            .points
+            .iter()
+            .map(|point| transform.transform_point(*point))
+            .collect();
+        if transform.scale.x * transform.scale.y < 0. {
+            points.reverse();
+        }
+        Polygon { points }
+    }
+
+    pub fn area(&self) -> f32 {
+        signed_area(&self.points)
+    }
+
+    pub fn centroid(&self) -> Vec2 {
+        let mut centroid = Vec2::ZERO;
+        let mut area = 0.;
+        for i in 0..self.points.len() {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % self.points.len()];
+            let cross = a.perp_dot(b);
+            centroid += (a + b) * cross;
+            area += cross;
+        }
+        centroid / (area * 3.)
+    }
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let mut area = 0.;
+    for i in 0..points.len() {
+        area += points[i].perp_dot(points[(i + 1) % points.len()]);
+    }
+    area * 0.5
+}
+
+/// Removes duplicate and collinear points from a closed outline.
+fn simplify_outline(mut points: Vec<Vec2>) -> Result<Vec<Vec2>, PolygonError> {
+    if points.iter().any(|point| !point.is_finite()) {
+        return Err(PolygonError::NotFinite);
+    }
+    let mut removed = true;
+    while removed && points.len() >= 3 {
+        removed = false;
+        for i in 0..points.len() {
+            let a = points[(i + points.len() - 1) % points.len()];
+            let b = points[i];
+            let c = points[(i + 1) % points.len()];
+            let cross = (b - a).perp_dot(c - b);
+            let scale = (b - a).length() * (c - b).length();
+            if scale == 0. || cross.abs() <= scale * 0.00001 && (b - a).dot(c - b) >= 0. {
+                points.remove(i);
+                removed = true;
+                break;
+            }
+        }
+    }
+    if points.len() < 3 {
+        Err(PolygonError::TooFewPoints)
+    } else {
+        Ok(points)
+    }
+}
+
+/// Whether any two non-adjacent edges of a closed outline intersect.
+fn self_intersecting(points: &[Vec2]) -> bool {
+    let count = points.len();
+    for i in 0..count {
+        let (a0, a1) = (points[i], points[(i + 1) % count]);
+        for j in (i + 2)..count {
+            if i == 0 && j == count - 1 {
+                continue;
+            }
+            let (b0, b1) = (points[j], points[(j + 1) % count]);
+            let d0 = (a1 - a0).perp_dot(b0 - a0);
+            let d1 = (a1 - a0).perp_dot(b1 - a0);
+            let d2 = (b1 - b0).perp_dot(a0 - b0);
+            let d3 = (b1 - b0).perp_dot(a1 - b0);
+            if d0 * d1 <= 0. && d2 * d3 <= 0. && (d0, d1, d2, d3) != (0., 0., 0., 0.) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Ear clipping triangulation of a counter-clockwise outline. Returns triangles as indices into
+/// `points`.
+fn triangulate(points: &[Vec2]) -> Result<Vec<Vec<usize>>, PolygonError> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = vec![];
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let (a, b, c) = (
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            );
+            (points[b] - points[a]).perp_dot(points[c] - points[b]) > 0.
+                && remaining.iter().all(|&index| {
+                    index == a
+                        || index == b
+                        || index == c
+                        || !triangle_contains_point(points[a], points[b], points[c], points[index])
+                })
+        });
+        if let Some(ear) = ear {
+            triangles.push(vec![
+                remaining[(ear + count - 1) % count],
+                remaining[ear],
+                remaining[(ear + 1) % count],
+            ]);
+            remaining.remove(ear);
+        } else {
+            return Err(PolygonError::NotSimple);
+        }
+    }
+    triangles.push(remaining);
+    Ok(triangles)
+}
+
+/// Whether `point` is inside or on the edge of the counter-clockwise triangle `a`, `b`, `c`.
+fn triangle_contains_point(a: Vec2, b: Vec2, c: Vec2, point: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.
+        && (c - b).perp_dot(point - b) >= 0.
+        && (a - c).perp_dot(point - c) >= 0.
+}
+
+/// Merges two pieces that share an edge, if the result is convex.
+fn merge_pieces(a: &[usize], b: &[usize], points: &[Vec2]) -> Option<Vec<usize>> {
+    for i in 0..a.len() {
+        let a0 = a[i];
+        let a1 = a[(i + 1) % a.len()];
+        for j in 0..b.len() {
+            if b[j] == a1 && b[(j + 1) % b.len()] == a0 {
+                let mut merged = vec![];
+                for k in 0..a.len() - 1 {
+                    merged.push(a[(i + 1 + k) % a.len()]);
+                }
+                for k in 0..b.len() - 1 {
+                    merged.push(b[(j + 1 + k) % b.len()]);
+                }
+                let convex = (0..merged.len()).all(|k| {
+                    let p0 = points[merged[k]];
+                    let p1 = points[merged[(k + 1) % merged.len()]];
+                    let p2 = points[merged[(k + 2) % merged.len()]];
+                    (p1 - p0).perp_dot(p2 - p1) >= 0.
+                });
+                return convex.then_some(merged);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{geometry::prelude::*, transform2::Transform2};
+
+    use super::PolygonError;
+
+    #[test]
+    fn polygon_new() {
+        let triangle = Polygon::new([Vec2::ZERO, Vec2::new(0., 1.), Vec2::new(1., 0.)]).unwrap();
+        assert_eq!(
+            triangle.points(),
+            &[Vec2::new(1., 0.), Vec2::new(0., 1.), Vec2::ZERO]
+        );
+        assert_eq!(triangle.area(), 0.5);
+        let square = Polygon::new([
+            Vec2::ZERO,
+            Vec2::new(0.5, 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(0., 1.),
+        ])
+        .unwrap();
+        assert_eq!(square.points().len(), 4);
+        assert_eq!(square.centroid(), Vec2::splat(0.5));
+    }
+
+    #[test]
+    fn polygon_new_invalid() {
+        assert_eq!(
+            Polygon::new([Vec2::ZERO, Vec2::X]),
+            Err(PolygonError::TooFewPoints)
+        );
+        assert_eq!(
+            Polygon::new([Vec2::ZERO, Vec2::X, Vec2::new(2., 0.)]),
+            Err(PolygonError::TooFewPoints)
+        );
+        assert_eq!(
+            Polygon::new([Vec2::ZERO, Vec2::X, Vec2::new(f32::NAN, 1.)]),
+            Err(PolygonError::NotFinite)
+        );
+        assert_eq!(
+            Polygon::new([
+                Vec2::ZERO,
+                Vec2::new(2., 0.),
+                Vec2::new(1., 0.5),
+                Vec2::new(2., 2.),
+                Vec2::new(0., 2.),
+            ]),
+            Err(PolygonError::NotConvex)
+        );
+        let star = (0..5).map(|i| Vec2::from_angle(i as f32 * std::f32::consts::TAU * 2. / 5.));
+        assert_eq!(Polygon::new(star), Err(PolygonError::NotConvex));
+    }
+
+    #[test]
+    fn polygon_transformed() {
+        let triangle = Polygon::new([Vec2::ZERO, Vec2::X, Vec2::Y]).unwrap();
+        let mirrored =
+            triangle.transformed(&Transform2::from_xy(1., 0.).with_scale(Vec2::new(-2., 1.)));
+        assert_eq!(
+            mirrored.points(),
+            &[Vec2::new(1., 1.), Vec2::new(-1., 0.), Vec2::new(1., 0.)]
+        );
+        assert!(mirrored.area() > 0.);
+    }
+
+    #[test]
+    fn polygon_decompose() {
+        let outline = [
+            Vec2::new(0., 0.),
+            Vec2::new(3., 0.),
+            Vec2::new(3., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 3.),
+            Vec2::new(0., 3.),
+        ];
+        let pieces = Polygon::decompose(outline).unwrap();
+        assert_eq!(pieces.len(), 2);
+        let area: f32 = pieces.iter().map(|piece| piece.area()).sum();
+        assert!((area - 5.).abs() < 0.0001);
+        let convex = Polygon::decompose([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]).unwrap();
+        assert_eq!(convex.len(), 1);
+        let bowtie = [Vec2::ZERO, Vec2::ONE, Vec2::X, Vec2::Y];
+        assert!(Polygon::decompose(bowtie).is_err());
+    }
+}
@@ -0,0 +1,424 @@
+use bevy::prelude::*;
+
+use super::{
+    convex::ToConvex, Aabb, Capsule, Circle, Ellipse, Obb, Point, Polygon, Segment, Shape,
+    TransformedShape,
+};
+
+/// A ray starting at `origin` and going along the unit vector `direction` for at most
+/// `max_distance`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray2 {
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub max_distance: f32,
+}
+
+impl Ray2 {
+    /// An unbounded ray. `direction` is normalized.
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+            max_distance: f32::INFINITY,
+        }
+    }
+
+    /// A ray going from `start` to `end`, for line of sight checks.
+    pub fn between(start: Vec2, end: Vec2) -> Self {
+        Self {
+            max_distance: start.distance(end),
+            ..Self::new(start, end - start)
+        }
+    }
+
+    pub fn with_max_distance(self, max_distance: f32) -> Self {
+        Self {
+            max_distance,
+            ..self
+        }
+    }
+
+    pub fn point_at(&self, distance: f32) -> Vec2 {
+        self.origin + self.direction * distance
+    }
+
+    pub fn raycast<T: Raycast>(&self, shape: &T) -> Option<RayHit> {
+        shape.raycast(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Distance from the ray origin to the hit.
+    pub distance: f32,
+    pub point: Vec2,
+    /// Unit surface normal at the hit. Rays starting inside a shape hit it at distance zero, with
+    /// the normal facing back along the ray.
+    pub normal: Vec2,
+}
+
+pub trait Raycast {
+    fn raycast(&self, ray: &Ray2) -> Option<RayHit>;
+}
+
+macro_rules! impl_raycast_convex {
+    ($($primitive:ty),+) => {
+        $(
+            impl Raycast for $primitive {
+                fn raycast(&self, ray: &Ray2) -> Option<RayHit> {
+                    if ray.direction == Vec2::ZERO {
+                        return None;
+                    }
+                    self.to_convex()
+                        .raycast(ray.origin, ray.direction, ray.max_distance)
+                        .map(|(distance, normal)| RayHit {
+                            distance,
+                            point: ray.point_at(distance),
+                            normal,
+                        })
+                }
+            }
+        )+
+    };
+}
+
+impl_raycast_convex!(Circle, Aabb, Obb, Polygon, Point, Segment, Capsule);
+
+impl Raycast for Ellipse {
+    fn raycast(&self, ray: &Ray2) -> Option<RayHit> {
+        if ray.direction == Vec2::ZERO {
+            return None;
+        }
+        // Solved exactly in the ellipse's local space, where it is a unit circle.
+        let inverse_rotation = Vec2::from_angle(-self.rotation);
+        let radii = self.size * 0.5;
+        let origin = inverse_rotation.rotate(ray.origin - self.position) / radii;
+        let direction = inverse_rotation.rotate(ray.direction) / radii;
+        let a = direction.length_squared();
+        let b = origin.dot(direction);
+        let c = origin.length_squared() - 1.;
+        if c < 0. {
+            return Some(RayHit {
+                distance: 0.,
+                point: ray.origin,
+                normal: -ray.direction,
+            });
+        }
+        let discriminant = b * b - a * c;
+        if b > 0. || discriminant < 0. {
+            return None;
+        }
+        let distance = (-b - discriminant.sqrt()) / a;
+        if distance > ray.max_distance {
+            return None;
+        }
+        let local_normal = (origin + direction * distance) / radii;
+        Some(RayHit {
+            distance,
+            point: ray.point_at(distance),
+            normal: Vec2::from_angle(self.rotation)
+                .rotate(local_normal)
+                .normalize_or_zero(),
+        })
+    }
+}
+
+impl Raycast for TransformedShape {
+    fn raycast(&self, ray: &Ray2) -> Option<RayHit> {
+        if let Shape::TileGrid(grid) = &self.shape {
+            return grid.raycast(&self.transform, ray);
+        }
+        transformed_shape_to_shape!(self, shape, shape.raycast(ray), None, |compound| compound
+            .transformed_parts(&self.transform)
+            .filter_map(|part| part.raycast(ray))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance)))
+    }
+}
+
+/// First hit of a [`TransformedShape`] moving along a vector, found by
+/// [`TransformedShape::shape_cast`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeCastHit {
+    /// Fraction of the motion, from zero to one, after which the shapes touch.
+    pub time_of_impact: f32,
+    /// Distance the shape travels before touching.
+    pub distance: f32,
+    /// Where the shapes touch.
+    pub point: Vec2,
+    /// Unit surface normal of the other shape where they touch. Shapes that already overlap hit
+    /// at time zero, with the normal facing against the motion, or pointing out of the other
+    /// shape when there is no motion.
+    pub normal: Vec2,
+}
+
+impl TransformedShape {
+    /// Sweeps the shape along `motion` and returns when it first touches `other`.
+    pub fn shape_cast(&self, motion: Vec2, other: &TransformedShape) -> Option<ShapeCastHit> {
+        let a = transformed_shape_to_shape!(self, a, Some(a.to_convex()), None, |compound| {
+            return first_shape_cast(
+                compound
+                    .transformed_parts(&self.transform)
+                    .filter_map(|part| part.shape_cast(motion, other)),
+            );
+        })?;
+        let b = transformed_shape_to_shape!(other, b, Some(b.to_convex()), None, |compound| {
+            return first_shape_cast(
+                compound
+                    .transformed_parts(&other.transform)
+                    .filter_map(|part| self.shape_cast(motion, &part)),
+            );
+        })?;
+        let length = motion.length();
+        if length == 0. {
+            return a.contact(&b).map(|contact| ShapeCastHit {
+                time_of_impact: 0.,
+                distance: 0.,
+                point: contact.points[0],
+                normal: -contact.normal,
+            });
+        }
+        let direction = motion / length;
+        let (distance, normal) =
+            a.minkowski_difference(&b)
+                .raycast(Vec2::ZERO, direction, length)?;
+        let mut moved = a;
+        for point in moved.points.iter_mut() {
+            *point += direction * distance;
+        }
+        let (_, point_a, point_b) = moved.closest_points(&b);
+        let point = if point_a == point_b {
+            point_a
+        } else {
+            point_a + (point_b - point_a).normalize() * moved.radius
+        };
+        Some(ShapeCastHit {
+            time_of_impact: distance / length,
+            distance,
+            point,
+            normal,
+        })
+    }
+}
+
+fn first_shape_cast(hits: impl Iterator<Item = ShapeCastHit>) -> Option<ShapeCastHit> {
+    hits.min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    const EPSILON: f32 = 0.0001;
+
+    fn assert_hit(hit: Option<RayHit>, distance: f32, normal: Vec2) {
+        let hit = hit.unwrap();
+        assert!((hit.distance - distance).abs() < EPSILON, "{:?}", hit);
+        assert!(hit.normal.abs_diff_eq(normal, EPSILON), "{:?}", hit);
+    }
+
+    #[test]
+    fn raycast_primitives() {
+        let ray = Ray2::new(Vec2::new(-5., 0.), Vec2::X);
+        assert_hit(
+            ray.raycast(&Circle {
+                position: Vec2::ZERO,
+                radius: 2.,
+            }),
+            4.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(&Aabb {
+                position: Vec2::new(1., 0.5),
+                size: Vec2::splat(2.),
+            }),
+            5.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(&Obb {
+                position: Vec2::ZERO,
+                size: Vec2::splat(2.),
+                rotation: FRAC_PI_2 * 0.5,
+            }),
+            5. - 2_f32.sqrt(),
+            Vec2::new(-1., 1.).normalize(),
+        );
+        assert_hit(
+            ray.raycast(&Ellipse {
+                position: Vec2::ZERO,
+                size: Vec2::new(6., 2.),
+                rotation: 0.,
+            }),
+            2.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(&Ellipse {
+                position: Vec2::ZERO,
+                size: Vec2::new(6., 2.),
+                rotation: FRAC_PI_2,
+            }),
+            4.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(
+                &Polygon::new([Vec2::new(-1., -1.), Vec2::new(1., 0.), Vec2::new(-1., 1.)])
+                    .unwrap(),
+            ),
+            4.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(&Segment {
+                start: Vec2::new(0., -1.),
+                end: Vec2::new(0., 1.),
+            }),
+            5.,
+            -Vec2::X,
+        );
+        assert_hit(
+            ray.raycast(&Capsule {
+                start: Vec2::new(0., 0.5),
+                end: Vec2::new(0., 3.),
+                radius: 2.,
+            }),
+            5. - 0.75_f32.sqrt(),
+            Vec2::new(-(0.75_f32.sqrt()), -0.5),
+        );
+        assert!(ray
+            .raycast(&Segment {
+                start: Vec2::new(0., 1.),
+                end: Vec2::new(0., 2.),
+            })
+            .is_none());
+        assert!(ray
+            .raycast(&Point {
+                position: Vec2::new(3., 0.)
+            })
+            .is_some());
+    }
+
+    #[test]
+    fn raycast_limits() {
+        let circle = Circle {
+            position: Vec2::ZERO,
+            radius: 2.,
+        };
+        assert!(Ray2::new(Vec2::new(-5., 0.), -Vec2::X)
+            .raycast(&circle)
+            .is_none());
+        assert!(Ray2::between(Vec2::new(-5., 0.), Vec2::new(-3., 0.))
+            .raycast(&circle)
+            .is_none());
+        assert!(Ray2::new(Vec2::new(-5., 0.), Vec2::X)
+            .with_max_distance(4.)
+            .raycast(&circle)
+            .is_some());
+        assert_hit(
+            Ray2::new(Vec2::new(0.5, 0.), Vec2::Y).raycast(&circle),
+            0.,
+            -Vec2::Y,
+        );
+        let hit = Ray2::new(Vec2::new(-5., 0.), Vec2::X)
+            .raycast(&circle)
+            .unwrap();
+        assert!(hit.point.abs_diff_eq(Vec2::new(-1., 0.), EPSILON));
+    }
+
+    #[test]
+    fn raycast_transformed_shape() {
+        let ground = Shape::Aabb {
+            size: Vec2::new(10., 1.),
+        }
+        .transformed_by(Transform2::from_xy(0., -3.).with_rotation(FRAC_PI_2 * 0.5));
+        let probe = Ray2::new(Vec2::ZERO, -Vec2::Y);
+        let hit = probe.raycast(&ground).unwrap();
+        assert!(hit
+            .normal
+            .abs_diff_eq(Vec2::new(-1., 1.).normalize(), EPSILON));
+        let stretched = Shape::Circle { radius: 2. }
+            .transformed_by(Transform2::from_xy(5., 0.).with_scale(Vec2::new(3., 1.)));
+        assert_hit(
+            Ray2::new(Vec2::ZERO, Vec2::X).raycast(&stretched),
+            2.,
+            -Vec2::X,
+        );
+        assert!(Ray2::new(Vec2::ZERO, Vec2::X)
+            .raycast(&TransformedShape::default())
+            .is_none());
+    }
+
+    #[test]
+    fn shape_cast() {
+        let player = Shape::Aabb {
+            size: Vec2::splat(2.),
+        }
+        .at(Vec2::ZERO);
+        let wall = Shape::Aabb {
+            size: Vec2::new(2., 10.),
+        }
+        .at(Vec2::new(5., 0.));
+        let hit = player.shape_cast(Vec2::new(6., 0.), &wall).unwrap();
+        assert!((hit.time_of_impact - 0.5).abs() < EPSILON);
+        assert!((hit.distance - 3.).abs() < EPSILON);
+        assert!(hit.normal.abs_diff_eq(-Vec2::X, EPSILON));
+        assert!((hit.point.x - 4.).abs() < EPSILON);
+        assert!(player.shape_cast(Vec2::new(2., 0.), &wall).is_none());
+        assert!(player.shape_cast(Vec2::new(0., 6.), &wall).is_none());
+        let ball = Shape::Circle { radius: 2. }.at(Vec2::new(0., 5.));
+        let floor = Shape::Capsule {
+            height: 10.,
+            radius: 1.,
+        }
+        .transformed_by(Transform2::from_rotation(FRAC_PI_2));
+        let hit = ball.shape_cast(Vec2::new(0., -10.), &floor).unwrap();
+        assert!((hit.distance - 3.5).abs() < EPSILON);
+        assert!(hit.normal.abs_diff_eq(Vec2::Y, EPSILON));
+        assert!(hit.point.abs_diff_eq(Vec2::new(0., 0.5), EPSILON));
+        let overlapping = ball.shape_cast(Vec2::new(0., -1.), &ball).unwrap();
+        assert_eq!(overlapping.time_of_impact, 0.);
+    }
+
+    #[test]
+    fn shape_cast_touches() {
+        let shapes = [
+            Shape::Circle { radius: 1. },
+            Shape::Aabb {
+                size: Vec2::new(1., 2.),
+            },
+            Shape::polygon([Vec2::ZERO, Vec2::X, Vec2::Y]).unwrap(),
+            Shape::Capsule {
+                height: 2.,
+                radius: 0.5,
+            },
+            Shape::Segment {
+                start: Vec2::new(-1., 0.),
+                end: Vec2::new(1., 0.5),
+            },
+        ];
+        for a in shapes.iter() {
+            for b in shapes.iter() {
+                let moving = a.transformed_by(Transform2::from_xy(-4., 0.3).with_rotation(0.3));
+                let target = b.transformed_by(Transform2::from_xy(1., 0.5).with_rotation(-0.2));
+                let motion = Vec2::new(8., 0.);
+                let hit = moving.shape_cast(motion, &target).unwrap();
+                let mut before = moving.clone();
+                before.transform.translation += motion * (hit.time_of_impact - 0.001);
+                let mut after = moving.clone();
+                after.transform.translation += motion * (hit.time_of_impact + 0.001);
+                assert!(!before.colliding_with(&target), "{:?} {:?}", a, b);
+                assert!(after.colliding_with(&target), "{:?} {:?}", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// A line segment from `start` to `end`, for lasers, tripwires and other thin shapes.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+impl Segment {
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        super::convex::closest_point_on_segment(point, self.start, self.end)
+    }
+}
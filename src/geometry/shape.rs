@@ -2,67 +2,72 @@ use bevy::prelude::*;
 
 use crate::transform2::Transform2;
 
-use super::{Aabb, Circle, CollidingWith};
+use super::{
+    convex::ToConvex, Aabb, Capsule, Circle, CompoundShape, Point, Polygon, PolygonError, Segment,
+    TileGridCollider,
+};
 
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Clone, Debug)]
 pub enum Shape {
     #[default]
     None,
+    /// A circle whose real radius is `radius * 0.5`. Use [`Shape::to_mesh`] to draw it, so the
+    /// visuals match the hitbox.
     Circle {
         radius: f32,
     },
     Aabb {
         size: Vec2,
     },
+    Polygon(Polygon),
+    /// An upright capsule. `height` is the full height, including the caps.
+    Capsule {
+        height: f32,
+        radius: f32,
+    },
+    Segment {
+        start: Vec2,
+        end: Vec2,
+    },
+    Point,
+    Compound(CompoundShape),
+    TileGrid(TileGridCollider),
 }
 
 impl Shape {
     pub fn at(&self, translation: Vec2) -> TransformedShape {
+        self.transformed_by(Transform2::from_translation(translation))
+    }
+
+    pub fn transformed_by(&self, transform: Transform2) -> TransformedShape {
         TransformedShape {
-            transform: Transform2::from_translation(translation),
-            shape: *self,
+            transform,
+            shape: self.clone(),
         }
     }
+
+    /// Creates a [`Shape::Polygon`], validating that the points form a convex polygon.
+    pub fn polygon(points: impl IntoIterator<Item = Vec2>) -> Result<Shape, PolygonError> {
+        Ok(Shape::Polygon(Polygon::new(points)?))
+    }
+
+    /// Bounding box of the shape around its own origin, or `None` for [`Shape::None`].
+    pub fn bounding_aabb(&self) -> Option<Aabb> {
+        self.transformed_by(Transform2::default()).bounding_aabb()
+    }
+
+    /// Creates a [`Shape::Compound`] from parts placed relative to the shape.
+    pub fn compound(parts: impl IntoIterator<Item = (Transform2, Shape)>) -> Shape {
+        Shape::Compound(CompoundShape::new(parts))
+    }
 }
 
-#[derive(Default, Copy, Clone, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct TransformedShape {
     pub transform: Transform2,
     pub shape: Shape,
 }
 
-impl TransformedShape {
-    pub fn colliding_with(&self, other: &TransformedShape) -> bool {
-        macro_rules! match_shape {
-            ($shape:expr, $transform:expr, $name:ident, $expr:expr) => {
-                match $shape {
-                    Shape::None => return false,
-                    Shape::Circle { radius } => {
-                        let $name = Circle {
-                            position: $transform.translation,
-                            radius: *radius,
-                        };
-                        $expr
-                    }
-                    Shape::Aabb { size } => {
-                        let $name = Aabb {
-                            position: $transform.translation,
-                            size: *size,
-                        };
-                        $expr
-                    }
-                }
-            };
-        }
-        match_shape!(
-            &self.shape,
-            self.transform,
-            a,
-            match_shape!(&other.shape, other.transform, b, a.colliding_with(&b))
-        )
-    }
-}
-
 impl From<Circle> for TransformedShape {
     fn from(circle: Circle) -> Self {
         Self {
@@ -74,6 +79,15 @@ impl From<Circle> for TransformedShape {
     }
 }
 
+impl From<Polygon> for TransformedShape {
+    fn from(polygon: Polygon) -> Self {
+        Self {
+            shape: Shape::Polygon(polygon),
+            transform: Transform2::default(),
+        }
+    }
+}
+
 impl From<Aabb> for TransformedShape {
     fn from(aabb: Aabb) -> Self {
         Self {
@@ -83,24 +97,211 @@ impl From<Aabb> for TransformedShape {
     }
 }
 
+impl From<Point> for TransformedShape {
+    fn from(point: Point) -> Self {
+        Self {
+            shape: Shape::Point,
+            transform: Transform2::from_translation(point.position),
+        }
+    }
+}
+
+impl From<Segment> for TransformedShape {
+    fn from(segment: Segment) -> Self {
+        Self {
+            shape: Shape::Segment {
+                start: segment.start,
+                end: segment.end,
+            },
+            transform: Transform2::default(),
+        }
+    }
+}
+
+impl From<Capsule> for TransformedShape {
+    fn from(capsule: Capsule) -> Self {
+        let axis = capsule.end - capsule.start;
+        Self {
+            shape: Shape::Capsule {
+                height: axis.length() + capsule.radius,
+                radius: capsule.radius,
+            },
+            transform: Transform2::from_translation((capsule.start + capsule.end) * 0.5)
+                .with_rotation(if axis == Vec2::ZERO {
+                    0.
+                } else {
+                    Vec2::Y.angle_between(axis)
+                }),
+        }
+    }
+}
+
+/// Converts a [`TransformedShape`] into the world space primitive that represents it, binding it
+/// to `$name` and evaluating `$expr`. Scale and rotation are applied, picking the simplest
+/// primitive possible: circles only become ellipses when non-uniformly scaled, and boxes only
+/// become [`Obb`](crate::geometry::Obb)s when rotated. Non-uniformly scaled capsules are
+/// approximated by a polygon. Compounds have no single primitive, so they are bound to `$compound`
+/// and `$compound_expr` is evaluated instead, usually combining queries on each part. Tile grids
+/// are bound to `$compound` as the compound of their merged rectangles.
+///
+/// With `near $bounds, |$parts| $parts_expr` instead, `$parts` is bound to an iterator over the
+/// parts in world space. For tile grids, it only holds the rectangles that overlap or touch
+/// `$bounds`, an `Option<Aabb>` of the other shape, so queries that only care about overlapping
+/// parts don't visit the whole level. `@arms` takes separate `|$compound|` and `|$grid|`
+/// expressions, for queries that search tile grids some other way.
 macro_rules! transformed_shape_to_shape {
-    ($transformed_shape:expr, $name:ident, $expr:expr, $none_expr:expr) => {
+    (
+        $transformed_shape:expr,
+        $name:ident,
+        $expr:expr,
+        $none_expr:expr,
+        |$compound:ident| $compound_expr:expr
+    ) => {
+        transformed_shape_to_shape!(
+            @arms $transformed_shape,
+            $name,
+            $expr,
+            $none_expr,
+            |$compound| $compound_expr,
+            |grid| {
+                let $compound = grid.compound();
+                $compound_expr
+            }
+        )
+    };
+    (
+        $transformed_shape:expr,
+        $name:ident,
+        $expr:expr,
+        $none_expr:expr,
+        near $bounds:expr,
+        |$parts:ident| $parts_expr:expr
+    ) => {
+        transformed_shape_to_shape!(
+            @arms $transformed_shape,
+            $name,
+            $expr,
+            $none_expr,
+            |compound| {
+                #[allow(unused_mut)]
+                let mut $parts = compound.transformed_parts(&$transformed_shape.transform);
+                $parts_expr
+            },
+            |grid| {
+                #[allow(unused_mut)]
+                let mut $parts = grid.parts_near(&$transformed_shape.transform, $bounds);
+                $parts_expr
+            }
+        )
+    };
+    (
+        @arms $transformed_shape:expr,
+        $name:ident,
+        $expr:expr,
+        $none_expr:expr,
+        |$compound:ident| $compound_expr:expr,
+        |$grid:ident| $grid_expr:expr
+    ) => {
         match &$transformed_shape.shape {
             crate::geometry::Shape::None => $none_expr,
+            crate::geometry::Shape::Compound($compound) => $compound_expr,
+            crate::geometry::Shape::TileGrid($grid) => $grid_expr,
             crate::geometry::Shape::Circle { radius } => {
-                let $name = crate::geometry::Circle {
-                    position: $transformed_shape.transform.translation,
-                    radius: *radius,
+                let transform = &$transformed_shape.transform;
+                let scale = transform.scale.abs();
+                if scale.x == scale.y {
+                    let $name = crate::geometry::Circle {
+                        position: transform.translation,
+                        radius: *radius * scale.x,
+                    };
+                    $expr
+                } else {
+                    let $name = crate::geometry::Ellipse {
+                        position: transform.translation,
+                        size: Vec2::splat(*radius) * scale,
+                        rotation: transform.rotation,
+                    };
+                    $expr
+                }
+            }
+            crate::geometry::Shape::Aabb { size } => {
+                let transform = &$transformed_shape.transform;
+                if transform.rotation == 0. {
+                    let $name = crate::geometry::Aabb {
+                        position: transform.translation,
+                        size: *size * transform.scale.abs(),
+                    };
+                    $expr
+                } else {
+                    let $name = crate::geometry::Obb {
+                        position: transform.translation,
+                        size: *size * transform.scale.abs(),
+                        rotation: transform.rotation,
+                    };
+                    $expr
+                }
+            }
+            crate::geometry::Shape::Polygon(polygon) => {
+                let $name = polygon.transformed(&$transformed_shape.transform);
+                $expr
+            }
+            crate::geometry::Shape::Capsule { height, radius } => {
+                let transform = &$transformed_shape.transform;
+                let scale = transform.scale.abs();
+                let capsule = crate::geometry::Capsule::vertical(Vec2::ZERO, *height, *radius);
+                if scale.x == scale.y {
+                    let $name = crate::geometry::Capsule {
+                        start: transform.transform_point(capsule.start),
+                        end: transform.transform_point(capsule.end),
+                        radius: *radius * scale.x,
+                    };
+                    $expr
+                } else {
+                    let $name = capsule
+                        .to_polygon(crate::geometry::ELLIPSE_SEGMENTS)
+                        .transformed(transform);
+                    $expr
+                }
+            }
+            crate::geometry::Shape::Segment { start, end } => {
+                let transform = &$transformed_shape.transform;
+                let $name = crate::geometry::Segment {
+                    start: transform.transform_point(*start),
+                    end: transform.transform_point(*end),
                 };
                 $expr
             }
-            crate::geometry::Shape::Aabb { size } => {
-                let $name = crate::geometry::Aabb {
+            crate::geometry::Shape::Point => {
+                let $name = crate::geometry::Point {
                     position: $transformed_shape.transform.translation,
-                    size: *size,
                 };
                 $expr
             }
         }
     };
 }
+
+impl TransformedShape {
+    /// Bounding box of the shape in world space, or `None` for [`Shape::None`]. Rotation and scale
+    /// are taken into account, and the box is tight for every shape except compounds, whose cached
+    /// local box is rotated.
+    pub fn bounding_aabb(&self) -> Option<Aabb> {
+        transformed_shape_to_shape!(self, shape, Some(shape.bounding_aabb()), None, |compound| {
+            compound.bounding_aabb(&self.transform)
+        })
+    }
+
+    /// Outlines of the shape in world space, one per part, as returned by `Convex::outline`.
+    pub(crate) fn outlines(&self) -> Vec<Vec<Vec2>> {
+        transformed_shape_to_shape!(
+            self,
+            shape,
+            vec![shape.to_convex().outline()],
+            vec![],
+            |compound| compound
+                .transformed_parts(&self.transform)
+                .flat_map(|part| part.outlines())
+                .collect()
+        )
+    }
+}
@@ -0,0 +1,288 @@
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+    sprite::Mesh2dHandle,
+};
+
+use crate::flow::FlowSet;
+
+use super::{GeometrySystem, Shape};
+
+pub(crate) struct ShapeMeshPlugin;
+
+impl Plugin for ShapeMeshPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            shape_mesh_update
+                .in_set(GeometrySystem::ShapeMesh)
+                .in_base_set(FlowSet::VisualUpdate)
+                .run_if(resource_exists::<Assets<Mesh>>()),
+        );
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeMeshMode {
+    #[default]
+    Fill,
+    Outline,
+}
+
+/// Keeps the entity's [`Mesh2dHandle`] in sync with `shape`, regenerating the mesh whenever the
+/// component changes.
+#[derive(Component, Default, Debug, Clone)]
+pub struct ShapeMesh {
+    pub shape: Shape,
+    pub mode: ShapeMeshMode,
+}
+
+impl ShapeMesh {
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            mode: ShapeMeshMode::Fill,
+        }
+    }
+
+    pub fn outline(self) -> Self {
+        Self {
+            mode: ShapeMeshMode::Outline,
+            ..self
+        }
+    }
+
+    pub fn to_mesh(&self) -> Mesh {
+        match self.mode {
+            ShapeMeshMode::Fill => self.shape.to_mesh(),
+            ShapeMeshMode::Outline => self.shape.to_outline_mesh(),
+        }
+    }
+}
+
+/// A 2D mesh generated from a [`Shape`], so visuals match the hitbox of a [`Collider`] with the
+/// same shape. Position it with a [`Transform2`](crate::transform2::Transform2).
+///
+/// [`Collider`]: super::Collider
+#[derive(Bundle, Clone, Default)]
+pub struct ShapeMeshBundle {
+    pub shape_mesh: ShapeMesh,
+    pub mesh: Mesh2dHandle,
+    pub material: Handle<ColorMaterial>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+impl ShapeMeshBundle {
+    pub fn new(shape: Shape, material: Handle<ColorMaterial>) -> Self {
+        Self {
+            shape_mesh: ShapeMesh::new(shape),
+            material,
+            ..Default::default()
+        }
+    }
+
+    pub fn outline(self) -> Self {
+        Self {
+            shape_mesh: self.shape_mesh.outline(),
+            ..self
+        }
+    }
+}
+
+impl Shape {
+    /// Filled mesh of the shape around its own origin, with UVs spanning its bounding box.
+    /// Points and segments have no area, so their meshes are empty.
+    pub fn to_mesh(&self) -> Mesh {
+        let outlines = self.transformed_by(default()).outlines();
+        let bounds = self.bounding_aabb().unwrap_or_default();
+        let (min, size) = (bounds.min(), bounds.size.max(Vec2::splat(f32::EPSILON)));
+        let mut positions: Vec<[f32; 3]> = vec![];
+        let mut uvs: Vec<[f32; 2]> = vec![];
+        let mut indices: Vec<u32> = vec![];
+        for outline in outlines.iter().filter(|outline| outline.len() >= 3) {
+            // Outlines are convex and counter-clockwise, so a fan covers them.
+            let first = positions.len() as u32;
+            for i in 1..outline.len() as u32 - 1 {
+                indices.extend([first, first + i, first + i + 1]);
+            }
+            for point in outline.iter() {
+                let uv = (*point - min) / size;
+                positions.push(point.extend(0.).to_array());
+                uvs.push([uv.x, 1. - uv.y]);
+            }
+        }
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; positions.len()]);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh
+    }
+
+    /// Outline of the shape around its own origin as a line list. Points have no outline.
+    pub fn to_outline_mesh(&self) -> Mesh {
+        let mut positions: Vec<[f32; 3]> = vec![];
+        for outline in self.transformed_by(default()).outlines() {
+            let edges = match outline.len() {
+                0 | 1 => 0,
+                2 => 1,
+                count => count,
+            };
+            for i in 0..edges {
+                positions.push(outline[i].extend(0.).to_array());
+                positions.push(outline[(i + 1) % outline.len()].extend(0.).to_array());
+            }
+        }
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh
+    }
+}
+
+fn shape_mesh_update(
+    mut shape_mesh_query: Query<(&ShapeMesh, &mut Mesh2dHandle), Changed<ShapeMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (shape_mesh, mut mesh_handle) in shape_mesh_query.iter_mut() {
+        // A new mesh each time, since the old handle may be shared with other entities.
+        mesh_handle.0 = meshes.add(shape_mesh.to_mesh());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        prelude::*,
+        render::mesh::{Indices, VertexAttributeValues},
+        sprite::Mesh2dHandle,
+    };
+
+    use crate::{
+        geometry::{prelude::*, ELLIPSE_SEGMENTS},
+        transform2::Transform2,
+    };
+
+    use super::shape_mesh_update;
+
+    fn positions(mesh: &Mesh) -> Vec<Vec2> {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions
+                .iter()
+                .map(|position| Vec2::new(position[0], position[1]))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn triangle_count(mesh: &Mesh) -> usize {
+        match mesh.indices() {
+            Some(Indices::U32(indices)) => indices.len() / 3,
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn shape_mesh_matches_hitbox() {
+        let shapes = [
+            Shape::Circle { radius: 100. },
+            Shape::Aabb {
+                size: Vec2::new(40., 20.),
+            },
+            Shape::Capsule {
+                height: 80.,
+                radius: 20.,
+            },
+            Shape::polygon([
+                Vec2::new(-10., -10.),
+                Vec2::new(30., 0.),
+                Vec2::new(0., 20.),
+            ])
+            .unwrap(),
+        ];
+        for shape in shapes.iter() {
+            let mesh = shape.to_mesh();
+            let vertices = positions(&mesh);
+            assert!(!vertices.is_empty());
+            assert_eq!(triangle_count(&mesh), vertices.len() - 2);
+            let hitbox = shape.at(Vec2::ZERO);
+            for position in vertices.iter() {
+                assert!(hitbox.signed_distance(*position).abs() < 0.001, "{shape:?}");
+            }
+            let outline = positions(&shape.to_outline_mesh());
+            assert_eq!(outline.len(), vertices.len() * 2);
+        }
+        let circle = positions(&Shape::Circle { radius: 100. }.to_mesh());
+        assert_eq!(circle.len(), ELLIPSE_SEGMENTS);
+        assert!(circle
+            .iter()
+            .all(|position| (position.length() - 50.).abs() < 0.001));
+    }
+
+    #[test]
+    fn shape_mesh_compound_and_degenerate() {
+        let compound = Shape::compound([
+            (
+                Transform2::from_xy(-10., 0.),
+                Shape::Aabb {
+                    size: Vec2::splat(4.),
+                },
+            ),
+            (
+                Transform2::from_xy(10., 0.),
+                Shape::Aabb {
+                    size: Vec2::splat(4.),
+                },
+            ),
+        ]);
+        let mesh = compound.to_mesh();
+        assert_eq!(positions(&mesh).len(), 8);
+        assert_eq!(triangle_count(&mesh), 4);
+        assert!(positions(&mesh).contains(&Vec2::new(12., 2.)));
+
+        assert!(positions(&Shape::Point.to_mesh()).is_empty());
+        assert!(positions(&Shape::Point.to_outline_mesh()).is_empty());
+        let segment = Shape::Segment {
+            start: Vec2::ZERO,
+            end: Vec2::X,
+        };
+        assert!(positions(&segment.to_mesh()).is_empty());
+        assert_eq!(positions(&segment.to_outline_mesh()).len(), 2);
+    }
+
+    #[test]
+    fn shape_mesh_system() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Mesh>()
+            .add_system(shape_mesh_update);
+        let entity = app
+            .world
+            .spawn(ShapeMeshBundle::new(
+                Shape::Aabb {
+                    size: Vec2::splat(2.),
+                },
+                Handle::default(),
+            ))
+            .id();
+        app.update();
+        let handle = app.world.get::<Mesh2dHandle>(entity).unwrap().0.clone();
+        let meshes = app.world.resource::<Assets<Mesh>>();
+        assert_eq!(positions(meshes.get(&handle).unwrap()).len(), 4);
+
+        let sharing = app.world.spawn(Mesh2dHandle(handle.clone())).id();
+        app.world.get_mut::<ShapeMesh>(entity).unwrap().shape = Shape::Circle { radius: 2. };
+        app.update();
+        let new_handle = app.world.get::<Mesh2dHandle>(entity).unwrap().0.clone();
+        assert_ne!(new_handle, handle);
+        assert_eq!(app.world.get::<Mesh2dHandle>(sharing).unwrap().0, handle);
+        let meshes = app.world.resource::<Assets<Mesh>>();
+        assert_eq!(positions(meshes.get(&handle).unwrap()).len(), 4);
+        assert_eq!(
+            positions(meshes.get(&new_handle).unwrap()).len(),
+            ELLIPSE_SEGMENTS
+        );
+    }
+}
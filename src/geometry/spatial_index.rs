@@ -0,0 +1,474 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{flow::FlowSet, transform2::Transform2};
+
+use super::{
+    convex::ToConvex, Aabb, Circle, Collider, CollidingWith, ContainsPoint, DistanceToPoint,
+    GeometrySystem, TransformedShape,
+};
+
+pub(crate) struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>().add_system(
+            spatial_index_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(GeometrySystem::SpatialIndex)
+                .in_base_set(FlowSet::EntitySpawn)
+                .after(FlowSet::EntityMovement)
+                .before(GeometrySystem::Collisions),
+        );
+    }
+}
+
+/// Spatial hash of every [`Collider`], for finding the colliders in an area without testing all
+/// of them.
+///
+/// Colliders are bucketed into square cells by their bounding box. The index is updated each
+/// fixed update, after [`FlowSet::EntityMovement`], for colliders whose [`Collider`] or
+/// [`Transform2`] changed. Colliders can also be inserted by hand with [`SpatialIndex::insert`],
+/// for example to index shapes that aren't entities' colliders. The update only removes entries it
+/// inserted itself, so those stay until removed by hand.
+#[derive(Resource)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    entries: HashMap<Entity, SpatialIndexEntry>,
+    /// Entities inserted by [`spatial_index_update`] from their [`Collider`].
+    colliders: HashSet<Entity>,
+}
+
+#[derive(Debug, Clone)]
+struct SpatialIndexEntry {
+    shape: TransformedShape,
+    min_cell: IVec2,
+    max_cell: IVec2,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(64.)
+    }
+}
+
+impl SpatialIndex {
+    /// Creates an empty index. `cell_size` should be around the size of a typical collider.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+            colliders: HashSet::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&TransformedShape> {
+        self.entries.get(&entity).map(|entry| &entry.shape)
+    }
+
+    /// Inserts or moves an entity's shape. Shapes without a bounding box, such as
+    /// [`Shape::None`](super::Shape::None), are removed from the index.
+    pub fn insert(&mut self, entity: Entity, shape: TransformedShape) {
+        let Some(aabb) = shape.bounding_aabb() else {
+            self.remove_entry(entity);
+            return;
+        };
+        let (min_cell, max_cell) = self.cell_range(aabb);
+        if let Some(entry) = self.entries.get_mut(&entity) {
+            if entry.min_cell == min_cell && entry.max_cell == max_cell {
+                entry.shape = shape;
+                return;
+            }
+        }
+        self.remove_entry(entity);
+        for cell in cells(min_cell, max_cell) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+        self.entries.insert(
+            entity,
+            SpatialIndexEntry {
+                shape,
+                min_cell,
+                max_cell,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<TransformedShape> {
+        self.colliders.remove(&entity);
+        self.remove_entry(entity)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.colliders.clear();
+    }
+
+    /// Inserts the shape of an entity's [`Collider`], which is then removed with the collider.
+    fn insert_collider(&mut self, entity: Entity, shape: TransformedShape) {
+        self.insert(entity, shape);
+        self.colliders.insert(entity);
+    }
+
+    fn remove_entry(&mut self, entity: Entity) -> Option<TransformedShape> {
+        let entry = self.entries.remove(&entity)?;
+        for cell in cells(entry.min_cell, entry.max_cell) {
+            if let Some(entities) = self.cells.get_mut(&cell) {
+                entities.retain(|cell_entity| *cell_entity != entity);
+                if entities.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        Some(entry.shape)
+    }
+
+    /// Entities whose shape collides with `aabb`.
+    pub fn query_aabb(&self, aabb: Aabb) -> Vec<(Entity, &TransformedShape)> {
+        self.query(aabb, |shape| shape.colliding_with(&aabb))
+    }
+
+    /// Entities whose shape collides with `circle`.
+    pub fn query_circle(&self, circle: Circle) -> Vec<(Entity, &TransformedShape)> {
+        self.query(circle.to_convex().aabb(), |shape| {
+            shape.colliding_with(&circle)
+        })
+    }
+
+    /// Entities whose shape contains `point`.
+    pub fn query_point(&self, point: Vec2) -> Vec<(Entity, &TransformedShape)> {
+        let bounds = Aabb {
+            position: point,
+            size: Vec2::ZERO,
+        };
+        self.query(bounds, |shape| shape.contains_point(point))
+    }
+
+    /// Entities whose shape collides with `shape`.
+    pub fn query_shape(&self, shape: &TransformedShape) -> Vec<(Entity, &TransformedShape)> {
+        match shape.bounding_aabb() {
+            Some(bounds) => self.query(bounds, |other| other.colliding_with(shape)),
+            None => vec![],
+        }
+    }
+
+    /// The `k` entities closest to `point`, nearest first. Entities containing the point are at
+    /// distance zero.
+    pub fn nearest(&self, point: Vec2, k: usize) -> Vec<(Entity, &TransformedShape)> {
+        let mut found: Vec<(f32, Entity, &TransformedShape)> = vec![];
+        let mut visited = HashSet::new();
+        let center = self.cell(point);
+        let mut ring = 0;
+        while k > 0 && visited.len() < self.entries.len() {
+            // Once a ring covers more cells than are occupied, checking every entity is cheaper.
+            let ring_cells: Vec<IVec2> =
+                if (ring * 2 + 1) * (ring * 2 + 1) > self.cells.len() as i32 {
+                    self.cells.keys().copied().collect()
+                } else {
+                    ring_cells(center, ring).collect()
+                };
+            for cell in ring_cells.into_iter() {
+                let Some(entities) = self.cells.get(&cell) else {
+                    continue;
+                };
+                for entity in entities.iter() {
+                    if visited.insert(*entity) {
+                        let shape = &self.entries[entity].shape;
+                        found.push((shape.distance_to_point(point), *entity, shape));
+                    }
+                }
+            }
+            found.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+            found.truncate(k);
+            // Entities that haven't been found yet are at least `ring` cells away.
+            if found.len() == k && found[k - 1].0 <= ring as f32 * self.cell_size {
+                break;
+            }
+            ring += 1;
+        }
+        found
+            .into_iter()
+            .map(|(_, entity, shape)| (entity, shape))
+            .collect()
+    }
+
+    fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    fn cell_range(&self, aabb: Aabb) -> (IVec2, IVec2) {
+        (self.cell(aabb.min()), self.cell(aabb.max()))
+    }
+
+    /// Entities in the cells covered by `bounds` that pass `filter`, each returned once.
+    fn query(
+        &self,
+        bounds: Aabb,
+        filter: impl Fn(&TransformedShape) -> bool,
+    ) -> Vec<(Entity, &TransformedShape)> {
+        let (min_cell, max_cell) = self.cell_range(bounds);
+        let mut result = vec![];
+        for cell in cells(min_cell, max_cell) {
+            let Some(entities) = self.cells.get(&cell) else {
+                continue;
+            };
+            for entity in entities.iter() {
+                let entry = &self.entries[entity];
+                // An entity covering several cells is only tested in the first one the query
+                // reaches.
+                if entry.min_cell.max(min_cell) != cell {
+                    continue;
+                }
+                if filter(&entry.shape) {
+                    result.push((*entity, &entry.shape));
+                }
+            }
+        }
+        result
+    }
+}
+
+fn cells(min_cell: IVec2, max_cell: IVec2) -> impl Iterator<Item = IVec2> {
+    (min_cell.y..=max_cell.y)
+        .flat_map(move |y| (min_cell.x..=max_cell.x).map(move |x| IVec2::new(x, y)))
+}
+
+/// Cells at Chebyshev distance `ring` from `center`.
+fn ring_cells(center: IVec2, ring: i32) -> impl Iterator<Item = IVec2> {
+    cells(center - ring, center + ring)
+        .filter(move |cell| (*cell - center).abs().max_element() == ring)
+}
+
+type ChangedColliderQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Collider, &'static Transform2),
+    Or<(Changed<Collider>, Changed<Transform2>)>,
+>;
+
+fn spatial_index_update(
+    collider_query: Query<(Entity, &Collider, &Transform2)>,
+    changed_query: ChangedColliderQuery,
+    mut removed_colliders: RemovedComponents<Collider>,
+    mut spatial_index: ResMut<SpatialIndex>,
+) {
+    for entity in removed_colliders.iter() {
+        spatial_index.remove(entity);
+    }
+    for (entity, collider, transform) in changed_query.iter() {
+        spatial_index.insert_collider(entity, collider.at(*transform));
+    }
+    // Removals may be missed when fixed updates are skipped for a few frames.
+    if spatial_index.colliders.len() != collider_query.iter().len() {
+        let stale: Vec<Entity> = spatial_index
+            .colliders
+            .iter()
+            .filter(|entity| !collider_query.contains(**entity))
+            .copied()
+            .collect();
+        for entity in stale.into_iter() {
+            spatial_index.remove(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    use super::spatial_index_update;
+
+    fn shapes(world: &mut World) -> Vec<(Entity, TransformedShape)> {
+        let shapes = [
+            Shape::Circle { radius: 30. },
+            Shape::Aabb {
+                size: Vec2::new(80., 20.),
+            },
+            Shape::Capsule {
+                height: 150.,
+                radius: 20.,
+            },
+            Shape::Point,
+        ];
+        (0..200)
+            .map(|i| {
+                let i = i as f32;
+                let transform = Transform2::from_xy((i * 73.1) % 900. - 450., (i * 31.7) % 700.)
+                    .with_rotation(i * 0.3)
+                    .with_scale(Vec2::new(1. + i % 2., 1.));
+                (
+                    world.spawn_empty().id(),
+                    shapes[i as usize % shapes.len()].transformed_by(transform),
+                )
+            })
+            .collect()
+    }
+
+    fn sorted(mut entities: Vec<Entity>) -> Vec<Entity> {
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn spatial_index_queries() {
+        let mut world = World::new();
+        let shapes = shapes(&mut world);
+        let mut spatial_index = SpatialIndex::new(50.);
+        for (entity, shape) in shapes.iter() {
+            spatial_index.insert(*entity, shape.clone());
+        }
+        assert_eq!(spatial_index.len(), shapes.len());
+        let aabb = Aabb {
+            position: Vec2::new(10., 300.),
+            size: Vec2::new(300., 150.),
+        };
+        let circle = Circle {
+            position: Vec2::new(-200., 100.),
+            radius: 250.,
+        };
+        let point = Vec2::new(100., 400.);
+        let brute_force = |filter: &dyn Fn(&TransformedShape) -> bool| {
+            sorted(
+                shapes
+                    .iter()
+                    .filter(|(_, shape)| filter(shape))
+                    .map(|(entity, _)| *entity)
+                    .collect(),
+            )
+        };
+        let query = |result: Vec<(Entity, &TransformedShape)>| {
+            sorted(result.into_iter().map(|(entity, _)| entity).collect())
+        };
+        let expected = brute_force(&|shape| shape.colliding_with(&aabb));
+        assert!(!expected.is_empty());
+        assert_eq!(query(spatial_index.query_aabb(aabb)), expected);
+        let expected = brute_force(&|shape| shape.colliding_with(&circle));
+        assert!(!expected.is_empty());
+        assert_eq!(query(spatial_index.query_circle(circle)), expected);
+        let expected = brute_force(&|shape| shape.contains_point(point));
+        assert_eq!(query(spatial_index.query_point(point)), expected);
+        let shape = Shape::Capsule {
+            height: 400.,
+            radius: 30.,
+        }
+        .transformed_by(Transform2::from_xy(0., 200.).with_rotation(1.));
+        let expected = brute_force(&|other| other.colliding_with(&shape));
+        assert_eq!(query(spatial_index.query_shape(&shape)), expected);
+    }
+
+    #[test]
+    fn spatial_index_nearest() {
+        let mut world = World::new();
+        let shapes = shapes(&mut world);
+        let mut spatial_index = SpatialIndex::new(50.);
+        for (entity, shape) in shapes.iter() {
+            spatial_index.insert(*entity, shape.clone());
+        }
+        for point in [Vec2::new(0., 300.), Vec2::new(2000., -1000.)] {
+            let nearest: Vec<Entity> = spatial_index
+                .nearest(point, 5)
+                .into_iter()
+                .map(|(entity, _)| entity)
+                .collect();
+            let mut expected: Vec<(f32, Entity)> = shapes
+                .iter()
+                .map(|(entity, shape)| (shape.distance_to_point(point), *entity))
+                .collect();
+            expected.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+            assert_eq!(nearest.len(), 5);
+            for (entity, (distance, _)) in nearest.iter().zip(expected.iter()) {
+                let shape = spatial_index.get(*entity).unwrap();
+                assert_eq!(shape.distance_to_point(point), *distance);
+            }
+        }
+        assert!(spatial_index.nearest(Vec2::ZERO, 0).is_empty());
+        assert_eq!(spatial_index.nearest(Vec2::ZERO, 1000).len(), shapes.len());
+    }
+
+    #[test]
+    fn spatial_index_update_system() {
+        let mut world = World::new();
+        world.init_resource::<SpatialIndex>();
+        let mut schedule = Schedule::new();
+        schedule.add_system(spatial_index_update);
+        let entity = world
+            .spawn((
+                Collider::new(Shape::Aabb {
+                    size: Vec2::splat(10.),
+                }),
+                Transform2::from_xy(0., 0.),
+            ))
+            .id();
+        schedule.run(&mut world);
+        let far = Vec2::new(200., 0.);
+        assert_eq!(world.resource::<SpatialIndex>().len(), 1);
+        assert!(world.resource::<SpatialIndex>().query_point(far).is_empty());
+
+        world.get_mut::<Transform2>(entity).unwrap().scale = Vec2::new(50., 1.);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpatialIndex>().query_point(far).len(), 1);
+
+        world.get_mut::<Transform2>(entity).unwrap().rotation = std::f32::consts::FRAC_PI_2;
+        schedule.run(&mut world);
+        assert!(world.resource::<SpatialIndex>().query_point(far).is_empty());
+        assert_eq!(
+            world
+                .resource::<SpatialIndex>()
+                .query_point(Vec2::new(0., 200.))
+                .len(),
+            1
+        );
+
+        world.entity_mut(entity).remove::<Collider>();
+        schedule.run(&mut world);
+        assert!(world.resource::<SpatialIndex>().is_empty());
+    }
+
+    #[test]
+    fn spatial_index_update_keeps_hand_inserted() {
+        let mut world = World::new();
+        world.init_resource::<SpatialIndex>();
+        let mut schedule = Schedule::new();
+        schedule.add_system(spatial_index_update);
+        let collider = world
+            .spawn((
+                Collider::new(Shape::Circle { radius: 10. }),
+                Transform2::from_xy(0., 0.),
+            ))
+            .id();
+        let by_hand = world.spawn_empty().id();
+        world.resource_mut::<SpatialIndex>().insert(
+            by_hand,
+            Shape::Circle { radius: 10. }.transformed_by(Transform2::from_xy(100., 0.)),
+        );
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpatialIndex>().len(), 2);
+        assert!(world.resource::<SpatialIndex>().get(by_hand).is_some());
+
+        world.despawn(collider);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpatialIndex>().len(), 1);
+        assert!(world.resource::<SpatialIndex>().get(by_hand).is_some());
+    }
+}
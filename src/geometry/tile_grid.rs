@@ -0,0 +1,647 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::transform2::Transform2;
+
+use super::{Aabb, CompoundShape, Ray2, RayHit, Raycast, Shape, TransformedShape};
+
+/// A grid of solid and empty cells, for tile based levels. Use it as a [`Shape::TileGrid`].
+///
+/// Cell `(0, 0)` has its min corner at `origin`, and rows go up. Adjacent solid cells are merged
+/// into as few rectangles as possible, which collision, contact and distance queries test like the
+/// parts of a [`CompoundShape`], skipping the rectangles on rows away from the other shape.
+/// Raycasts and point queries walk the cells directly.
+///
+/// The cells and rectangles are shared between clones, since shapes are cloned by every query.
+#[derive(Default, Clone, Debug)]
+pub struct TileGridCollider {
+    size: UVec2,
+    cell_size: Vec2,
+    origin: Vec2,
+    cells: Arc<Vec<u64>>,
+    merged: Arc<MergedCells>,
+}
+
+/// The merged rectangles of a [`TileGridCollider`], indexed by the rows they cover.
+#[derive(Default, Clone, Debug)]
+struct MergedCells {
+    compound: CompoundShape,
+    /// First and last cell of each rectangle, in the order of the compound's parts.
+    rects: Vec<[UVec2; 2]>,
+    /// Indices of the rectangles covering each row.
+    rows: Vec<Vec<usize>>,
+}
+
+impl TileGridCollider {
+    /// An empty grid of `size` cells.
+    pub fn new(size: UVec2, cell_size: Vec2) -> Self {
+        let cell_count = (size.x * size.y) as usize;
+        let mut grid = Self {
+            size,
+            cell_size,
+            origin: Vec2::ZERO,
+            cells: Arc::new(vec![0; cell_count.div_ceil(64)]),
+            merged: default(),
+        };
+        grid.set_rects(Vec::new());
+        grid
+    }
+
+    pub fn with_origin(self, origin: Vec2) -> Self {
+        let mut grid = Self { origin, ..self };
+        grid.set_rects(grid.merged.rects.clone());
+        grid
+    }
+
+    pub fn with_solid(mut self, cells: impl IntoIterator<Item = UVec2>) -> Self {
+        self.set_cells(cells.into_iter().map(|cell| (cell, true)));
+        self
+    }
+
+    /// Creates a grid from rows of text, top row first, where `#` marks a solid cell.
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = &'a str>, cell_size: Vec2) -> Self {
+        let rows: Vec<_> = rows.into_iter().collect();
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = rows.len();
+        let solid = rows.iter().enumerate().flat_map(|(row_index, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, tile)| *tile == '#')
+                .map(move |(x, _)| UVec2::new(x as u32, (height - 1 - row_index) as u32))
+        });
+        Self::new(UVec2::new(width as u32, height as u32), cell_size).with_solid(solid)
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn cell_size(&self) -> Vec2 {
+        self.cell_size
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Whether `cell` is solid. Cells outside the grid are empty.
+    pub fn is_solid(&self, cell: UVec2) -> bool {
+        self.index(cell)
+            .is_some_and(|index| self.cells[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    pub fn set(&mut self, cell: UVec2, solid: bool) {
+        self.set_cells([(cell, solid)]);
+    }
+
+    /// Sets many cells at once, merging the solid cells a single time. Cells outside the grid are
+    /// ignored.
+    ///
+    /// Rectangles are merged again from the lowest row a changed cell can affect, which is the
+    /// bottom of the rectangles touching it, up to the top of the grid. Editing the top rows is
+    /// cheap, while editing the bottom row merges the whole grid again.
+    pub fn set_cells(&mut self, cells: impl IntoIterator<Item = (UVec2, bool)>) {
+        let mut from_row = self.size.y;
+        let bits = Arc::make_mut(&mut self.cells);
+        for (cell, solid) in cells {
+            let Some(index) = Self::cell_index(self.size, cell) else {
+                continue;
+            };
+            let bit = 1 << (index % 64);
+            if (bits[index / 64] & bit != 0) == solid {
+                continue;
+            }
+            bits[index / 64] ^= bit;
+            // Rectangles covering the cell, or stopped from growing up by it, start over.
+            let rows = cell.y.saturating_sub(1)..=cell.y;
+            for rect in rows.flat_map(|y| self.merged.rows[y as usize].iter()) {
+                let [min, max] = self.merged.rects[*rect];
+                if (min.x..=max.x).contains(&cell.x) {
+                    from_row = from_row.min(min.y);
+                }
+            }
+            from_row = from_row.min(cell.y);
+        }
+        if from_row < self.size.y {
+            self.merge_cells(from_row);
+        }
+    }
+
+    /// The cell containing `point`, relative to the grid, if it is inside the grid.
+    pub fn cell_at(&self, point: Vec2) -> Option<UVec2> {
+        let cell = ((point - self.origin) / self.cell_size).floor();
+        (cell.cmpge(Vec2::ZERO).all() && cell.cmplt(self.size.as_vec2()).all())
+            .then(|| cell.as_uvec2())
+    }
+
+    /// Bounds of `cell`, relative to the grid.
+    pub fn cell_aabb(&self, cell: UVec2) -> Aabb {
+        let min = self.origin + cell.as_vec2() * self.cell_size;
+        Aabb::from_min_max(min, min + self.cell_size)
+    }
+
+    /// Bounds of the whole grid, including empty cells, relative to the grid.
+    pub fn bounds(&self) -> Aabb {
+        Aabb::from_min_max(
+            self.origin,
+            self.origin + self.size.as_vec2() * self.cell_size,
+        )
+    }
+
+    /// The merged rectangles covering the solid cells, relative to the grid.
+    pub fn rects(&self) -> impl Iterator<Item = Aabb> + '_ {
+        self.merged
+            .compound
+            .parts()
+            .iter()
+            .filter_map(|(transform, shape)| match shape {
+                Shape::Aabb { size } => Some(Aabb {
+                    position: transform.translation,
+                    size: *size,
+                }),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn compound(&self) -> &CompoundShape {
+        &self.merged.compound
+    }
+
+    /// The merged rectangles placed by `transform` that overlap or touch `aabb`, in world space.
+    /// Only the rows covered by `aabb` are visited, and nothing is returned for `None`.
+    pub(crate) fn parts_near<'a>(
+        &'a self,
+        transform: &'a Transform2,
+        aabb: Option<Aabb>,
+    ) -> impl Iterator<Item = TransformedShape> + 'a {
+        let merged = &*self.merged;
+        aabb.and_then(|aabb| self.cell_range(transform, aabb))
+            .into_iter()
+            .flat_map(move |[min, max]| {
+                (min.y..=max.y).flat_map(move |y| {
+                    merged.rows[y as usize].iter().copied().filter(move |rect| {
+                        let [rect_min, rect_max] = merged.rects[*rect];
+                        // Each rectangle is returned once, on the first row it shares with the
+                        // range.
+                        rect_min.y.max(min.y) == y && rect_min.x <= max.x && rect_max.x >= min.x
+                    })
+                })
+            })
+            .map(move |rect| {
+                let (part_transform, shape) = &merged.compound.parts()[rect];
+                shape.transformed_by(transform.mul_transform(*part_transform))
+            })
+    }
+
+    /// The merged rectangle placed by `transform` with the smallest `distance` from a shape whose
+    /// bounding box is `aabb`, and that distance.
+    ///
+    /// A rectangle can't be closer than the gap between its box and `aabb`, so the rectangles
+    /// around `aabb` are measured first, widening the search only while a farther one could still
+    /// be closer.
+    pub(crate) fn nearest_part(
+        &self,
+        transform: &Transform2,
+        aabb: Option<Aabb>,
+        distance: impl Fn(&TransformedShape) -> f32,
+    ) -> Option<(TransformedShape, f32)> {
+        let aabb = aabb?;
+        let bounds = self.compound().bounding_aabb(transform)?;
+        let step = (self.cell_size * transform.scale.abs())
+            .max_element()
+            .max(f32::EPSILON);
+        let mut margin = 0.;
+        loop {
+            let searched = Aabb {
+                position: aabb.position,
+                size: aabb.size + Vec2::splat(margin * 2.),
+            };
+            let nearest = self
+                .parts_near(transform, Some(searched))
+                .map(|part| {
+                    let part_distance = distance(&part);
+                    (part, part_distance)
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            let covers_grid = searched.min().cmple(bounds.min()).all()
+                && searched.max().cmpge(bounds.max()).all();
+            match nearest {
+                Some((_, nearest_distance))
+                    if nearest_distance <= margin || nearest_distance.is_nan() || covers_grid =>
+                {
+                    return nearest;
+                }
+                Some((_, nearest_distance)) => margin = nearest_distance,
+                None if covers_grid => return None,
+                None => margin = (margin * 2.).max(step),
+            }
+        }
+    }
+
+    fn cell_index(size: UVec2, cell: UVec2) -> Option<usize> {
+        (cell.x < size.x && cell.y < size.y).then(|| (cell.y * size.x + cell.x) as usize)
+    }
+
+    fn index(&self, cell: UVec2) -> Option<usize> {
+        Self::cell_index(self.size, cell)
+    }
+
+    /// Range of cells overlapping or touching `aabb`, given in world space, or `None` if it misses
+    /// the grid.
+    fn cell_range(&self, transform: &Transform2, aabb: Aabb) -> Option<[UVec2; 2]> {
+        if self.size.cmpeq(UVec2::ZERO).any() {
+            return None;
+        }
+        let last = self.size - UVec2::ONE;
+        let (min, max) = (aabb.min(), aabb.max());
+        let local = Aabb::from_points(
+            [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+                .map(|corner| Self::to_local(transform, corner)),
+        )?;
+        if !local.min().is_finite() || !local.max().is_finite() {
+            return Some([UVec2::ZERO, last]);
+        }
+        let min_cell = ((local.min() - self.origin) / self.cell_size).ceil() - Vec2::ONE;
+        let max_cell = ((local.max() - self.origin) / self.cell_size).floor();
+        if max_cell.cmplt(Vec2::ZERO).any() || min_cell.cmpgt(last.as_vec2()).any() {
+            return None;
+        }
+        Some([
+            min_cell.max(Vec2::ZERO).as_uvec2(),
+            max_cell.min(last.as_vec2()).as_uvec2(),
+        ])
+    }
+
+    /// Greedily covers the solid cells with rectangles, growing each one right, then up. The
+    /// rectangles starting below `from_row` are kept, since the cells they cover didn't change.
+    fn merge_cells(&mut self, from_row: u32) {
+        let mut rects: Vec<[UVec2; 2]> = self
+            .merged
+            .rects
+            .iter()
+            .copied()
+            .take_while(|[min, _]| min.y < from_row)
+            .collect();
+        let columns = self.size.x;
+        let covered_index = |cell: UVec2| ((cell.y - from_row) * columns + cell.x) as usize;
+        let mut covered = vec![false; (columns * (self.size.y - from_row)) as usize];
+        for [min, max] in rects.iter() {
+            for y in min.y.max(from_row)..=max.y {
+                for x in min.x..=max.x {
+                    covered[covered_index(UVec2::new(x, y))] = true;
+                }
+            }
+        }
+        let free =
+            |covered: &[bool], cell: UVec2| self.is_solid(cell) && !covered[covered_index(cell)];
+        for y in from_row..self.size.y {
+            for x in 0..self.size.x {
+                if !free(&covered, UVec2::new(x, y)) {
+                    continue;
+                }
+                let mut width = 1;
+                while x + width < self.size.x && free(&covered, UVec2::new(x + width, y)) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while y + height < self.size.y
+                    && (x..x + width).all(|x| free(&covered, UVec2::new(x, y + height)))
+                {
+                    height += 1;
+                }
+                for covered_y in y..y + height {
+                    for covered_x in x..x + width {
+                        covered[covered_index(UVec2::new(covered_x, covered_y))] = true;
+                    }
+                }
+                rects.push([UVec2::new(x, y), UVec2::new(x + width - 1, y + height - 1)]);
+            }
+        }
+        self.set_rects(rects);
+    }
+
+    fn set_rects(&mut self, rects: Vec<[UVec2; 2]>) {
+        let mut compound = CompoundShape::default();
+        let mut rows = vec![Vec::new(); self.size.y as usize];
+        for (index, [min, max]) in rects.iter().enumerate() {
+            let min_corner = self.cell_aabb(*min).min();
+            let size = (*max - *min + UVec2::ONE).as_vec2() * self.cell_size;
+            compound.push(
+                Transform2::from_translation(min_corner + size * 0.5),
+                Shape::Aabb { size },
+            );
+            for row in rows[min.y as usize..=max.y as usize].iter_mut() {
+                row.push(index);
+            }
+        }
+        self.merged = Arc::new(MergedCells {
+            compound,
+            rects,
+            rows,
+        });
+    }
+
+    fn to_local(transform: &Transform2, point: Vec2) -> Vec2 {
+        Vec2::from_angle(-transform.rotation).rotate(point - transform.translation)
+            / transform.scale
+    }
+
+    pub(crate) fn contains_point(&self, transform: &Transform2, point: Vec2) -> bool {
+        self.cell_at(Self::to_local(transform, point))
+            .is_some_and(|cell| self.is_solid(cell))
+    }
+
+    /// Walks the cells crossed by the ray, in the grid's local space, until one is solid.
+    pub(crate) fn raycast(&self, transform: &Transform2, ray: &Ray2) -> Option<RayHit> {
+        if ray.direction == Vec2::ZERO {
+            return None;
+        }
+        let origin = Self::to_local(transform, ray.origin);
+        let direction = Self::to_local(transform, transform.translation + ray.direction);
+        // The mapping to local space is affine, so distances along the unnormalized local
+        // direction are world distances.
+        let local_length = direction.length();
+        let entry = self.bounds().raycast(&Ray2 {
+            origin,
+            direction: direction / local_length,
+            max_distance: ray.max_distance * local_length,
+        })?;
+        let mut distance = entry.distance / local_length;
+        let mut normal = entry.normal;
+        let entry_point = origin + direction * distance;
+        let last_cell = self.size.as_ivec2() - IVec2::ONE;
+        let mut cell = ((entry_point - self.origin) / self.cell_size)
+            .floor()
+            .as_ivec2()
+            .clamp(IVec2::ZERO, last_cell);
+
+        let step = IVec2::new(
+            direction
+                .x
+                .partial_cmp(&0.)
+                .map_or(0, |ordering| ordering as i32),
+            direction
+                .y
+                .partial_cmp(&0.)
+                .map_or(0, |ordering| ordering as i32),
+        );
+        let next_boundary = self.origin + (cell + step.max(IVec2::ZERO)).as_vec2() * self.cell_size;
+        let mut next_distance = Vec2::select(
+            step.cmpeq(IVec2::ZERO),
+            Vec2::splat(f32::INFINITY),
+            (next_boundary - origin) / direction,
+        );
+        let distance_per_cell = (self.cell_size / direction).abs();
+
+        loop {
+            if self.is_solid(cell.as_uvec2()) {
+                let normal = if distance == 0. {
+                    -ray.direction
+                } else {
+                    Vec2::from_angle(transform.rotation)
+                        .rotate(normal / transform.scale)
+                        .normalize_or_zero()
+                };
+                return Some(RayHit {
+                    distance,
+                    point: ray.point_at(distance),
+                    normal,
+                });
+            }
+            if next_distance.x < next_distance.y {
+                cell.x += step.x;
+                distance = next_distance.x;
+                next_distance.x += distance_per_cell.x;
+                normal = Vec2::new(-step.x as f32, 0.);
+            } else {
+                cell.y += step.y;
+                distance = next_distance.y;
+                next_distance.y += distance_per_cell.y;
+                normal = Vec2::new(0., -step.y as f32);
+            }
+            if distance > ray.max_distance
+                || cell.cmplt(IVec2::ZERO).any()
+                || cell.cmpgt(last_cell).any()
+            {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        transform2::Transform2,
+    };
+
+    const EPSILON: f32 = 0.0001;
+
+    fn level_grid() -> TileGridCollider {
+        TileGridCollider::from_rows(
+            [
+                "#......#", //
+                "#..##..#", //
+                "#......#", //
+                "########", //
+            ],
+            Vec2::splat(10.),
+        )
+    }
+
+    #[test]
+    fn tile_grid_cells() {
+        let mut grid = level_grid();
+        assert_eq!(grid.size(), UVec2::new(8, 4));
+        assert!(grid.is_solid(UVec2::new(0, 0)));
+        assert!(grid.is_solid(UVec2::new(3, 2)));
+        assert!(!grid.is_solid(UVec2::new(1, 1)));
+        assert!(!grid.is_solid(UVec2::new(8, 0)));
+        assert_eq!(grid.cell_at(Vec2::new(35., 25.)), Some(UVec2::new(3, 2)));
+        assert_eq!(grid.cell_at(Vec2::new(-1., 0.)), None);
+
+        // The floor, both walls and the platform.
+        assert_eq!(grid.rects().count(), 4);
+        let covered: f32 = grid.rects().map(|rect| rect.size.x * rect.size.y).sum();
+        assert!((covered - 16. * 100.).abs() < EPSILON);
+
+        grid.set(UVec2::new(3, 2), false);
+        assert!(!grid.is_solid(UVec2::new(3, 2)));
+        assert_eq!(grid.rects().count(), 4);
+        grid.set_cells([(UVec2::new(4, 2), false), (UVec2::new(1, 1), true)]);
+        assert_eq!(grid.rects().count(), 4);
+    }
+
+    /// A level with scattered platforms, and the same level as a plain compound of its rectangles.
+    fn scattered_level(transform: Transform2) -> (TransformedShape, TransformedShape) {
+        let cells = (0..40)
+            .flat_map(|x| (0..20).map(move |y| UVec2::new(x, y)))
+            .filter(|cell| (cell.x * 7 + cell.y * 13) % 11 < 3 || cell.y == 0);
+        let grid = TileGridCollider::new(UVec2::new(40, 20), Vec2::splat(10.)).with_solid(cells);
+        let compound = Shape::Compound(grid.compound().clone()).transformed_by(transform);
+        (Shape::TileGrid(grid).transformed_by(transform), compound)
+    }
+
+    #[test]
+    fn tile_grid_partial_merge() {
+        let mut grid = level_grid();
+        let edits = [
+            (UVec2::new(3, 2), false),
+            (UVec2::new(2, 2), true),
+            (UVec2::new(4, 0), false),
+            (UVec2::new(5, 1), true),
+            (UVec2::new(5, 2), true),
+            (UVec2::new(0, 3), false),
+        ];
+        for (index, edit) in edits.into_iter().enumerate() {
+            grid.set_cells([edit]);
+            let solid = (0..8)
+                .flat_map(|x| (0..4).map(move |y| UVec2::new(x, y)))
+                .filter(|cell| grid.is_solid(*cell));
+            let merged = TileGridCollider::new(grid.size(), grid.cell_size()).with_solid(solid);
+            assert_eq!(
+                grid.rects().collect::<Vec<_>>(),
+                merged.rects().collect::<Vec<_>>(),
+                "{index}"
+            );
+        }
+    }
+
+    #[test]
+    fn tile_grid_culled_queries() {
+        for transform in [
+            Transform2::from_xy(-200., -100.),
+            Transform2::from_xy(50., 20.)
+                .with_rotation(0.7)
+                .with_scale(Vec2::new(1.5, 0.5)),
+        ] {
+            let (level, compound) = scattered_level(transform);
+            let Shape::TileGrid(grid) = &level.shape else {
+                unreachable!()
+            };
+            let mut culled = 0;
+            for step in 0..200 {
+                let step = step as f32;
+                let position = transform.transform_point(Vec2::new(
+                    (step * 37.) % 420. - 10.,
+                    (step * 23.) % 210. - 5.,
+                ));
+                let shapes = [
+                    Shape::Circle { radius: 12. }.at(position),
+                    Shape::Aabb {
+                        size: Vec2::new(25., 6.),
+                    }
+                    .transformed_by(Transform2::from_translation(position).with_rotation(step)),
+                ];
+                for shape in shapes {
+                    assert_eq!(
+                        level.colliding_with(&shape),
+                        compound.colliding_with(&shape),
+                        "{position}"
+                    );
+                    assert_eq!(
+                        level.contact_with(&shape).map(|contact| contact.depth),
+                        compound.contact_with(&shape).map(|contact| contact.depth),
+                        "{position}"
+                    );
+                    assert!(
+                        (level.distance_to(&shape) - compound.distance_to(&shape)).abs() < EPSILON,
+                        "{position}"
+                    );
+                    assert_eq!(
+                        shape.distance_to(&level),
+                        shape.distance_to(&compound),
+                        "{position}"
+                    );
+                    let near = grid
+                        .parts_near(&level.transform, shape.bounding_aabb())
+                        .count();
+                    culled += grid.compound().parts().len() - near;
+                }
+                assert!(
+                    (level.signed_distance(position) - compound.signed_distance(position)).abs()
+                        < EPSILON
+                );
+                assert!(level
+                    .closest_point(position)
+                    .abs_diff_eq(compound.closest_point(position), EPSILON));
+            }
+            assert!(culled > 0);
+        }
+    }
+
+    #[test]
+    fn tile_grid_queries() {
+        let level = Shape::TileGrid(level_grid().with_origin(Vec2::new(-40., -20.))).at(Vec2::ZERO);
+        assert!(level.contains_point(Vec2::new(-5., -15.)));
+        assert!(level.contains_point(Vec2::new(-5., 5.)));
+        assert!(!level.contains_point(Vec2::new(-5., -5.)));
+        assert!(!level.contains_point(Vec2::new(100., 0.)));
+
+        let player = Circle {
+            position: Vec2::new(-20., -7.),
+            radius: 10.,
+        };
+        assert!(level.colliding_with(&player));
+        assert!(player.colliding_with(&level));
+        let contact = level.contact_with(&player).unwrap();
+        assert!(contact.normal.abs_diff_eq(Vec2::Y, EPSILON));
+        assert!((contact.depth - 2.).abs() < EPSILON);
+        assert!(!level.colliding_with(&Circle {
+            position: Vec2::new(-20., -5.),
+            radius: 8.,
+        }));
+
+        let aabb = level.bounding_aabb().unwrap();
+        assert!(aabb.min().abs_diff_eq(Vec2::new(-40., -20.), EPSILON));
+        assert!(aabb.max().abs_diff_eq(Vec2::new(40., 20.), EPSILON));
+    }
+
+    #[test]
+    fn tile_grid_raycast() {
+        let level = Shape::TileGrid(level_grid()).transformed_by(Transform2::from_xy(100., 0.));
+        let hit = level
+            .raycast(&Ray2::new(Vec2::new(115., 15.), Vec2::X))
+            .unwrap();
+        assert!((hit.distance - 55.).abs() < EPSILON);
+        assert!(hit.normal.abs_diff_eq(Vec2::NEG_X, EPSILON));
+
+        let hit = level
+            .raycast(&Ray2::new(Vec2::new(135., 35.), Vec2::NEG_Y))
+            .unwrap();
+        assert!((hit.distance - 5.).abs() < EPSILON);
+        assert!(hit.normal.abs_diff_eq(Vec2::Y, EPSILON));
+
+        let hit = level
+            .raycast(&Ray2::new(Vec2::new(50., 55.), Vec2::new(1., -1.)))
+            .unwrap();
+        assert!(hit.point.abs_diff_eq(Vec2::new(100., 5.), EPSILON));
+        assert!(hit.normal.abs_diff_eq(Vec2::NEG_X, EPSILON));
+
+        let hit = level
+            .raycast(&Ray2::new(Vec2::new(105., 5.), Vec2::X))
+            .unwrap();
+        assert_eq!(hit.distance, 0.);
+        assert!(level
+            .raycast(&Ray2::new(Vec2::new(115., 15.), Vec2::X).with_max_distance(50.))
+            .is_none());
+        assert!(level
+            .raycast(&Ray2::new(Vec2::new(115., 35.), Vec2::Y))
+            .is_none());
+
+        let rotated = Shape::TileGrid(level_grid().with_origin(Vec2::new(-40., -20.)))
+            .transformed_by(Transform2::from_rotation(std::f32::consts::FRAC_PI_2))
+            .raycast(&Ray2::new(Vec2::new(100., 0.), Vec2::NEG_X))
+            .unwrap();
+        assert!((rotated.distance - 80.).abs() < EPSILON);
+        assert!(rotated.normal.abs_diff_eq(Vec2::X, EPSILON));
+    }
+}
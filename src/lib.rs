@@ -1,5 +1,9 @@
 use bevy::{app::PluginGroupBuilder, prelude::*};
 
+// Lets tests derive `AssetStruct`, whose generated code names this crate `tinae`.
+#[cfg(test)]
+extern crate self as tinae;
+
 macro_rules! features {
     ($(($feature:literal, $mod:ident, $plugin:ident)),+) => {
         $(
@@ -37,11 +41,16 @@ macro_rules! features {
 features!(
     ("tinae_asset_struct", asset_struct, AssetStructPlugin),
     ("tinae_cursor", cursor, CursorPlugin),
+    ("tinae_fixed_point", fixed_point, FixedPointPlugin),
     ("tinae_fixed_timestep", fixed_timestep, FixedTimestepPlugin),
     ("tinae_flow", flow, FlowPlugin),
     ("tinae_force_ratio", force_ratio, ForceRatioPlugin),
     ("tinae_geometry", geometry, GeometryPlugin),
+    ("tinae_motion", motion, MotionPlugin),
+    ("tinae_navigation", navigation, NavigationPlugin),
+    ("tinae_picking", picking, PickingPlugin),
     ("tinae_scenes", scenes, ScenesPlugin),
+    ("tinae_schedule_graph", schedule_graph, ScheduleGraphPlugin),
     ("tinae_screen_fade", screen_fade, ScreenFadePlugin),
     ("tinae_spine", spine, SpinePlugin),
     ("tinae_sub_assets", sub_assets, SubAssetsPlugin),
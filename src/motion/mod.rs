@@ -0,0 +1,8 @@
+mod motion;
+pub use motion::*;
+
+pub mod prelude {
+    pub use super::{
+        Acceleration2, AngularVelocity, Gravity, LinearDamping, MaxSpeed, MotionSystem, Velocity2,
+    };
+}
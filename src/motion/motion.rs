@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "tinae_geometry")]
+use crate::geometry::GeometrySystem;
+use crate::{flow::FlowSet, transform2::Transform2};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum MotionSystem {
+    Integrate,
+}
+
+pub struct MotionPlugin;
+
+impl Plugin for MotionPlugin {
+    fn build(&self, app: &mut App) {
+        let integrate = motion_integrate
+            .in_set(MotionSystem::Integrate)
+            .in_base_set(FlowSet::EntityMovement);
+        #[cfg(feature = "tinae_geometry")]
+        let integrate = integrate.before(GeometrySystem::KinematicBody);
+        app.add_system(integrate.in_schedule(CoreSchedule::FixedUpdate));
+    }
+}
+
+/// Linear velocity in units per second, moving the entity's [`Transform2`] every fixed update.
+///
+/// Entities with a [`KinematicBody`](crate::geometry::KinematicBody) should set its velocity
+/// instead, so they don't move through colliders. Entities moved by velocity, such as moving
+/// platforms, move before kinematic bodies, so bodies standing on them see where they are this
+/// update.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Velocity2(pub Vec2);
+
+/// Angular velocity in radians per second, rotating the entity's [`Transform2`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct AngularVelocity(pub f32);
+
+/// Acceleration in units per second squared, added to [`Velocity2`].
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Acceleration2(pub Vec2);
+
+/// Acceleration added to [`Velocity2`] on top of [`Acceleration2`]. Kept separate so gameplay code
+/// can overwrite the acceleration every update without losing gravity.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Gravity(pub Vec2);
+
+/// Decay rate of [`Velocity2`] in 1/s: after `t` seconds the velocity is multiplied by
+/// `e^(-rate * t)`. Zero keeps the velocity, and higher values stop the entity faster.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct LinearDamping(pub f32);
+
+/// Maximum length of [`Velocity2`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct MaxSpeed(pub f32);
+
+/// Advances `velocity` by one step of `delta_seconds` with semi-implicit Euler, and returns the
+/// translation to apply: acceleration, damping and the speed limit are applied to the velocity
+/// first, and the entity is then moved by the new velocity.
+pub fn integrate_velocity(
+    velocity: &mut Vec2,
+    acceleration: Vec2,
+    damping: f32,
+    max_speed: Option<f32>,
+    delta_seconds: f32,
+) -> Vec2 {
+    *velocity += acceleration * delta_seconds;
+    if damping > 0. {
+        *velocity *= (-damping * delta_seconds).exp();
+    }
+    if let Some(max_speed) = max_speed {
+        *velocity = velocity.clamp_length_max(max_speed);
+    }
+    *velocity * delta_seconds
+}
+
+type MotionQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform2,
+        Option<&'static mut Velocity2>,
+        Option<&'static AngularVelocity>,
+        Option<&'static Acceleration2>,
+        Option<&'static Gravity>,
+        Option<&'static LinearDamping>,
+        Option<&'static MaxSpeed>,
+    ),
+    Or<(With<Velocity2>, With<AngularVelocity>)>,
+>;
+
+fn motion_integrate(mut motion_query: MotionQuery, time: Res<FixedTime>) {
+    let delta_seconds = time.period.as_secs_f32();
+    for (mut transform, velocity, angular_velocity, acceleration, gravity, damping, max_speed) in
+        motion_query.iter_mut()
+    {
+        if let Some(mut velocity) = velocity {
+            let acceleration = acceleration
+                .map(|acceleration| acceleration.0)
+                .unwrap_or_default()
+                + gravity.map(|gravity| gravity.0).unwrap_or_default();
+            let translation = integrate_velocity(
+                &mut velocity.0,
+                acceleration,
+                damping.map(|damping| damping.0).unwrap_or_default(),
+                max_speed.map(|max_speed| max_speed.0),
+                delta_seconds,
+            );
+            transform.translation += translation;
+        }
+        if let Some(angular_velocity) = angular_velocity {
+            transform.rotation += angular_velocity.0 * delta_seconds;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{motion::prelude::*, transform2::Transform2};
+
+    use super::{integrate_velocity, motion_integrate};
+
+    const EPSILON: f32 = 0.0001;
+
+    #[test]
+    fn integrate_semi_implicit_euler() {
+        let mut velocity = Vec2::new(1., 0.);
+        let translation = integrate_velocity(&mut velocity, Vec2::new(0., -10.), 0., None, 0.1);
+        assert!(velocity.abs_diff_eq(Vec2::new(1., -1.), EPSILON));
+        assert!(translation.abs_diff_eq(Vec2::new(0.1, -0.1), EPSILON));
+    }
+
+    #[test]
+    fn integrate_damping_and_max_speed() {
+        let mut velocity = Vec2::new(10., 0.);
+        integrate_velocity(&mut velocity, Vec2::ZERO, 2., None, 0.5);
+        assert!((velocity.x - 10. * (-1_f32).exp()).abs() < EPSILON);
+        let mut velocity = Vec2::ZERO;
+        for _ in 0..100 {
+            integrate_velocity(&mut velocity, Vec2::new(0., 100.), 0., Some(5.), 0.1);
+        }
+        assert!((velocity.length() - 5.).abs() < EPSILON);
+    }
+
+    #[test]
+    fn motion_system() {
+        let mut world = World::new();
+        world.insert_resource(FixedTime::new_from_secs(0.5));
+        let mut schedule = Schedule::new();
+        schedule.add_system(motion_integrate);
+        let falling = world
+            .spawn((
+                Transform2::new(),
+                Velocity2(Vec2::new(2., 0.)),
+                Gravity(Vec2::new(0., -4.)),
+            ))
+            .id();
+        let spinning = world.spawn((Transform2::new(), AngularVelocity(1.))).id();
+        let still = world
+            .spawn((Transform2::from_xy(1., 1.), Acceleration2(Vec2::X)))
+            .id();
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        let transform = world.get::<Transform2>(falling).unwrap();
+        assert!(transform
+            .translation
+            .abs_diff_eq(Vec2::new(2., -3.), EPSILON));
+        assert_eq!(
+            world.get::<Velocity2>(falling).unwrap().0,
+            Vec2::new(2., -4.)
+        );
+        assert_eq!(world.get::<Transform2>(spinning).unwrap().rotation, 1.);
+        assert_eq!(
+            world.get::<Transform2>(still).unwrap().translation,
+            Vec2::new(1., 1.)
+        );
+    }
+}
@@ -0,0 +1,8 @@
+mod nav_grid;
+mod pathfinding;
+pub use nav_grid::*;
+pub use pathfinding::*;
+
+pub mod prelude {
+    pub use super::{Connectivity, CornerRule, FlowField, NavGrid, NavObstacle, NavigationSystem};
+}
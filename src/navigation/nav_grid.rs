@@ -0,0 +1,622 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::{
+    flow::FlowSet,
+    geometry::{
+        Aabb, Capsule, CollidingWith, ContainsPoint, GeometrySystem, Segment, Shape,
+        TransformedShape,
+    },
+    transform2::Transform2,
+};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum NavigationSystem {
+    NavGrid,
+}
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            nav_grid_update
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(NavigationSystem::NavGrid)
+                .in_base_set(FlowSet::EntitySpawn)
+                .after(FlowSet::EntityMovement)
+                .after(GeometrySystem::Collisions)
+                .run_if(resource_exists::<NavGrid>()),
+        );
+    }
+}
+
+/// A shape that blocks the cells of the [`NavGrid`] it overlaps, placed at the entity's
+/// [`Transform2`].
+#[derive(Component, Debug, Clone)]
+pub struct NavObstacle {
+    pub shape: Shape,
+}
+
+impl NavObstacle {
+    pub fn new(shape: Shape) -> Self {
+        Self { shape }
+    }
+}
+
+/// How paths move between neighbouring cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only horizontal and vertical moves.
+    Four,
+    /// Diagonal moves too, following a [`CornerRule`].
+    #[default]
+    Eight,
+}
+
+/// When a diagonal move is allowed past the two cells it cuts between.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CornerRule {
+    /// Both cells must be free, so paths never touch the corner of an obstacle.
+    #[default]
+    NoCornerCutting,
+    /// One of the cells must be free, so paths can cut corners but not squeeze between two
+    /// diagonal obstacles.
+    OneFree,
+    /// Diagonal moves are always allowed.
+    Always,
+}
+
+/// A grid of free and blocked cells for pathfinding, covering `size` cells from `origin` in world
+/// space.
+///
+/// Cells are blocked by the shapes of [`NavObstacle`] entities, which are rasterized each fixed
+/// update after [`FlowSet::EntityMovement`] when their [`NavObstacle`] or [`Transform2`] changed.
+/// Only the cells of obstacles that changed are updated, since every cell lists the obstacles
+/// covering it. Obstacles can also be inserted by hand with [`NavGrid::insert`], and stay until
+/// removed by hand since the update only removes the obstacles it inserted itself.
+///
+/// Insert the grid as a resource for the obstacles to be tracked. See [`NavGrid::find_path`] and
+/// [`NavGrid::flow_field`] to navigate it.
+#[derive(Resource, Debug, Clone)]
+pub struct NavGrid {
+    size: UVec2,
+    cell_size: Vec2,
+    origin: Vec2,
+    pub connectivity: Connectivity,
+    pub corner_rule: CornerRule,
+    /// Obstacles blocking each cell.
+    blockers: Vec<Vec<Entity>>,
+    obstacles: HashMap<Entity, NavObstacleEntry>,
+    /// Obstacles blocking no cell, such as ones outside the grid or thinner than a cell's edge.
+    off_grid: HashSet<Entity>,
+    /// Entities inserted by [`nav_grid_update`] from their [`NavObstacle`].
+    nav_obstacles: HashSet<Entity>,
+}
+
+#[derive(Debug, Clone)]
+struct NavObstacleEntry {
+    shape: TransformedShape,
+    cells: Vec<usize>,
+}
+
+impl NavGrid {
+    /// A grid of `size` free cells, whose cell `(0, 0)` has its min corner at `origin`.
+    pub fn new(size: UVec2, cell_size: Vec2, origin: Vec2) -> Self {
+        Self {
+            size,
+            cell_size,
+            origin,
+            connectivity: default(),
+            corner_rule: default(),
+            blockers: vec![Vec::new(); (size.x * size.y) as usize],
+            obstacles: HashMap::new(),
+            off_grid: HashSet::new(),
+            nav_obstacles: HashSet::new(),
+        }
+    }
+
+    pub fn with_connectivity(self, connectivity: Connectivity) -> Self {
+        Self {
+            connectivity,
+            ..self
+        }
+    }
+
+    pub fn with_corner_rule(self, corner_rule: CornerRule) -> Self {
+        Self {
+            corner_rule,
+            ..self
+        }
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn cell_size(&self) -> Vec2 {
+        self.cell_size
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+
+    /// Bounds of the whole grid in world space.
+    pub fn bounds(&self) -> Aabb {
+        Aabb::from_min_max(
+            self.origin,
+            self.origin + self.size.as_vec2() * self.cell_size,
+        )
+    }
+
+    /// The cell containing `point`, if it is inside the grid.
+    pub fn cell_at(&self, point: Vec2) -> Option<UVec2> {
+        let cell = ((point - self.origin) / self.cell_size).floor();
+        (cell.cmpge(Vec2::ZERO).all() && cell.cmplt(self.size.as_vec2()).all())
+            .then(|| cell.as_uvec2())
+    }
+
+    pub fn cell_center(&self, cell: UVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    pub fn cell_aabb(&self, cell: UVec2) -> Aabb {
+        let min = self.origin + cell.as_vec2() * self.cell_size;
+        Aabb::from_min_max(min, min + self.cell_size)
+    }
+
+    /// Whether `cell` is blocked by an obstacle. Cells outside the grid are blocked.
+    pub fn is_blocked(&self, cell: UVec2) -> bool {
+        match self.index(cell) {
+            Some(index) => !self.blockers[index].is_empty(),
+            None => true,
+        }
+    }
+
+    /// Inserts or moves an obstacle, blocking the cells its shape overlaps.
+    pub fn insert(&mut self, entity: Entity, shape: TransformedShape) {
+        self.remove_entry(entity);
+        let cells = self.rasterize(&shape);
+        for index in cells.iter() {
+            self.blockers[*index].push(entity);
+        }
+        if cells.is_empty() {
+            self.off_grid.insert(entity);
+        }
+        self.obstacles
+            .insert(entity, NavObstacleEntry { shape, cells });
+    }
+
+    /// Removes an obstacle, freeing the cells no other obstacle blocks.
+    pub fn remove(&mut self, entity: Entity) -> Option<TransformedShape> {
+        self.nav_obstacles.remove(&entity);
+        self.remove_entry(entity)
+    }
+
+    pub fn clear(&mut self) {
+        self.obstacles.clear();
+        self.nav_obstacles.clear();
+        self.off_grid.clear();
+        for blockers in self.blockers.iter_mut() {
+            blockers.clear();
+        }
+    }
+
+    /// Inserts the shape of an entity's [`NavObstacle`], which is then removed with the component.
+    fn insert_nav_obstacle(&mut self, entity: Entity, shape: TransformedShape) {
+        self.insert(entity, shape);
+        self.nav_obstacles.insert(entity);
+    }
+
+    fn remove_entry(&mut self, entity: Entity) -> Option<TransformedShape> {
+        let entry = self.obstacles.remove(&entity)?;
+        for index in entry.cells.iter() {
+            let blockers = &mut self.blockers[*index];
+            if let Some(position) = blockers.iter().position(|blocker| *blocker == entity) {
+                blockers.swap_remove(position);
+            }
+        }
+        self.off_grid.remove(&entity);
+        Some(entry.shape)
+    }
+
+    pub fn obstacle_count(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    /// Whether a straight line from `start` to `end` stays inside the grid without touching an
+    /// obstacle's shape.
+    pub fn line_of_sight(&self, start: Vec2, end: Vec2) -> bool {
+        self.clear_path(start, end, 0.)
+    }
+
+    /// Whether a circle of `radius` can move in a straight line from `start` to `end` inside the
+    /// grid without touching an obstacle's shape.
+    pub fn clear_path(&self, start: Vec2, end: Vec2, radius: f32) -> bool {
+        let bounds = self.bounds();
+        if !bounds.contains_point(start) || !bounds.contains_point(end) {
+            return false;
+        }
+        let sweep = if radius > 0. {
+            // Like circles, capsules store twice their real radius.
+            TransformedShape::from(Capsule {
+                start,
+                end,
+                radius: radius * 2.,
+            })
+        } else {
+            TransformedShape::from(Segment { start, end })
+        };
+        let Some(sweep_aabb) = sweep.bounding_aabb() else {
+            return true;
+        };
+        !self.obstacles_near(sweep_aabb).into_iter().any(|entity| {
+            let obstacle = &self.obstacles[&entity].shape;
+            obstacle
+                .bounding_aabb()
+                .is_some_and(|aabb| aabb.colliding_with(&sweep_aabb))
+                && obstacle.colliding_with(&sweep)
+        })
+    }
+
+    /// Obstacles that may touch `aabb`: those blocking the cells it covers and their neighbours,
+    /// since a shape can touch a cell it doesn't block, and those blocking no cell.
+    fn obstacles_near(&self, aabb: Aabb) -> HashSet<Entity> {
+        let mut near = self.off_grid.clone();
+        let Some((min_cell, max_cell)) = self.cell_range(aabb) else {
+            return near;
+        };
+        let min_cell = (min_cell.as_ivec2() - IVec2::ONE)
+            .max(IVec2::ZERO)
+            .as_uvec2();
+        let max_cell = (max_cell + UVec2::ONE).min(self.size - UVec2::ONE);
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                near.extend(
+                    self.blockers[(y * self.size.x + x) as usize]
+                        .iter()
+                        .copied(),
+                );
+            }
+        }
+        near
+    }
+
+    /// Removes the waypoints of `path` that a circle of `radius` can skip by going straight to a
+    /// later one, keeping the first and last points.
+    pub fn smooth_path(&self, path: &[Vec2], radius: f32) -> Vec<Vec2> {
+        let Some(first) = path.first() else {
+            return vec![];
+        };
+        let mut smoothed = vec![*first];
+        let mut anchor = 0;
+        while anchor < path.len() - 1 {
+            let next = (anchor + 2..path.len())
+                .rev()
+                .find(|next| self.clear_path(path[anchor], path[*next], radius))
+                .unwrap_or(anchor + 1);
+            smoothed.push(path[next]);
+            anchor = next;
+        }
+        smoothed
+    }
+
+    pub(crate) fn cell_count(&self) -> usize {
+        self.blockers.len()
+    }
+
+    pub(crate) fn index(&self, cell: UVec2) -> Option<usize> {
+        (cell.x < self.size.x && cell.y < self.size.y)
+            .then(|| (cell.y * self.size.x + cell.x) as usize)
+    }
+
+    pub(crate) fn cell(&self, index: usize) -> UVec2 {
+        UVec2::new(index as u32 % self.size.x, index as u32 / self.size.x)
+    }
+
+    /// Free cells reachable in one move from `cell`, with the world distance of the move.
+    pub(crate) fn neighbours(&self, cell: UVec2) -> impl Iterator<Item = (UVec2, f32)> + '_ {
+        const ORTHOGONAL: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+        const DIAGONAL: [IVec2; 4] = [
+            IVec2::new(1, 1),
+            IVec2::new(-1, 1),
+            IVec2::new(-1, -1),
+            IVec2::new(1, -1),
+        ];
+        let diagonals = match self.connectivity {
+            Connectivity::Four => &DIAGONAL[..0],
+            Connectivity::Eight => &DIAGONAL[..],
+        };
+        let free = move |offset: IVec2| {
+            let neighbour = cell.as_ivec2() + offset;
+            (neighbour.cmpge(IVec2::ZERO).all() && !self.is_blocked(neighbour.as_uvec2()))
+                .then(|| neighbour.as_uvec2())
+        };
+        ORTHOGONAL
+            .iter()
+            .chain(diagonals.iter())
+            .filter_map(move |offset| {
+                let neighbour = free(*offset)?;
+                if offset.x != 0 && offset.y != 0 {
+                    let sides = [IVec2::new(offset.x, 0), IVec2::new(0, offset.y)]
+                        .into_iter()
+                        .filter(|side| free(*side).is_some())
+                        .count();
+                    let allowed = match self.corner_rule {
+                        CornerRule::NoCornerCutting => sides == 2,
+                        CornerRule::OneFree => sides >= 1,
+                        CornerRule::Always => true,
+                    };
+                    if !allowed {
+                        return None;
+                    }
+                }
+                Some((neighbour, (offset.as_vec2() * self.cell_size).length()))
+            })
+    }
+
+    /// First and last cells covered by `aabb`, or `None` if it misses the grid.
+    fn cell_range(&self, aabb: Aabb) -> Option<(UVec2, UVec2)> {
+        let aabb = aabb.intersection(&self.bounds())?;
+        let last_cell = self.size.as_ivec2() - IVec2::ONE;
+        let to_cell = |point: Vec2| {
+            ((point - self.origin) / self.cell_size)
+                .floor()
+                .as_ivec2()
+                .clamp(IVec2::ZERO, last_cell)
+                .as_uvec2()
+        };
+        Some((to_cell(aabb.min()), to_cell(aabb.max())))
+    }
+
+    fn rasterize(&self, shape: &TransformedShape) -> Vec<usize> {
+        let Some((min_cell, max_cell)) =
+            shape.bounding_aabb().and_then(|aabb| self.cell_range(aabb))
+        else {
+            return vec![];
+        };
+        // Cells are shrunk slightly, so shapes that only touch a cell's edge don't block it.
+        let margin = self.cell_size.min_element() * 0.001;
+        (min_cell.y..=max_cell.y)
+            .flat_map(|y| (min_cell.x..=max_cell.x).map(move |x| UVec2::new(x, y)))
+            .filter(|cell| shape.colliding_with(&self.cell_aabb(*cell).expand(-margin)))
+            .filter_map(|cell| self.index(cell))
+            .collect()
+    }
+}
+
+type ChangedObstacleQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static NavObstacle, &'static Transform2),
+    Or<(Changed<NavObstacle>, Changed<Transform2>)>,
+>;
+
+fn nav_grid_update(
+    obstacle_query: Query<(Entity, &NavObstacle, &Transform2)>,
+    changed_query: ChangedObstacleQuery,
+    mut removed_obstacles: RemovedComponents<NavObstacle>,
+    mut nav_grid: ResMut<NavGrid>,
+) {
+    for entity in removed_obstacles.iter() {
+        nav_grid.remove(entity);
+    }
+    for (entity, obstacle, transform) in changed_query.iter() {
+        nav_grid.insert_nav_obstacle(entity, obstacle.shape.transformed_by(*transform));
+    }
+    // Removals may be missed when fixed updates are skipped for a few frames, and a grid that
+    // replaced another one doesn't know about the obstacles that didn't change since.
+    if nav_grid.nav_obstacles.len() != obstacle_query.iter().len() {
+        let stale: Vec<Entity> = nav_grid
+            .nav_obstacles
+            .iter()
+            .filter(|entity| !obstacle_query.contains(**entity))
+            .copied()
+            .collect();
+        for entity in stale.into_iter() {
+            nav_grid.remove(entity);
+        }
+        for (entity, obstacle, transform) in obstacle_query.iter() {
+            if !nav_grid.nav_obstacles.contains(&entity) {
+                nav_grid.insert_nav_obstacle(entity, obstacle.shape.transformed_by(*transform));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{
+        geometry::{prelude::*, TransformedShape},
+        navigation::prelude::*,
+        transform2::Transform2,
+    };
+
+    use super::nav_grid_update;
+
+    #[test]
+    fn nav_grid_obstacles() {
+        let mut world = World::new();
+        world.insert_resource(NavGrid::new(
+            UVec2::new(10, 10),
+            Vec2::splat(10.),
+            Vec2::ZERO,
+        ));
+        let mut schedule = Schedule::new();
+        schedule.add_system(nav_grid_update);
+        let wall = world
+            .spawn((
+                NavObstacle::new(Shape::Aabb {
+                    size: Vec2::new(10., 30.),
+                }),
+                Transform2::from_xy(55., 35.),
+            ))
+            .id();
+        let pillar = world
+            .spawn((
+                NavObstacle::new(Shape::Circle { radius: 8. }),
+                Transform2::from_xy(55., 48.),
+            ))
+            .id();
+        schedule.run(&mut world);
+        let nav_grid = world.resource::<NavGrid>();
+        assert_eq!(nav_grid.obstacle_count(), 2);
+        for y in 2..=5 {
+            assert!(nav_grid.is_blocked(UVec2::new(5, y)), "{y}");
+        }
+        // The wall's edges touch its neighbours without blocking them.
+        assert!(!nav_grid.is_blocked(UVec2::new(4, 3)));
+        assert!(!nav_grid.is_blocked(UVec2::new(5, 1)));
+        assert!(!nav_grid.is_blocked(UVec2::new(5, 6)));
+        assert!(nav_grid.is_blocked(UVec2::new(10, 0)));
+
+        // The pillar still blocks the cell it shares with the wall.
+        world.get_mut::<Transform2>(wall).unwrap().translation = Vec2::new(15., 35.);
+        schedule.run(&mut world);
+        let nav_grid = world.resource::<NavGrid>();
+        assert!(nav_grid.is_blocked(UVec2::new(1, 3)));
+        assert!(!nav_grid.is_blocked(UVec2::new(5, 3)));
+        assert!(nav_grid.is_blocked(UVec2::new(5, 4)));
+        assert!(nav_grid.is_blocked(UVec2::new(5, 5)));
+
+        world.despawn(pillar);
+        schedule.run(&mut world);
+        let nav_grid = world.resource::<NavGrid>();
+        assert_eq!(nav_grid.obstacle_count(), 1);
+        assert!(!nav_grid.is_blocked(UVec2::new(5, 4)));
+        assert!(!nav_grid.is_blocked(UVec2::new(5, 5)));
+
+        // A new grid picks up the existing obstacles.
+        world.insert_resource(NavGrid::new(UVec2::new(4, 4), Vec2::splat(10.), Vec2::ZERO));
+        schedule.run(&mut world);
+        assert!(world.resource::<NavGrid>().is_blocked(UVec2::new(1, 3)));
+    }
+
+    #[test]
+    fn nav_grid_update_keeps_hand_inserted() {
+        let mut world = World::new();
+        world.insert_resource(NavGrid::new(
+            UVec2::new(10, 10),
+            Vec2::splat(10.),
+            Vec2::ZERO,
+        ));
+        let mut schedule = Schedule::new();
+        schedule.add_system(nav_grid_update);
+        let wall = world
+            .spawn((
+                NavObstacle::new(Shape::Aabb {
+                    size: Vec2::splat(10.),
+                }),
+                Transform2::from_xy(15., 15.),
+            ))
+            .id();
+        let by_hand = world.spawn_empty().id();
+        world.resource_mut::<NavGrid>().insert(
+            by_hand,
+            Shape::Aabb {
+                size: Vec2::splat(10.),
+            }
+            .at(Vec2::new(75., 75.)),
+        );
+        schedule.run(&mut world);
+        let nav_grid = world.resource::<NavGrid>();
+        assert_eq!(nav_grid.obstacle_count(), 2);
+        assert!(nav_grid.is_blocked(UVec2::new(7, 7)));
+
+        world.despawn(wall);
+        schedule.run(&mut world);
+        let nav_grid = world.resource::<NavGrid>();
+        assert_eq!(nav_grid.obstacle_count(), 1);
+        assert!(!nav_grid.is_blocked(UVec2::new(1, 1)));
+        assert!(nav_grid.is_blocked(UVec2::new(7, 7)));
+    }
+
+    #[test]
+    fn clear_path_matches_every_obstacle() {
+        let mut nav_grid = NavGrid::new(UVec2::new(20, 20), Vec2::splat(10.), Vec2::ZERO);
+        for index in 0..30 {
+            let i = index as f32;
+            nav_grid.insert(
+                Entity::from_raw(index),
+                Shape::Circle { radius: 6. }.at(Vec2::new((i * 37.) % 200., (i * 53.) % 200.)),
+            );
+        }
+        // Blocks no cell, since it only lies along cell edges.
+        nav_grid.insert(
+            Entity::from_raw(30),
+            Shape::Segment {
+                start: Vec2::new(100., 40.),
+                end: Vec2::new(100., 160.),
+            }
+            .at(Vec2::ZERO),
+        );
+        // Outside the grid, but in reach of wide sweeps along its edge.
+        nav_grid.insert(
+            Entity::from_raw(31),
+            Shape::Aabb {
+                size: Vec2::new(200., 10.),
+            }
+            .at(Vec2::new(100., -8.)),
+        );
+        assert_eq!(nav_grid.off_grid.len(), 2);
+
+        let brute_force = |start: Vec2, end: Vec2, radius: f32| {
+            let sweep = TransformedShape::from(Capsule {
+                start,
+                end,
+                radius: radius * 2.,
+            });
+            !nav_grid
+                .obstacles
+                .values()
+                .any(|obstacle| obstacle.shape.colliding_with(&sweep))
+        };
+        let mut blocked = 0;
+        for step in 0..300 {
+            let i = step as f32;
+            let start = Vec2::new((i * 13.) % 199. + 0.5, (i * 7.) % 199. + 0.5);
+            let end = Vec2::new((i * 29.) % 199. + 0.5, (i * 31.) % 199. + 0.5);
+            let radius = (step % 3) as f32 * 2. + 0.5;
+            let clear = nav_grid.clear_path(start, end, radius);
+            assert_eq!(clear, brute_force(start, end, radius), "{start} {end}");
+            blocked += usize::from(!clear);
+        }
+        assert!(blocked > 0 && blocked < 300);
+    }
+
+    #[test]
+    fn nav_grid_line_of_sight() {
+        let mut nav_grid = NavGrid::new(UVec2::new(10, 10), Vec2::splat(10.), Vec2::ZERO);
+        nav_grid.insert(
+            Entity::from_raw(0),
+            Shape::Aabb {
+                size: Vec2::new(10., 40.),
+            }
+            .at(Vec2::new(50., 50.)),
+        );
+        assert!(nav_grid.line_of_sight(Vec2::new(5., 5.), Vec2::new(95., 5.)));
+        assert!(!nav_grid.line_of_sight(Vec2::new(5., 50.), Vec2::new(95., 50.)));
+        assert!(nav_grid.line_of_sight(Vec2::new(5., 75.), Vec2::new(95., 75.)));
+        assert!(!nav_grid.clear_path(Vec2::new(5., 75.), Vec2::new(95., 75.), 6.));
+        assert!(!nav_grid.line_of_sight(Vec2::new(5., 5.), Vec2::new(150., 5.)));
+
+        let path = [
+            Vec2::new(5., 5.),
+            Vec2::new(15., 15.),
+            Vec2::new(25., 25.),
+            Vec2::new(45., 25.),
+            Vec2::new(65., 25.),
+            Vec2::new(85., 75.),
+        ];
+        assert_eq!(
+            nav_grid.smooth_path(&path, 0.),
+            vec![Vec2::new(5., 5.), Vec2::new(65., 25.), Vec2::new(85., 75.)]
+        );
+        assert_eq!(nav_grid.smooth_path(&path[..1], 0.), vec![path[0]]);
+        assert!(nav_grid.smooth_path(&[], 0.).is_empty());
+    }
+}
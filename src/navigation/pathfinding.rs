@@ -0,0 +1,337 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::prelude::*;
+
+use super::{Connectivity, NavGrid};
+
+/// A cell waiting to be visited, ordered so the [`BinaryHeap`] pops the lowest cost first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Frontier {
+    cost: f32,
+    index: usize,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl NavGrid {
+    /// Shortest path of cells from `start` to `goal` with A*, both included, or `None` if the goal
+    /// is blocked or unreachable. The start cell may be blocked, so agents pushed into an obstacle
+    /// can still find their way out.
+    pub fn find_path_cells(&self, start: UVec2, goal: UVec2) -> Option<Vec<UVec2>> {
+        let start_index = self.index(start)?;
+        let goal_index = self.index(goal)?;
+        if self.is_blocked(goal) {
+            return None;
+        }
+        let mut costs = vec![f32::INFINITY; self.cell_count()];
+        let mut came_from = vec![usize::MAX; self.cell_count()];
+        let mut frontier = BinaryHeap::new();
+        costs[start_index] = 0.;
+        frontier.push(Frontier {
+            cost: self.heuristic(start, goal),
+            index: start_index,
+        });
+        while let Some(Frontier { cost, index }) = frontier.pop() {
+            if index == goal_index {
+                let mut path = vec![goal];
+                let mut index = goal_index;
+                while index != start_index {
+                    index = came_from[index];
+                    path.push(self.cell(index));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let cell = self.cell(index);
+            if cost > costs[index] + self.heuristic(cell, goal) {
+                continue;
+            }
+            for (neighbour, distance) in self.neighbours(cell) {
+                let neighbour_index = self.index(neighbour).unwrap();
+                let neighbour_cost = costs[index] + distance;
+                if neighbour_cost < costs[neighbour_index] {
+                    costs[neighbour_index] = neighbour_cost;
+                    came_from[neighbour_index] = index;
+                    frontier.push(Frontier {
+                        cost: neighbour_cost + self.heuristic(neighbour, goal),
+                        index: neighbour_index,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Shortest path from `start` to `goal` in world space, going through the centers of the cells
+    /// in between, or `None` if either point is outside the grid or the goal can't be reached.
+    /// See [`NavGrid::smooth_path`] to remove the waypoints that aren't needed.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let cells = self.find_path_cells(self.cell_at(start)?, self.cell_at(goal)?)?;
+        let mut path = vec![start];
+        if cells.len() > 2 {
+            path.extend(
+                cells[1..cells.len() - 1]
+                    .iter()
+                    .map(|cell| self.cell_center(*cell)),
+            );
+        }
+        path.push(goal);
+        Some(path)
+    }
+
+    /// Distances from every cell to the closest of `goals`, for steering many agents to the same
+    /// places without a search each. Goals outside the grid or in blocked cells are ignored.
+    pub fn flow_field(&self, goals: impl IntoIterator<Item = Vec2>) -> FlowField {
+        let mut costs = vec![f32::INFINITY; self.cell_count()];
+        let mut next = vec![None; self.cell_count()];
+        let mut frontier = BinaryHeap::new();
+        for goal in goals {
+            let Some(cell) = self.cell_at(goal).filter(|cell| !self.is_blocked(*cell)) else {
+                continue;
+            };
+            let index = self.index(cell).unwrap();
+            costs[index] = 0.;
+            frontier.push(Frontier { cost: 0., index });
+        }
+        // Moves are symmetric, so searching outwards from the goals finds the way back to them.
+        while let Some(Frontier { cost, index }) = frontier.pop() {
+            if cost > costs[index] {
+                continue;
+            }
+            for (neighbour, distance) in self.neighbours(self.cell(index)) {
+                let neighbour_index = self.index(neighbour).unwrap();
+                if cost + distance < costs[neighbour_index] {
+                    costs[neighbour_index] = cost + distance;
+                    next[neighbour_index] = Some(index);
+                    frontier.push(Frontier {
+                        cost: cost + distance,
+                        index: neighbour_index,
+                    });
+                }
+            }
+        }
+        FlowField {
+            grid: self.clone_layout(),
+            costs,
+            next,
+        }
+    }
+
+    /// Lower bound of the distance between two cells.
+    fn heuristic(&self, from: UVec2, to: UVec2) -> f32 {
+        let cells = (to.as_ivec2() - from.as_ivec2()).abs().as_vec2();
+        let cell_size = self.cell_size();
+        match self.connectivity {
+            Connectivity::Four => (cells * cell_size).dot(Vec2::ONE),
+            Connectivity::Eight => {
+                let diagonal = cells.min_element();
+                diagonal * cell_size.length() + ((cells - diagonal) * cell_size).dot(Vec2::ONE)
+            }
+        }
+    }
+
+    /// The grid without its obstacles, for mapping points to cells.
+    fn clone_layout(&self) -> NavGrid {
+        NavGrid::new(self.size(), self.cell_size(), self.origin())
+    }
+}
+
+/// Distances to a set of goals from every cell of a [`NavGrid`], built by [`NavGrid::flow_field`].
+///
+/// The field is a snapshot: it isn't updated when obstacles move, so it should be rebuilt when the
+/// grid or the goals change.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    grid: NavGrid,
+    costs: Vec<f32>,
+    next: Vec<Option<usize>>,
+}
+
+impl FlowField {
+    /// Distance from the cell containing `point` to the closest goal, or `None` if no goal can be
+    /// reached from it.
+    pub fn distance(&self, point: Vec2) -> Option<f32> {
+        let index = self.grid.index(self.grid.cell_at(point)?)?;
+        Some(self.costs[index]).filter(|cost| cost.is_finite())
+    }
+
+    /// The cell to move to from the cell containing `point`, or `None` at a goal or when no goal
+    /// can be reached.
+    pub fn next_cell(&self, point: Vec2) -> Option<UVec2> {
+        let index = self.grid.index(self.grid.cell_at(point)?)?;
+        self.next[index].map(|next| self.grid.cell(next))
+    }
+
+    /// Unit direction from `point` towards the center of its next cell, or zero at a goal or when
+    /// no goal can be reached.
+    pub fn direction(&self, point: Vec2) -> Vec2 {
+        self.next_cell(point).map_or(Vec2::ZERO, |cell| {
+            (self.grid.cell_center(cell) - point).normalize_or_zero()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::SQRT_2;
+
+    use bevy::prelude::*;
+
+    use crate::{geometry::prelude::*, navigation::prelude::*};
+
+    const EPSILON: f32 = 0.0001;
+
+    /// A 10x10 grid of unit cells with a wall at x = 5 from y = 0 to 7.
+    fn walled_grid() -> NavGrid {
+        let mut nav_grid = NavGrid::new(UVec2::splat(10), Vec2::ONE, Vec2::ZERO);
+        nav_grid.insert(
+            Entity::from_raw(0),
+            Shape::Aabb {
+                size: Vec2::new(1., 8.),
+            }
+            .at(Vec2::new(5.5, 4.)),
+        );
+        nav_grid
+    }
+
+    fn length(path: &[Vec2]) -> f32 {
+        path.windows(2).map(|pair| pair[0].distance(pair[1])).sum()
+    }
+
+    #[test]
+    fn find_path_around_wall() {
+        let nav_grid = walled_grid();
+        let path = nav_grid
+            .find_path_cells(UVec2::new(2, 2), UVec2::new(8, 2))
+            .unwrap();
+        assert_eq!(path.first(), Some(&UVec2::new(2, 2)));
+        assert_eq!(path.last(), Some(&UVec2::new(8, 2)));
+        assert!(path.iter().all(|cell| !nav_grid.is_blocked(*cell)));
+        assert!(path.contains(&UVec2::new(5, 8)));
+        // Corners can't be cut, so the path goes through the three cells of the gap.
+        assert!(path.contains(&UVec2::new(4, 8)) && path.contains(&UVec2::new(6, 8)));
+        let cost: f32 = path
+            .windows(2)
+            .map(|pair| pair[0].as_vec2().distance(pair[1].as_vec2()))
+            .sum();
+        assert!((cost - (10. + 4. * SQRT_2)).abs() < EPSILON);
+
+        let four = walled_grid().with_connectivity(Connectivity::Four);
+        let path = four
+            .find_path_cells(UVec2::new(2, 2), UVec2::new(8, 2))
+            .unwrap();
+        assert_eq!(path.len(), 6 + 6 + 6 + 1);
+        assert!(path
+            .windows(2)
+            .all(|pair| (pair[0].as_ivec2() - pair[1].as_ivec2())
+                .abs()
+                .dot(IVec2::ONE)
+                == 1));
+
+        assert!(nav_grid
+            .find_path_cells(UVec2::new(2, 2), UVec2::new(5, 2))
+            .is_none());
+        assert!(nav_grid
+            .find_path_cells(UVec2::new(2, 2), UVec2::new(10, 2))
+            .is_none());
+        assert_eq!(
+            nav_grid.find_path_cells(UVec2::new(5, 2), UVec2::new(4, 2)),
+            Some(vec![UVec2::new(5, 2), UVec2::new(4, 2)])
+        );
+    }
+
+    #[test]
+    fn find_path_corner_rules() {
+        // Two blocks touching at a corner, with the path having to cross between them.
+        let mut nav_grid = NavGrid::new(UVec2::new(2, 2), Vec2::ONE, Vec2::ZERO);
+        nav_grid.insert(Entity::from_raw(0), Shape::Point.at(Vec2::new(1.5, 0.5)));
+        nav_grid.insert(Entity::from_raw(1), Shape::Point.at(Vec2::new(0.5, 1.5)));
+        let (start, goal) = (UVec2::new(0, 0), UVec2::new(1, 1));
+        assert!(nav_grid.find_path_cells(start, goal).is_none());
+        let nav_grid = nav_grid.with_corner_rule(CornerRule::OneFree);
+        assert!(nav_grid.find_path_cells(start, goal).is_none());
+        let nav_grid = nav_grid.with_corner_rule(CornerRule::Always);
+        assert_eq!(
+            nav_grid.find_path_cells(start, goal),
+            Some(vec![start, goal])
+        );
+
+        // Cutting around a single block.
+        let mut nav_grid = NavGrid::new(UVec2::new(2, 2), Vec2::ONE, Vec2::ZERO);
+        nav_grid.insert(Entity::from_raw(0), Shape::Point.at(Vec2::new(1.5, 0.5)));
+        assert_eq!(
+            nav_grid.find_path_cells(start, goal).map(|path| path.len()),
+            Some(3)
+        );
+        let nav_grid = nav_grid.with_corner_rule(CornerRule::OneFree);
+        assert_eq!(
+            nav_grid.find_path_cells(start, goal).map(|path| path.len()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn find_and_smooth_path() {
+        let nav_grid = walled_grid();
+        let (start, goal) = (Vec2::new(2.2, 2.3), Vec2::new(8.4, 1.9));
+        let path = nav_grid.find_path(start, goal).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        let smoothed = nav_grid.smooth_path(&path, 0.25);
+        assert!(smoothed.len() < path.len());
+        assert!(length(&smoothed) < length(&path));
+        assert!(smoothed
+            .windows(2)
+            .all(|pair| nav_grid.clear_path(pair[0], pair[1], 0.25)));
+        assert!(nav_grid.find_path(start, Vec2::new(-1., 0.)).is_none());
+    }
+
+    #[test]
+    fn flow_field() {
+        let nav_grid = walled_grid();
+        let field = nav_grid.flow_field([Vec2::new(8.5, 2.5), Vec2::new(-3., 0.)]);
+        assert_eq!(field.distance(Vec2::new(8.5, 2.5)), Some(0.));
+        assert_eq!(field.direction(Vec2::new(8.5, 2.5)), Vec2::ZERO);
+        assert!((field.distance(Vec2::new(8.5, 5.5)).unwrap() - 3.).abs() < EPSILON);
+        assert!(field
+            .direction(Vec2::new(8.5, 5.5))
+            .abs_diff_eq(Vec2::NEG_Y, EPSILON));
+        assert_eq!(field.distance(Vec2::new(5.5, 2.5)), None);
+
+        // Agents west of the wall are sent around it through the gap.
+        let mut cell = UVec2::new(2, 2);
+        let mut steps = 0;
+        while let Some(next) = field.next_cell(nav_grid.cell_center(cell)) {
+            assert!(!nav_grid.is_blocked(next));
+            cell = next;
+            steps += 1;
+            assert!(steps < 100);
+        }
+        assert_eq!(cell, UVec2::new(8, 2));
+        let from_west = field
+            .distance(nav_grid.cell_center(UVec2::new(2, 2)))
+            .unwrap();
+        let path = nav_grid
+            .find_path(
+                nav_grid.cell_center(UVec2::new(2, 2)),
+                nav_grid.cell_center(UVec2::new(8, 2)),
+            )
+            .unwrap();
+        assert!((from_west - length(&path)).abs() < EPSILON);
+    }
+}
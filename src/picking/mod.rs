@@ -0,0 +1,9 @@
+mod picking;
+pub use picking::*;
+
+pub mod prelude {
+    pub use super::{
+        Hovered, Pickable, Picking, PickingSystem, PointerButton, PointerClick, PointerDragStart,
+        PointerOut, PointerOver, PointerPress,
+    };
+}
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    cursor::Cursor,
+    fixed_timestep::{AddFixedEvent, AddFixedInput, CoreFixedSet, FixedInput, FixedInputSystem},
+    geometry::{ContainsPoint, Shape},
+    transform2::Transform2,
+};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum PickingSystem {
+    PointerInput,
+    Pick,
+}
+
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Picking>()
+            .init_resource::<Input<PointerButton>>()
+            .add_fixed_input::<PointerButton>()
+            .add_fixed_event::<PointerOver>()
+            .add_fixed_event::<PointerOut>()
+            .add_fixed_event::<PointerPress>()
+            .add_fixed_event::<PointerClick>()
+            .add_fixed_event::<PointerDragStart>()
+            .add_system(
+                pointer_input
+                    .in_set(PickingSystem::PointerInput)
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem)
+                    .before(FixedInputSystem),
+            )
+            .add_system(
+                picking_update
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(PickingSystem::Pick)
+                    .in_base_set(CoreFixedSet::PreUpdate),
+            );
+    }
+}
+
+/// Buttons of the pointer. Mouse buttons map to their counterparts, and touching the screen
+/// presses [`PointerButton::Primary`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum PointerButton {
+    Primary,
+    Secondary,
+    Middle,
+}
+
+impl PointerButton {
+    const ALL: [PointerButton; 3] = [
+        PointerButton::Primary,
+        PointerButton::Secondary,
+        PointerButton::Middle,
+    ];
+}
+
+/// A shape that the pointer can hover and click, placed at the entity's [`GlobalTransform`].
+///
+/// When pickables overlap, the one with the highest z, as set by
+/// [`Depth`](crate::transform2::Depth), is picked. The pointer only picks entities whose `layers`
+/// share a bit with [`Picking::mask`].
+#[derive(Component, Debug, Clone)]
+pub struct Pickable {
+    pub shape: Shape,
+    pub layers: u32,
+}
+
+impl Pickable {
+    pub fn new(shape: Shape) -> Self {
+        Self { shape, layers: 1 }
+    }
+
+    pub fn with_layers(self, layers: u32) -> Self {
+        Self { layers, ..self }
+    }
+}
+
+/// Marks the entity under the pointer.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Hovered;
+
+/// Sent when the pointer starts hovering an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerOver {
+    pub entity: Entity,
+}
+
+/// Sent when the pointer stops hovering an entity, including when it is despawned or loses its
+/// [`Pickable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerOut {
+    pub entity: Entity,
+}
+
+/// Sent when a button is pressed over an entity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerPress {
+    pub entity: Entity,
+    pub button: PointerButton,
+    pub position: Vec2,
+}
+
+/// Sent when a button is pressed and released over the same entity without dragging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerClick {
+    pub entity: Entity,
+    pub button: PointerButton,
+    pub position: Vec2,
+}
+
+/// Sent when the pointer moves further than [`Picking::drag_threshold`] while a button pressed
+/// over an entity is held. `position` is where the button was pressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerDragStart {
+    pub entity: Entity,
+    pub button: PointerButton,
+    pub position: Vec2,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    entity: Entity,
+    position: Vec2,
+    dragging: bool,
+}
+
+#[derive(Resource, Debug)]
+pub struct Picking {
+    /// Layers of [`Pickable`]s that the pointer can pick.
+    pub mask: u32,
+    /// How far, in world units, the pointer moves before a press becomes a drag.
+    pub drag_threshold: f32,
+    hovered: Option<Entity>,
+    presses: HashMap<PointerButton, Press>,
+}
+
+impl Default for Picking {
+    fn default() -> Self {
+        Self {
+            mask: u32::MAX,
+            drag_threshold: 4.,
+            hovered: None,
+            presses: HashMap::new(),
+        }
+    }
+}
+
+impl Picking {
+    /// The entity under the pointer as of the last fixed update.
+    pub fn hovered(&self) -> Option<Entity> {
+        self.hovered
+    }
+
+    /// The entity `button` was pressed over, while it is held.
+    pub fn pressed(&self, button: PointerButton) -> Option<Entity> {
+        self.presses.get(&button).map(|press| press.entity)
+    }
+}
+
+fn pointer_input(
+    mut pointer_input: ResMut<Input<PointerButton>>,
+    mouse_input: Option<Res<Input<MouseButton>>>,
+    touches: Option<Res<Touches>>,
+) {
+    pointer_input.clear();
+    let touched = touches.is_some_and(|touches| touches.iter().next().is_some());
+    for button in PointerButton::ALL {
+        let mouse_button = match button {
+            PointerButton::Primary => MouseButton::Left,
+            PointerButton::Secondary => MouseButton::Right,
+            PointerButton::Middle => MouseButton::Middle,
+        };
+        let pressed = mouse_input
+            .as_ref()
+            .is_some_and(|mouse_input| mouse_input.pressed(mouse_button))
+            || button == PointerButton::Primary && touched;
+        if pressed && !pointer_input.pressed(button) {
+            pointer_input.press(button);
+        } else if !pressed && pointer_input.pressed(button) {
+            pointer_input.release(button);
+        }
+    }
+}
+
+fn global_transform2(global_transform: &GlobalTransform) -> Transform2 {
+    let (scale, rotation, translation) = global_transform.to_scale_rotation_translation();
+    Transform2 {
+        translation: translation.truncate(),
+        rotation: rotation.to_euler(EulerRot::ZYX).0,
+        scale: scale.truncate(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn picking_update(
+    mut commands: Commands,
+    mut picking: ResMut<Picking>,
+    pickable_query: Query<(Entity, &Pickable, &GlobalTransform)>,
+    cursor: Res<Cursor>,
+    pointer_input: Res<FixedInput<PointerButton>>,
+    mut pointer_over_events: EventWriter<PointerOver>,
+    mut pointer_out_events: EventWriter<PointerOut>,
+    mut pointer_press_events: EventWriter<PointerPress>,
+    mut pointer_click_events: EventWriter<PointerClick>,
+    mut pointer_drag_start_events: EventWriter<PointerDragStart>,
+) {
+    let position = cursor.position;
+    let hovered = pickable_query
+        .iter()
+        .filter(|(_, pickable, _)| pickable.layers & picking.mask != 0)
+        .filter(|(_, pickable, global_transform)| {
+            pickable
+                .shape
+                .transformed_by(global_transform2(global_transform))
+                .contains_point(position)
+        })
+        .max_by(|(a, _, a_transform), (b, _, b_transform)| {
+            let (a_depth, b_depth) = (a_transform.translation().z, b_transform.translation().z);
+            // Ties go to the entity spawned last, which is usually drawn on top.
+            a_depth.total_cmp(&b_depth).then(a.cmp(b))
+        })
+        .map(|(entity, ..)| entity);
+
+    if hovered != picking.hovered {
+        if let Some(entity) = picking.hovered {
+            pointer_out_events.send(PointerOut { entity });
+            if let Some(mut entity_commands) = commands.get_entity(entity) {
+                entity_commands.remove::<Hovered>();
+            }
+        }
+        if let Some(entity) = hovered {
+            pointer_over_events.send(PointerOver { entity });
+            commands.entity(entity).insert(Hovered);
+        }
+        picking.hovered = hovered;
+    }
+
+    for button in PointerButton::ALL {
+        if pointer_input.just_pressed(button) {
+            if let Some(entity) = hovered {
+                pointer_press_events.send(PointerPress {
+                    entity,
+                    button,
+                    position,
+                });
+                picking.presses.insert(
+                    button,
+                    Press {
+                        entity,
+                        position,
+                        dragging: false,
+                    },
+                );
+            }
+        }
+        let drag_threshold = picking.drag_threshold;
+        if let Some(press) = picking.presses.get_mut(&button) {
+            if !press.dragging && press.position.distance(position) > drag_threshold {
+                press.dragging = true;
+                pointer_drag_start_events.send(PointerDragStart {
+                    entity: press.entity,
+                    button,
+                    position: press.position,
+                });
+            }
+        }
+        if !pointer_input.pressed(button) {
+            if let Some(press) = picking.presses.remove(&button) {
+                if !press.dragging && hovered == Some(press.entity) {
+                    pointer_click_events.send(PointerClick {
+                        entity: press.entity,
+                        button,
+                        position,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::{
+        cursor::Cursor, fixed_timestep::FixedInput, geometry::Shape, picking::prelude::*,
+        transform2::Depth,
+    };
+
+    use super::picking_update;
+
+    struct TestApp {
+        world: World,
+        schedule: Schedule,
+    }
+
+    impl TestApp {
+        fn new() -> Self {
+            let mut world = World::new();
+            world.init_resource::<Cursor>();
+            world.init_resource::<Picking>();
+            world.init_resource::<FixedInput<PointerButton>>();
+            world.init_resource::<Events<PointerOver>>();
+            world.init_resource::<Events<PointerOut>>();
+            world.init_resource::<Events<PointerPress>>();
+            world.init_resource::<Events<PointerClick>>();
+            world.init_resource::<Events<PointerDragStart>>();
+            let mut schedule = Schedule::new();
+            schedule.add_systems((picking_update, apply_system_buffers).chain());
+            Self { world, schedule }
+        }
+
+        fn spawn(&mut self, position: Vec2, depth: f32) -> Entity {
+            self.world
+                .spawn((
+                    Pickable::new(Shape::Aabb {
+                        size: Vec2::splat(10.),
+                    }),
+                    GlobalTransform::from_translation(position.extend(depth)),
+                ))
+                .id()
+        }
+
+        fn update(&mut self, position: Vec2) {
+            self.world.resource_mut::<Cursor>().position = position;
+            self.schedule.run(&mut self.world);
+            self.world
+                .resource_mut::<FixedInput<PointerButton>>()
+                .clear();
+        }
+
+        fn press(&mut self, button: PointerButton) {
+            self.world
+                .resource_mut::<FixedInput<PointerButton>>()
+                .press(button);
+        }
+
+        fn release(&mut self, button: PointerButton) {
+            self.world
+                .resource_mut::<FixedInput<PointerButton>>()
+                .release(button);
+        }
+
+        fn events<T: Event + Clone>(&mut self) -> Vec<T> {
+            self.world.resource_mut::<Events<T>>().drain().collect()
+        }
+    }
+
+    #[test]
+    fn picking_hover() {
+        let mut app = TestApp::new();
+        let below = app.spawn(Vec2::ZERO, Depth::Exact(0.1).depth_f32());
+        let above = app.spawn(Vec2::new(5., 0.), Depth::Exact(0.5).depth_f32());
+
+        app.update(Vec2::new(-3., 0.));
+        assert_eq!(
+            app.events::<PointerOver>(),
+            vec![PointerOver { entity: below }]
+        );
+        assert!(app.world.get::<Hovered>(below).is_some());
+
+        app.update(Vec2::new(3., 0.));
+        assert_eq!(
+            app.events::<PointerOut>(),
+            vec![PointerOut { entity: below }]
+        );
+        assert_eq!(
+            app.events::<PointerOver>(),
+            vec![PointerOver { entity: above }]
+        );
+        assert!(app.world.get::<Hovered>(below).is_none());
+        assert!(app.world.get::<Hovered>(above).is_some());
+        assert_eq!(app.world.resource::<Picking>().hovered(), Some(above));
+
+        app.update(Vec2::new(3., 0.));
+        assert!(app.events::<PointerOver>().is_empty());
+
+        app.world.despawn(above);
+        app.update(Vec2::new(3., 0.));
+        assert_eq!(
+            app.events::<PointerOut>(),
+            vec![PointerOut { entity: above }]
+        );
+        assert_eq!(
+            app.events::<PointerOver>(),
+            vec![PointerOver { entity: below }]
+        );
+    }
+
+    #[test]
+    fn picking_layers() {
+        let mut app = TestApp::new();
+        let entity = app.spawn(Vec2::ZERO, 0.);
+        app.world
+            .entity_mut(entity)
+            .insert(Pickable::new(Shape::Circle { radius: 10. }).with_layers(0b10));
+        app.world.resource_mut::<Picking>().mask = 0b01;
+        app.update(Vec2::ZERO);
+        assert!(app.events::<PointerOver>().is_empty());
+        app.world.resource_mut::<Picking>().mask = 0b11;
+        app.update(Vec2::ZERO);
+        assert_eq!(app.events::<PointerOver>(), vec![PointerOver { entity }]);
+    }
+
+    #[test]
+    fn picking_follows_hierarchy() {
+        let mut app = TestApp::new();
+        let entity = app.spawn(Vec2::ZERO, 0.);
+        app.world.entity_mut(entity).insert(GlobalTransform::from(
+            Transform::from_xyz(100., 0., 0.).with_scale(Vec3::new(2., 2., 1.)),
+        ));
+        app.update(Vec2::new(109., 0.));
+        assert_eq!(app.events::<PointerOver>(), vec![PointerOver { entity }]);
+    }
+
+    #[test]
+    fn picking_click_and_drag() {
+        let mut app = TestApp::new();
+        let entity = app.spawn(Vec2::ZERO, 0.);
+        app.update(Vec2::ZERO);
+
+        app.press(PointerButton::Primary);
+        app.update(Vec2::ZERO);
+        assert_eq!(
+            app.events::<PointerPress>(),
+            vec![PointerPress {
+                entity,
+                button: PointerButton::Primary,
+                position: Vec2::ZERO,
+            }]
+        );
+        assert_eq!(
+            app.world
+                .resource::<Picking>()
+                .pressed(PointerButton::Primary),
+            Some(entity)
+        );
+        app.update(Vec2::new(1., 0.));
+        app.release(PointerButton::Primary);
+        app.update(Vec2::new(1., 0.));
+        assert_eq!(
+            app.events::<PointerClick>(),
+            vec![PointerClick {
+                entity,
+                button: PointerButton::Primary,
+                position: Vec2::new(1., 0.),
+            }]
+        );
+
+        app.press(PointerButton::Secondary);
+        app.update(Vec2::ZERO);
+        app.update(Vec2::new(4.5, 0.));
+        assert_eq!(
+            app.events::<PointerDragStart>(),
+            vec![PointerDragStart {
+                entity,
+                button: PointerButton::Secondary,
+                position: Vec2::ZERO,
+            }]
+        );
+        app.update(Vec2::ZERO);
+        app.release(PointerButton::Secondary);
+        app.update(Vec2::ZERO);
+        assert!(app.events::<PointerDragStart>().is_empty());
+        assert!(app.events::<PointerClick>().is_empty());
+
+        // Releasing somewhere else doesn't click.
+        app.press(PointerButton::Primary);
+        app.update(Vec2::ZERO);
+        app.world.resource_mut::<Picking>().drag_threshold = 100.;
+        app.release(PointerButton::Primary);
+        app.update(Vec2::new(50., 0.));
+        assert!(app.events::<PointerClick>().is_empty());
+        assert_eq!(
+            app.world
+                .resource::<Picking>()
+                .pressed(PointerButton::Primary),
+            None
+        );
+    }
+}
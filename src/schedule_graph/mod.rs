@@ -0,0 +1,6 @@
+mod schedule_graph;
+pub use schedule_graph::*;
+
+pub mod prelude {
+    pub use super::{Ambiguity, ScheduleDump, ScheduleGraphExport};
+}
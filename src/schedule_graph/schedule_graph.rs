@@ -0,0 +1,461 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    ecs::{
+        component::Components,
+        schedule::{BaseSetMembership, NodeId, ScheduleGraph, ScheduleLabel},
+    },
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
+
+use crate::flow::{
+    system_order::{build_graph, SystemOrder},
+    FlowSet,
+};
+
+/// Writes the schedules described by [`ScheduleGraphExport`] when the app starts running.
+pub struct ScheduleGraphPlugin;
+
+impl Plugin for ScheduleGraphPlugin {
+    fn build(&self, _app: &mut App) {}
+
+    fn setup(&self, app: &mut App) {
+        if let Some(export) = app.world.get_resource::<ScheduleGraphExport>().cloned() {
+            export.write(&mut app.world);
+        }
+    }
+}
+
+/// Insert this resource to write the resolved `FixedUpdate` and `Main` schedules to `directory`
+/// on startup, as Graphviz files (`fixed_update.dot`, `main.dot`) and text summaries
+/// (`fixed_update.txt`, `main.txt`). Ambiguities found in the schedules are also logged.
+#[derive(Resource, Debug, Clone)]
+pub struct ScheduleGraphExport {
+    pub directory: PathBuf,
+}
+
+impl ScheduleGraphExport {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn write(&self, world: &mut World) {
+        if let Err(error) = fs::create_dir_all(&self.directory) {
+            error!("Failed to create {:?}: {error}", self.directory);
+            return;
+        }
+        let schedules: [(Box<dyn ScheduleLabel>, &str); 2] = [
+            (Box::new(CoreSchedule::FixedUpdate), "fixed_update"),
+            (Box::new(CoreSchedule::Main), "main"),
+        ];
+        for (label, file_name) in schedules {
+            let Some(dump) = ScheduleDump::new(world, &*label) else {
+                continue;
+            };
+            for ambiguity in dump.ambiguities() {
+                warn!(
+                    "{} and {} in {:?} of {} both access {} without an order",
+                    ambiguity.systems[0],
+                    ambiguity.systems[1],
+                    ambiguity.flow_set,
+                    dump.name,
+                    ambiguity.components.join(", ")
+                );
+            }
+            write_file(
+                &self.directory.join(file_name).with_extension("dot"),
+                &dump.to_dot(),
+            );
+            write_file(
+                &self.directory.join(file_name).with_extension("txt"),
+                &dump.summary(),
+            );
+        }
+    }
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Err(error) = fs::write(path, contents) {
+        error!("Failed to write {path:?}: {error}");
+    }
+}
+
+/// Two systems in the same [`FlowSet`] with conflicting access to `components` and no order
+/// between them, so they can run in either order from one tick to the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguity {
+    pub systems: [String; 2],
+    pub flow_set: FlowSet,
+    pub components: Vec<String>,
+    ids: [NodeId; 2],
+}
+
+struct DumpedSystem {
+    id: NodeId,
+    name: String,
+    sets: Vec<String>,
+    after: Vec<String>,
+}
+
+/// Snapshot of a schedule after its base sets are resolved: its sets, its systems, and the
+/// ordering between them.
+pub struct ScheduleDump {
+    name: String,
+    sets: Vec<(NodeId, String)>,
+    systems: Vec<DumpedSystem>,
+    hierarchy: Vec<(NodeId, NodeId)>,
+    dependencies: Vec<(NodeId, NodeId)>,
+    groups: Vec<(String, Vec<usize>)>,
+    ambiguities: Vec<Ambiguity>,
+}
+
+impl ScheduleDump {
+    /// Builds the schedule with `label` and describes it, or returns `None` if it doesn't exist,
+    /// fails to build, or already ran, since running moves the systems out of its graph.
+    pub fn new(world: &mut World, label: &dyn ScheduleLabel) -> Option<Self> {
+        world.resource_scope(|world, mut schedules: Mut<Schedules>| {
+            let schedule = schedules.get_mut(label)?;
+            if !build_graph(schedule, world) {
+                return None;
+            }
+            let dump = Self::from_graph(format!("{label:?}"), schedule.graph(), world.components());
+            (!dump.systems.is_empty()).then_some(dump)
+        })
+    }
+
+    fn from_graph(name: String, graph: &ScheduleGraph, components: &Components) -> Self {
+        let hierarchy_graph = graph.hierarchy().graph();
+        // Each system has a set of its own type, used by orderings on the system function.
+        let is_system_type = |id: NodeId| {
+            graph
+                .get_set_at(id)
+                .is_some_and(|set| set.system_type().is_some())
+        };
+        let set_name = |id: NodeId| format!("{:?}", graph.set_at(id));
+
+        let mut sets = graph
+            .system_sets()
+            .filter(|(_, set, ..)| set.system_type().is_none())
+            .map(|(id, set, ..)| (id, format!("{set:?}")))
+            .collect::<Vec<_>>();
+        sets.sort_by_key(|(id, _)| *id);
+        let hierarchy = hierarchy_graph
+            .all_edges()
+            .filter(|(parent, ..)| !is_system_type(*parent))
+            .map(|(parent, child, _)| (parent, child))
+            .collect::<Vec<_>>();
+        let members = |id: NodeId| {
+            if is_system_type(id) {
+                hierarchy_graph.neighbors(id).collect()
+            } else {
+                vec![id]
+            }
+        };
+        let mut dependencies = Vec::new();
+        for (before, after, _) in graph.dependency().graph().all_edges() {
+            for before in members(before) {
+                for after in members(after) {
+                    dependencies.push((before, after));
+                }
+            }
+        }
+
+        let base_sets = graph
+            .systems()
+            .map(|(id, _, membership, _)| match membership {
+                BaseSetMembership::Some(base_set) => (id, Some(base_set)),
+                _ => (id, None),
+            })
+            .collect::<HashMap<_, _>>();
+        let mut systems = graph
+            .systems()
+            .map(|(id, system, ..)| DumpedSystem {
+                id,
+                name: get_short_name(&system.name()),
+                sets: hierarchy
+                    .iter()
+                    .filter(|(parent, child)| *child == id && Some(*parent) != base_sets[&id])
+                    .map(|(parent, _)| set_name(*parent))
+                    .collect(),
+                after: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        systems.sort_by_key(|system| system.id);
+
+        let order = SystemOrder::new(graph);
+        let mut groups = HashMap::<Option<NodeId>, Vec<usize>>::new();
+        for (index, system) in systems.iter().enumerate() {
+            groups.entry(base_sets[&system.id]).or_default().push(index);
+        }
+        for indices in groups.values_mut() {
+            for &index in indices.iter() {
+                let mut after = indices
+                    .iter()
+                    .filter(|&&other| order.runs_before(systems[other].id, systems[index].id))
+                    .map(|&other| systems[other].name.clone())
+                    .collect::<Vec<_>>();
+                after.sort();
+                systems[index].after = after;
+            }
+            // Systems have strictly more predecessors than any system they run after, so this
+            // lists them in an order they can run in.
+            indices.sort_by(|&a, &b| {
+                (systems[a].after.len(), &systems[a].name)
+                    .cmp(&(systems[b].after.len(), &systems[b].name))
+            });
+        }
+        let topsort = graph.dependency().cached_topsort();
+        let mut groups = groups.into_iter().collect::<Vec<_>>();
+        groups.sort_by_key(|(base_set, _)| {
+            base_set.map_or(usize::MAX, |base_set| {
+                topsort
+                    .iter()
+                    .position(|id| *id == base_set)
+                    .unwrap_or(usize::MAX - 1)
+            })
+        });
+        let groups = groups
+            .into_iter()
+            .map(|(base_set, indices)| {
+                let name = base_set.map_or_else(|| "No base set".to_string(), set_name);
+                (name, indices)
+            })
+            .collect();
+
+        let system_name = |id: NodeId| get_short_name(&graph.system_at(id).name());
+        let mut ambiguities = graph
+            .conflicting_systems()
+            .iter()
+            .filter(|(_, _, conflicts)| !conflicts.is_empty())
+            .filter_map(|(a, b, conflicts)| {
+                let base_set = base_sets[a]?;
+                if base_sets[b] != Some(base_set) {
+                    return None;
+                }
+                let flow_set = graph
+                    .set_at(base_set)
+                    .as_any()
+                    .downcast_ref::<FlowSet>()?
+                    .clone();
+                let mut systems = [(system_name(*a), *a), (system_name(*b), *b)];
+                systems.sort();
+                Some(Ambiguity {
+                    systems: systems.clone().map(|(name, _)| name),
+                    flow_set,
+                    components: conflicts
+                        .iter()
+                        .filter_map(|id| components.get_info(*id))
+                        .map(|info| get_short_name(info.name()))
+                        .collect(),
+                    ids: systems.map(|(_, id)| id),
+                })
+            })
+            .collect::<Vec<_>>();
+        ambiguities.sort_by(|a, b| a.systems.cmp(&b.systems));
+
+        Self {
+            name,
+            sets,
+            systems,
+            hierarchy,
+            dependencies,
+            groups,
+            ambiguities,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Systems in the same [`FlowSet`] that can run in either order while accessing the same
+    /// components or resources.
+    pub fn ambiguities(&self) -> &[Ambiguity] {
+        &self.ambiguities
+    }
+
+    /// Graphviz graph of the schedule. Sets are boxes linked to their members with dashed lines,
+    /// orderings are arrows, and ambiguities are red lines labelled with the shared components.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", quote(&self.name));
+        dot.push_str("    rankdir=LR;\n    node [fontname=\"monospace\"];\n");
+        for (id, name) in self.sets.iter() {
+            let _ = writeln!(
+                dot,
+                "    {} [label={}, shape=box, style=rounded];",
+                node_id(*id),
+                quote(name)
+            );
+        }
+        for system in self.systems.iter() {
+            let _ = writeln!(
+                dot,
+                "    {} [label={}];",
+                node_id(system.id),
+                quote(&system.name)
+            );
+        }
+        for (parent, child) in self.hierarchy.iter() {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [style=dashed, arrowhead=none];",
+                node_id(*parent),
+                node_id(*child)
+            );
+        }
+        for (before, after) in self.dependencies.iter() {
+            let _ = writeln!(dot, "    {} -> {};", node_id(*before), node_id(*after));
+        }
+        for ambiguity in self.ambiguities.iter() {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [color=red, dir=none, label={}];",
+                node_id(ambiguity.ids[0]),
+                node_id(ambiguity.ids[1]),
+                quote(&ambiguity.components.join(", "))
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Systems grouped by base set in the order the base sets run, each with its other sets and
+    /// the systems of its base set it always runs after, followed by the ambiguities.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}: {} systems, {} sets, {} orderings, {} ambiguities\n",
+            self.name,
+            self.systems.len(),
+            self.sets.len(),
+            self.dependencies.len(),
+            self.ambiguities.len()
+        );
+        for (name, indices) in self.groups.iter() {
+            let _ = write!(summary, "\n{name}\n");
+            for system in indices.iter().map(|&index| &self.systems[index]) {
+                let _ = write!(summary, "    {}", system.name);
+                if !system.sets.is_empty() {
+                    let _ = write!(summary, " in {}", system.sets.join(", "));
+                }
+                if !system.after.is_empty() {
+                    let _ = write!(summary, " after {}", system.after.join(", "));
+                }
+                summary.push('\n');
+            }
+        }
+        if !self.ambiguities.is_empty() {
+            summary.push_str("\nAmbiguities\n");
+            for ambiguity in self.ambiguities.iter() {
+                let _ = writeln!(
+                    summary,
+                    "    {} and {} in {:?}: {}",
+                    ambiguity.systems[0],
+                    ambiguity.systems[1],
+                    ambiguity.flow_set,
+                    ambiguity.components.join(", ")
+                );
+            }
+        }
+        summary
+    }
+}
+
+fn node_id(id: NodeId) -> String {
+    match id {
+        NodeId::System(index) => format!("system{index}"),
+        NodeId::Set(index) => format!("set{index}"),
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+
+    use crate::flow::{prelude::*, FlowPlugin};
+
+    use super::ScheduleDump;
+
+    #[derive(Component)]
+    struct Position;
+
+    fn spawn() {}
+
+    fn walk(_query: Query<&mut Position>) {}
+
+    fn run(_query: Query<&mut Position>) {}
+
+    fn look(_query: Query<&Position>) {}
+
+    fn draw(_query: Query<&mut Position>) {}
+
+    #[test]
+    fn dump_fixed_update() {
+        let mut app = App::new();
+        app.add_plugin(FlowPlugin)
+            .add_system(
+                walk.in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntityUpdate),
+            )
+            .add_system(
+                run.in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntityUpdate),
+            )
+            .add_system(
+                look.in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntityUpdate)
+                    .after(run)
+                    .after(walk),
+            )
+            .add_system(
+                spawn
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_base_set(FlowSet::EntitySpawn),
+            )
+            .add_system(draw.in_base_set(FlowSet::VisualUpdate));
+        let dump = ScheduleDump::new(&mut app.world, &CoreSchedule::FixedUpdate).unwrap();
+        assert_eq!(dump.name(), "FixedUpdate");
+        let [ambiguity] = dump.ambiguities() else {
+            panic!("{:?}", dump.ambiguities());
+        };
+        assert_eq!(ambiguity.systems, ["run".to_string(), "walk".to_string()]);
+        assert_eq!(ambiguity.flow_set, FlowSet::EntityUpdate);
+        assert_eq!(ambiguity.components, ["Position".to_string()]);
+        let summary = dump.summary();
+        assert!(summary.starts_with("FixedUpdate: 4 systems"), "{summary}");
+        assert!(
+            summary.contains("EntityUpdate\n    run\n    walk\n    look after run, walk\n"),
+            "{summary}"
+        );
+        assert!(summary.contains("    run and walk in EntityUpdate: Position\n"));
+        let entity_update = summary.find("EntityUpdate").unwrap();
+        assert!(summary.find("EntitySpawn").unwrap() > entity_update);
+        let dot = dump.to_dot();
+        assert!(dot.starts_with("digraph \"FixedUpdate\" {"));
+        assert!(dot.contains("[label=\"look\"]"));
+        assert!(dot.contains("[label=\"EntityUpdate\", shape=box, style=rounded]"));
+        assert_eq!(
+            dot.matches("[color=red, dir=none, label=\"Position\"]")
+                .count(),
+            1
+        );
+        assert!(!dot.contains("draw"));
+
+        let main = ScheduleDump::new(&mut app.world, &CoreSchedule::Main).unwrap();
+        assert!(main.summary().contains("VisualUpdate\n    draw\n"));
+
+        // Dumping leaves the schedules runnable.
+        app.world.run_schedule(CoreSchedule::FixedUpdate);
+        app.update();
+    }
+}
@@ -77,11 +77,9 @@ pub fn spine_sync_entities_2<S: Component + Clone + Copy + Debug + PartialEq + E
     for (mut bone_transform, bone) in bone_query.iter_mut() {
         if let Ok(spine) = spine_query.get(bone.spine_entity) {
             if let Some(bone) = bone.handle.get(&spine.skeleton) {
-                bone_transform.translation.x = bone.x();
-                bone_transform.translation.y = bone.y();
-                bone_transform.rotation = bone.rotation().to_radians();
-                bone_transform.scale.x = bone.scale_x();
-                bone_transform.scale.y = bone.scale_y();
+                *bone_transform = Transform2::from_xy(bone.x(), bone.y())
+                    .with_rotation(bone.rotation().to_radians())
+                    .with_scale(Vec2::new(bone.scale_x(), bone.scale_y()));
             }
         }
     }
@@ -116,11 +114,9 @@ pub fn spine_sync_entities_applied_2<
     for (mut bone_transform, bone) in bone_query.iter_mut() {
         if let Ok(spine) = spine_query.get(bone.spine_entity) {
             if let Some(bone) = bone.handle.get(&spine.skeleton) {
-                bone_transform.translation.x = bone.applied_x();
-                bone_transform.translation.y = bone.applied_y();
-                bone_transform.rotation = bone.applied_rotation().to_radians();
-                bone_transform.scale.x = bone.applied_scale_x();
-                bone_transform.scale.y = bone.applied_scale_y();
+                *bone_transform = Transform2::from_xy(bone.applied_x(), bone.applied_y())
+                    .with_rotation(bone.applied_rotation().to_radians())
+                    .with_scale(Vec2::new(bone.applied_scale_x(), bone.applied_scale_y()));
             }
         }
     }
@@ -0,0 +1,384 @@
+use bevy::{ecs::query::QueryEntityError, prelude::*};
+
+use crate::fixed_timestep::CoreFixedSet;
+
+use super::{Transform2, Transform2System, VisualTransform2};
+
+type FollowReadQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Transform2>,
+        Option<&'static Transform>,
+        Option<&'static Parent>,
+    ),
+>;
+
+type FollowWriteQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform2,
+        Option<&'static mut VisualTransform2>,
+    ),
+>;
+
+type FollowTransformQueries<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        FollowReadQuery<'static, 'static>,
+        FollowWriteQuery<'static, 'static>,
+    ),
+>;
+
+pub(crate) struct FollowPlugin;
+
+impl Plugin for FollowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            follow_update_fixed
+                .in_schedule(CoreSchedule::FixedUpdate)
+                .in_set(Transform2System::Follow)
+                .in_base_set(CoreFixedSet::PostUpdate)
+                .before(Transform2System::TransformVisualPropagate),
+        )
+        .add_system(
+            follow_update
+                .in_set(Transform2System::Follow)
+                .in_base_set(CoreSet::PostUpdate)
+                .before(Transform2System::TransformPropagate),
+        );
+    }
+}
+
+/// Moves an entity along with another entity without parenting it, so the follower keeps its own
+/// [`Depth`](super::Depth) and [`Persistent`](crate::Persistent) behavior.
+///
+/// The target's world transform is computed from its [`Transform2`] (or [`Transform`]) and those
+/// of its ancestors, so targets may be spine bones or other children. Smoothed followers are only
+/// moved in the fixed schedule. Followers without smoothing are also snapped to their target every
+/// frame, so they stay attached to targets that move outside of the fixed schedule.
+///
+/// If the target is despawned, the [`Follow`] component is removed and the follower stays where it
+/// is. If only an ancestor of the target is missing, the follower isn't moved but keeps following
+/// once the target's hierarchy is whole again.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Follow {
+    pub target: Entity,
+    /// Offset relative to the target. In [`FollowMode::Full`] the offset is in the target's local
+    /// space, otherwise the translation and rotation are added in world space.
+    pub offset: Transform2,
+    /// Time in seconds for the follower to close ~63% of the distance to its target. Zero snaps
+    /// the follower to the target.
+    pub smoothing: f32,
+    /// Maximum distance the follower may lag behind its target.
+    pub max_distance: Option<f32>,
+    pub mode: FollowMode,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FollowMode {
+    /// Follow translation and rotation.
+    #[default]
+    Full,
+    /// Follow translation only.
+    Position,
+    /// Follow rotation only.
+    Rotation,
+}
+
+impl Follow {
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            offset: Transform2::IDENTITY,
+            smoothing: 0.,
+            max_distance: None,
+            mode: FollowMode::Full,
+        }
+    }
+
+    pub fn with_offset(self, offset: Transform2) -> Self {
+        Self { offset, ..self }
+    }
+
+    pub fn with_smoothing(self, smoothing: f32) -> Self {
+        Self { smoothing, ..self }
+    }
+
+    pub fn with_max_distance(self, max_distance: f32) -> Self {
+        Self {
+            max_distance: Some(max_distance),
+            ..self
+        }
+    }
+
+    pub fn with_mode(self, mode: FollowMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// The world transform the follower is trying to reach.
+    pub fn desired(&self, current: Transform2, target: Transform2) -> Transform2 {
+        match self.mode {
+            FollowMode::Full => {
+                let attached = target.mul_transform(self.offset);
+                Transform2 {
+                    translation: attached.translation,
+                    rotation: attached.rotation,
+                    scale: current.scale,
+                }
+            }
+            FollowMode::Position => Transform2 {
+                translation: target.translation + self.offset.translation,
+                ..current
+            },
+            FollowMode::Rotation => Transform2 {
+                rotation: target.rotation + self.offset.rotation,
+                ..current
+            },
+        }
+    }
+
+    /// Moves the follower's world transform `current` towards `target` over `delta_seconds`.
+    pub fn resolve(
+        &self,
+        current: Transform2,
+        target: Transform2,
+        delta_seconds: f32,
+    ) -> Transform2 {
+        let desired = self.desired(current, target);
+        let mut resolved = if self.smoothing > 0. {
+            current.slerp(desired, 1. - (-delta_seconds / self.smoothing).exp())
+        } else {
+            desired
+        };
+        if let Some(max_distance) = self.max_distance {
+            let lag = resolved.translation - desired.translation;
+            if lag.length() > max_distance {
+                resolved.translation = desired.translation + lag.clamp_length_max(max_distance);
+            }
+        }
+        resolved
+    }
+}
+
+fn follow_update_fixed(
+    transform_queries: FollowTransformQueries,
+    follow_query: Query<(Entity, &Follow)>,
+    commands: Commands,
+    time: Res<FixedTime>,
+) {
+    follow(
+        transform_queries,
+        follow_query,
+        commands,
+        Some(time.period.as_secs_f32()),
+    );
+}
+
+fn follow_update(
+    transform_queries: FollowTransformQueries,
+    follow_query: Query<(Entity, &Follow)>,
+    commands: Commands,
+) {
+    follow(transform_queries, follow_query, commands, None);
+}
+
+/// Resolves all followers. When `delta_seconds` is `None`, only followers without smoothing are
+/// moved.
+fn follow(
+    mut transform_queries: FollowTransformQueries,
+    follow_query: Query<(Entity, &Follow)>,
+    mut commands: Commands,
+    delta_seconds: Option<f32>,
+) {
+    let mut resolved = vec![];
+    {
+        let read_query = transform_queries.p0();
+        for (follow_entity, follow) in follow_query.iter() {
+            if delta_seconds.is_none() && follow.smoothing > 0. {
+                continue;
+            }
+            if !read_query.contains(follow.target) {
+                commands.entity(follow_entity).remove::<Follow>();
+                continue;
+            }
+            let Ok(target) = world_transform2(follow.target, &read_query) else {
+                continue;
+            };
+            let (follow_transform, follow_parent) = match read_query.get(follow_entity) {
+                Ok((Some(follow_transform), _, follow_parent)) => (follow_transform, follow_parent),
+                _ => continue,
+            };
+            let parent = follow_parent
+                .and_then(|parent| world_transform2(parent.get(), &read_query).ok())
+                .unwrap_or_default();
+            let current = parent.mul_transform(*follow_transform);
+            let world = follow.resolve(current, target, delta_seconds.unwrap_or(0.));
+            resolved.push((follow_entity, parent.inverse().mul_transform(world)));
+        }
+    }
+    let mut write_query = transform_queries.p1();
+    for (follow_entity, transform) in resolved.into_iter() {
+        if let Ok((mut follow_transform, follow_visual_transform)) =
+            write_query.get_mut(follow_entity)
+        {
+            *follow_transform = transform;
+            if let Some(mut follow_visual_transform) = follow_visual_transform {
+                follow_visual_transform.0 = transform;
+            }
+        }
+    }
+}
+
+fn world_transform2(
+    entity: Entity,
+    query: &FollowReadQuery,
+) -> Result<Transform2, QueryEntityError> {
+    let (transform2, transform, parent) = query.get(entity)?;
+    let local = transform2
+        .copied()
+        .or_else(|| transform.map(|transform| Transform2::from(*transform)))
+        .unwrap_or_default();
+    if let Some(parent) = parent {
+        Ok(world_transform2(parent.get(), query)?.mul_transform(local))
+    } else {
+        Ok(local)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::prelude::*;
+
+    use crate::transform2::prelude::*;
+
+    use super::{follow_update, follow_update_fixed};
+
+    const EPSILON: f32 = 0.0001;
+
+    fn follow_world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.insert_resource(FixedTime::new_from_secs(1. / 60.));
+        let mut schedule = Schedule::new();
+        schedule.add_systems((follow_update_fixed, follow_update).chain());
+        (world, schedule)
+    }
+
+    #[test]
+    fn follow_despawned_target() {
+        let (mut world, mut schedule) = follow_world();
+        let target = world.spawn(Transform2::from_xy(10., 0.)).id();
+        let follower = world.spawn((Transform2::new(), Follow::new(target))).id();
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(10., 0.)
+        );
+
+        world.get_mut::<Transform2>(target).unwrap().translation = Vec2::new(20., 0.);
+        world.despawn(target);
+        schedule.run(&mut world);
+        assert!(world.get::<Follow>(follower).is_none());
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(10., 0.)
+        );
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(10., 0.)
+        );
+    }
+
+    #[test]
+    fn follow_target_with_missing_ancestor() {
+        let (mut world, mut schedule) = follow_world();
+        let target = world.spawn(Transform2::from_xy(5., 0.)).id();
+        let parent = world
+            .spawn(Transform2::from_xy(10., 0.))
+            .push_children(&[target])
+            .id();
+        let follower = world.spawn((Transform2::new(), Follow::new(target))).id();
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(15., 0.)
+        );
+
+        world.despawn(parent);
+        world.get_mut::<Transform2>(target).unwrap().translation = Vec2::new(0., 5.);
+        schedule.run(&mut world);
+        assert!(world.get::<Follow>(follower).is_some());
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(15., 0.)
+        );
+
+        world.entity_mut(target).remove::<Parent>();
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<Transform2>(follower).unwrap().translation,
+            Vec2::new(0., 5.)
+        );
+    }
+
+    #[test]
+    fn follow_full() {
+        let follow = Follow::new(Entity::PLACEHOLDER).with_offset(Transform2::from_xy(10., 0.));
+        let target = Transform2::from_xy(5., 5.).with_rotation(FRAC_PI_2);
+        let current = Transform2::new().with_scale(Vec2::splat(2.));
+        let resolved = follow.resolve(current, target, 1. / 60.);
+        assert!(resolved
+            .translation
+            .abs_diff_eq(Vec2::new(5., 15.), EPSILON));
+        assert!((resolved.rotation - FRAC_PI_2).abs() < EPSILON);
+        assert_eq!(resolved.scale, Vec2::splat(2.));
+    }
+
+    #[test]
+    fn follow_position_and_rotation() {
+        let target = Transform2::from_xy(5., 5.).with_rotation(FRAC_PI_2);
+        let current = Transform2::from_xy(-1., -1.).with_rotation(0.5);
+        let position = Follow::new(Entity::PLACEHOLDER)
+            .with_offset(Transform2::from_xy(10., 0.))
+            .with_mode(FollowMode::Position)
+            .resolve(current, target, 1. / 60.);
+        assert_eq!(position.translation, Vec2::new(15., 5.));
+        assert_eq!(position.rotation, 0.5);
+        let rotation = Follow::new(Entity::PLACEHOLDER)
+            .with_mode(FollowMode::Rotation)
+            .resolve(current, target, 1. / 60.);
+        assert_eq!(rotation.translation, Vec2::new(-1., -1.));
+        assert_eq!(rotation.rotation, FRAC_PI_2);
+    }
+
+    #[test]
+    fn follow_smoothing() {
+        let follow = Follow::new(Entity::PLACEHOLDER).with_smoothing(0.5);
+        let target = Transform2::from_xy(100., 0.);
+        let resolved = follow.resolve(Transform2::new(), target, 0.5);
+        let expected = 100. * (1. - (-1_f32).exp());
+        assert!((resolved.translation.x - expected).abs() < EPSILON);
+        let mut current = Transform2::new();
+        for _ in 0..1000 {
+            current = follow.resolve(current, target, 1. / 60.);
+        }
+        assert!(current.translation.abs_diff_eq(target.translation, 0.001));
+    }
+
+    #[test]
+    fn follow_max_distance() {
+        let follow = Follow::new(Entity::PLACEHOLDER)
+            .with_smoothing(10.)
+            .with_max_distance(20.);
+        let target = Transform2::from_xy(0., 100.);
+        let resolved = follow.resolve(Transform2::new(), target, 1. / 60.);
+        assert!(resolved
+            .translation
+            .abs_diff_eq(Vec2::new(0., 80.), EPSILON));
+    }
+}
@@ -1,6 +1,9 @@
+mod follow;
 mod transform2;
+
+pub use follow::*;
 pub use transform2::*;
 
 pub mod prelude {
-    pub use super::{Depth, Transform2, VisualTransform2};
+    pub use super::{Depth, Follow, FollowMode, Transform2, VisualTransform2};
 }
@@ -1,35 +1,41 @@
-use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
 use bevy::transform::TransformSystem;
+use bevy::{math::Affine2, prelude::*};
 use lerp::Lerp;
 
 use crate::fixed_timestep::CoreFixedSet;
 
+use super::FollowPlugin;
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum Transform2System {
     TransformPropagate,
     TransformVisualPropagate,
+    Follow,
 }
 
 pub struct Transform2Plugin;
 
 impl Plugin for Transform2Plugin {
     fn build(&self, app: &mut App) {
-        app.add_system(
-            update_transform2
-                .in_set(Transform2System::TransformPropagate)
-                .in_base_set(CoreSet::PostUpdate)
-                .before(TransformSystem::TransformPropagate),
-        )
-        .add_system(
-            update_visual_transform2
-                .in_schedule(CoreSchedule::FixedUpdate)
-                .in_set(Transform2System::TransformVisualPropagate)
-                .in_base_set(CoreFixedSet::PostUpdate),
-        );
+        app.add_plugin(FollowPlugin)
+            .add_system(
+                update_transform2
+                    .in_set(Transform2System::TransformPropagate)
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system(
+                update_visual_transform2
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .in_set(Transform2System::TransformVisualPropagate)
+                    .in_base_set(CoreFixedSet::PostUpdate),
+            );
     }
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct Transform2 {
     pub translation: Vec2,
     pub rotation: f32,
@@ -38,15 +44,17 @@ pub struct Transform2 {
 
 impl Default for Transform2 {
     fn default() -> Self {
-        Self {
-            translation: Vec2::ZERO,
-            rotation: 0.0,
-            scale: Vec2::ONE,
-        }
+        Self::IDENTITY
     }
 }
 
 impl Transform2 {
+    pub const IDENTITY: Self = Self {
+        translation: Vec2::ZERO,
+        rotation: 0.0,
+        scale: Vec2::ONE,
+    };
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -65,6 +73,46 @@ impl Transform2 {
         }
     }
 
+    pub fn from_rotation(rotation: f32) -> Self {
+        Self {
+            rotation,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self {
+            scale,
+            ..Default::default()
+        }
+    }
+
+    /// Extracts translation, rotation and scale from a 2D affine matrix. Shear can't be
+    /// represented and is discarded.
+    pub fn from_matrix(matrix: Mat3) -> Self {
+        Self::from_affine(Affine2::from_mat3(matrix))
+    }
+
+    /// Extracts translation, rotation and scale from an affine transform. Shear can't be
+    /// represented and is discarded.
+    pub fn from_affine(affine: Affine2) -> Self {
+        let x_axis = affine.matrix2.x_axis;
+        let y_axis = affine.matrix2.y_axis;
+        let det = affine.matrix2.determinant();
+        Self {
+            translation: affine.translation,
+            rotation: x_axis.y.atan2(x_axis.x),
+            scale: Vec2::new(x_axis.length(), y_axis.length() * det.signum()),
+        }
+    }
+
+    pub fn with_translation(self, translation: Vec2) -> Self {
+        Self {
+            translation,
+            ..self
+        }
+    }
+
     pub fn with_rotation(self, rotation: f32) -> Self {
         Self { rotation, ..self }
     }
@@ -72,6 +120,144 @@ impl Transform2 {
     pub fn with_scale(self, scale: Vec2) -> Self {
         Self { scale, ..self }
     }
+
+    /// Returns this transform rotated so that the local X axis points at `target`.
+    pub fn looking_at(mut self, target: Vec2) -> Self {
+        self.look_at(target);
+        self
+    }
+
+    pub fn compute_matrix(&self) -> Mat3 {
+        Mat3::from_scale_angle_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn compute_affine(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// Unit vector of the local X axis in world space.
+    pub fn local_x(&self) -> Vec2 {
+        Vec2::from_angle(self.rotation)
+    }
+
+    /// Unit vector of the local Y axis in world space.
+    pub fn local_y(&self) -> Vec2 {
+        self.local_x().perp()
+    }
+
+    pub fn translate_around(&mut self, point: Vec2, angle: f32) {
+        self.translation = point + Vec2::from_angle(angle).rotate(self.translation - point);
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.rotation += angle;
+    }
+
+    /// Rotates around `point`, changing both translation and rotation.
+    pub fn rotate_around(&mut self, point: Vec2, angle: f32) {
+        self.translate_around(point, angle);
+        self.rotate(angle);
+    }
+
+    /// Rotates so that the local X axis points at `target`. Does nothing if `target` is at the
+    /// current translation.
+    pub fn look_at(&mut self, target: Vec2) {
+        let direction = target - self.translation;
+        if direction != Vec2::ZERO {
+            self.rotation = direction.y.atan2(direction.x);
+        }
+    }
+
+    /// Multiplies `self` with `transform` component by component, returning the transform of a
+    /// child with local transform `transform` whose parent is `self`.
+    ///
+    /// Like [`Transform::mul_transform`], this is exact unless the parent has a non-uniform scale
+    /// and the child is rotated, since the result would contain shear.
+    pub fn mul_transform(&self, transform: Transform2) -> Self {
+        Self {
+            translation: self.transform_point(transform.translation),
+            rotation: self.rotation + transform.rotation,
+            scale: self.scale * transform.scale,
+        }
+    }
+
+    /// Returns the transform that undoes this one, so that `t.inverse().mul_transform(t)` is the
+    /// identity.
+    ///
+    /// This is exact when the scale is uniform, since otherwise the inverse contains shear.
+    pub fn inverse(&self) -> Self {
+        let scale = self.scale.recip();
+        let rotation = -self.rotation;
+        Self {
+            translation: -(Vec2::from_angle(rotation).rotate(self.translation) * scale),
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.translation + self.transform_vector(point)
+    }
+
+    /// Transforms a vector, applying scale and rotation but not translation.
+    pub fn transform_vector(&self, vector: Vec2) -> Vec2 {
+        Vec2::from_angle(self.rotation).rotate(vector * self.scale)
+    }
+
+    /// Linearly interpolates every component, including the rotation angle. Rotations are not
+    /// wrapped, so interpolating from `0` to `TAU` spins a full turn. See [`Transform2::slerp`].
+    pub fn lerp(&self, other: Transform2, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, t),
+            rotation: self.rotation.lerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    /// Like [`Transform2::lerp`], but the rotation takes the shortest path between the two
+    /// angles.
+    pub fn slerp(&self, other: Transform2, t: f32) -> Self {
+        let delta = (other.rotation - self.rotation + PI).rem_euclid(TAU) - PI;
+        Self {
+            rotation: self.rotation + delta * t,
+            ..self.lerp(other, t)
+        }
+    }
+}
+
+impl From<Transform> for Transform2 {
+    fn from(transform: Transform) -> Self {
+        let x_axis = transform.rotation * Vec3::X;
+        Self {
+            translation: transform.translation.truncate(),
+            rotation: x_axis.y.atan2(x_axis.x),
+            scale: transform.scale.truncate(),
+        }
+    }
+}
+
+impl From<Transform2> for Mat3 {
+    fn from(transform: Transform2) -> Self {
+        transform.compute_matrix()
+    }
+}
+
+impl From<Mat3> for Transform2 {
+    fn from(matrix: Mat3) -> Self {
+        Self::from_matrix(matrix)
+    }
+}
+
+impl From<Transform2> for Affine2 {
+    fn from(transform: Transform2) -> Self {
+        transform.compute_affine()
+    }
+}
+
+impl From<Affine2> for Transform2 {
+    fn from(affine: Affine2) -> Self {
+        Self::from_affine(affine)
+    }
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq)]
@@ -154,3 +340,212 @@ fn update_transform2_recursive(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use bevy::{math::Affine2, prelude::*};
+
+    use crate::transform2::prelude::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn transforms() -> Vec<Transform2> {
+        let mut transforms = vec![];
+        for translation in [Vec2::ZERO, Vec2::new(3., -2.), Vec2::new(-100., 50.)] {
+            for rotation in [0., 0.3, FRAC_PI_2, -2.5, PI] {
+                for scale in [
+                    Vec2::ONE,
+                    Vec2::splat(2.5),
+                    Vec2::splat(-0.5),
+                    Vec2::new(2., 0.5),
+                    Vec2::new(-1., 3.),
+                ] {
+                    transforms.push(Transform2 {
+                        translation,
+                        rotation,
+                        scale,
+                    });
+                }
+            }
+        }
+        transforms
+    }
+
+    fn to_transform(transform: Transform2) -> Transform {
+        Transform {
+            translation: transform.translation.extend(0.),
+            rotation: Quat::from_rotation_z(transform.rotation),
+            scale: transform.scale.extend(1.),
+        }
+    }
+
+    fn assert_vec2_eq(a: Vec2, b: Vec2) {
+        assert!(a.abs_diff_eq(b, EPSILON), "{a} != {b}");
+    }
+
+    fn assert_transform2_eq(a: Transform2, b: Transform2) {
+        assert!(
+            a.compute_matrix().abs_diff_eq(b.compute_matrix(), EPSILON),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn transform2_compute_matrix() {
+        for transform2 in transforms() {
+            let matrix = transform2.compute_matrix();
+            let transform_matrix = to_transform(transform2).compute_matrix();
+            assert_vec2_eq(
+                matrix.x_axis.truncate(),
+                transform_matrix.x_axis.truncate().truncate(),
+            );
+            assert_vec2_eq(
+                matrix.y_axis.truncate(),
+                transform_matrix.y_axis.truncate().truncate(),
+            );
+            assert_vec2_eq(
+                matrix.z_axis.truncate(),
+                transform_matrix.w_axis.truncate().truncate(),
+            );
+            assert!(Mat3::from(Affine2::from(transform2)).abs_diff_eq(matrix, EPSILON));
+        }
+    }
+
+    #[test]
+    fn transform2_from_matrix() {
+        for transform2 in transforms() {
+            assert_transform2_eq(
+                Transform2::from_matrix(transform2.compute_matrix()),
+                transform2,
+            );
+            assert_transform2_eq(Transform2::from(transform2.compute_affine()), transform2);
+        }
+        let transform2 = Transform2::from_matrix(Mat3::from_scale_angle_translation(
+            Vec2::new(2., 3.),
+            0.5,
+            Vec2::new(1., -1.),
+        ));
+        assert_vec2_eq(transform2.translation, Vec2::new(1., -1.));
+        assert_vec2_eq(transform2.scale, Vec2::new(2., 3.));
+        assert!((transform2.rotation - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn transform2_transform_point() {
+        let points = [Vec2::ZERO, Vec2::X, Vec2::new(-3., 7.)];
+        for transform2 in transforms() {
+            let transform = to_transform(transform2);
+            for point in points {
+                assert_vec2_eq(
+                    transform2.transform_point(point),
+                    transform.transform_point(point.extend(0.)).truncate(),
+                );
+                assert_vec2_eq(
+                    transform2.transform_vector(point),
+                    transform.transform_point(point.extend(0.)).truncate()
+                        - transform.translation.truncate(),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transform2_mul_transform() {
+        for a in transforms() {
+            for b in transforms() {
+                let expected = to_transform(a).mul_transform(to_transform(b));
+                let result = a.mul_transform(b);
+                assert_vec2_eq(result.translation, expected.translation.truncate());
+                assert_vec2_eq(result.scale, expected.scale.truncate());
+                assert_vec2_eq(result.local_x(), (expected.rotation * Vec3::X).truncate());
+                if a.scale.x == a.scale.y {
+                    let matrix = a.compute_matrix() * b.compute_matrix();
+                    assert!(result.compute_matrix().abs_diff_eq(matrix, EPSILON));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transform2_inverse() {
+        for transform2 in transforms() {
+            if transform2.scale.x != transform2.scale.y {
+                continue;
+            }
+            let inverse = transform2.inverse();
+            assert!(inverse
+                .compute_matrix()
+                .abs_diff_eq(transform2.compute_matrix().inverse(), EPSILON));
+            assert_transform2_eq(inverse.mul_transform(transform2), Transform2::IDENTITY);
+            assert_transform2_eq(transform2.mul_transform(inverse), Transform2::IDENTITY);
+            let point = Vec2::new(4., -9.);
+            assert_vec2_eq(
+                inverse.transform_point(transform2.transform_point(point)),
+                point,
+            );
+        }
+    }
+
+    #[test]
+    fn transform2_looking_at() {
+        let transform2 = Transform2::from_xy(1., 1.).looking_at(Vec2::new(1., 5.));
+        assert!((transform2.rotation - FRAC_PI_2).abs() < EPSILON);
+        assert_vec2_eq(transform2.local_x(), Vec2::Y);
+        assert_vec2_eq(transform2.local_y(), Vec2::NEG_X);
+        let unchanged = Transform2::from_rotation(1.).looking_at(Vec2::ZERO);
+        assert_eq!(unchanged.rotation, 1.);
+    }
+
+    #[test]
+    fn transform2_rotate_around() {
+        for transform2 in transforms() {
+            let point = Vec2::new(5., 2.);
+            let mut rotated = transform2;
+            rotated.rotate_around(point, 0.7);
+            let mut transform = to_transform(transform2);
+            transform.rotate_around(point.extend(0.), Quat::from_rotation_z(0.7));
+            assert_vec2_eq(rotated.translation, transform.translation.truncate());
+            assert_vec2_eq(rotated.local_x(), (transform.rotation * Vec3::X).truncate());
+        }
+    }
+
+    #[test]
+    fn transform2_lerp() {
+        let a = Transform2::from_xy(0., 10.)
+            .with_rotation(0.5)
+            .with_scale(Vec2::ONE);
+        let b = Transform2::from_xy(10., 0.)
+            .with_rotation(1.5)
+            .with_scale(Vec2::splat(3.));
+        let halfway = a.lerp(b, 0.5);
+        assert_vec2_eq(halfway.translation, Vec2::new(5., 5.));
+        assert!((halfway.rotation - 1.).abs() < EPSILON);
+        assert_vec2_eq(halfway.scale, Vec2::splat(2.));
+        assert_eq!(a.lerp(b, 0.), a);
+        assert_eq!(a.lerp(b, 1.), b);
+    }
+
+    #[test]
+    fn transform2_slerp() {
+        let a = Transform2::from_rotation(PI - 0.25);
+        let b = Transform2::from_rotation(-PI + 0.25);
+        let halfway = a.slerp(b, 0.5);
+        assert_vec2_eq(halfway.local_x(), Vec2::NEG_X);
+        assert!((a.lerp(b, 0.5).rotation).abs() < EPSILON);
+        let end = a.slerp(b, 1.);
+        assert_vec2_eq(end.local_x(), b.local_x());
+    }
+
+    #[test]
+    fn transform2_from_transform() {
+        let transform = Transform::from_xyz(3., 4., 5.)
+            .with_rotation(Quat::from_rotation_z(1.2))
+            .with_scale(Vec3::new(2., 3., 1.));
+        let transform2 = Transform2::from(transform);
+        assert_eq!(transform2.translation, Vec2::new(3., 4.));
+        assert!((transform2.rotation - 1.2).abs() < EPSILON);
+        assert_eq!(transform2.scale, Vec2::new(2., 3.));
+    }
+}